@@ -1,8 +1,12 @@
-use crate::utils::db_manager::{create_installation, delete_installation_by_id, get_install_info_by_id, get_installs, get_installs_by_manifest_id, get_manifest_info_by_filename, get_manifest_info_by_id, get_settings, update_install_disable_system_idle_by_id, update_install_env_vars_by_id, update_install_fps_value_by_id, update_install_game_background_by_id, update_install_game_location_by_id, update_install_graphics_api_by_id, update_install_ignore_updates_by_id, update_install_launch_args_by_id, update_install_launch_cmd_by_id, update_install_mangohud_config_location_by_id, update_install_pre_launch_cmd_by_id, update_install_prefix_location_by_id, update_install_shortcut_location_by_id, update_install_show_drpc_by_id, update_install_skip_hash_check_by_id, update_install_use_fps_unlock_by_id, update_install_use_gamemode_by_id, update_install_use_jadeite_by_id, update_install_use_mangohud_by_id, update_install_use_xxmi_by_id, update_install_xxmi_config_by_id, update_installs_order};
-use crate::utils::game_launch_manager::launch;
+use crate::utils::db_manager::{create_installation, delete_installation_by_id, get_install_info_by_id, get_installs, get_installs_by_manifest_id, get_integrity_report_by_install_id, delete_integrity_report_by_install_id, delete_play_sessions_by_install_id, create_launch_profile as insert_launch_profile, delete_launch_profiles_by_install_id, get_launch_profile_by_id, get_launch_profiles_by_install_id, get_manifest_info_by_filename, get_manifest_info_by_id, get_settings, update_install_disable_system_idle_by_id, update_install_env_list_by_id, update_install_fps_value_by_id, update_install_game_background_by_id, update_install_game_location_by_id, update_install_graphics_api_by_id, update_install_ignore_updates_by_id, update_install_launch_args_by_id, update_install_launch_cmd_by_id, update_install_launch_hooks_by_id, update_install_mangohud_config_location_by_id, update_install_proton_log_by_id, update_install_runner_auto_upgrade_by_id, update_install_wine_debug_by_id, update_install_prefix_location_by_id, update_install_shortcut_location_by_id, update_install_show_drpc_by_id, update_install_skip_hash_check_by_id, update_install_use_fps_unlock_by_id, update_install_use_gamemode_by_id, update_install_use_jadeite_by_id, update_install_use_mangohud_by_id, update_install_use_xxmi_by_id, update_install_xxmi_config_by_id, update_install_total_playtime_by_id, update_install_last_played_by_id, update_installs_order};
+use crate::utils::env_vars::{install_env, parse_legacy_env_vars, validate_env_vars, EnvConflict, EnvVarError};
+use crate::utils::game_launch_manager::{launch, preview_launch as resolve_launch_preview, LaunchPreview};
+use crate::utils::game_sessions::{get_session, list_sessions, stop_game as stop_game_session, GameSession, StopGameReport};
 use crate::utils::repo_manager::get_manifest;
-use crate::utils::shortcuts::remove_desktop_shortcut;
-use crate::utils::{models::{AddInstallRsp, DownloadSizesRsp, ResumeStatesRsp, GameVersion, LauncherInstall}, apply_xxmi_tweaks, copy_dir_all, generate_cuid, get_mi_path_from_game, show_dialog_with_callback, extract_authkey_from_content};
+use crate::utils::session_logs::{delete_session_logs, latest_session_log, tail_session_log, SessionLogTail};
+use crate::utils::shortcuts::{remove_desktop_shortcut, sync_install_profile_shortcuts};
+use crate::utils::launch_hooks::pre_launch_hook;
+use crate::utils::{apply_launch_profile, models::{AddInstallRsp, DownloadSizesRsp, EnvVar, ResumeStatesRsp, GameVersion, InstallBundle, LaunchHook, LaunchProfile, LauncherInstall, LauncherIntegrityReport}, apply_xxmi_tweaks, copy_dir_all, generate_cuid, get_mi_path_from_game, show_dialog_with_callback, extract_authkey_from_content};
 use fischl::utils::is_process_running;
 use fischl::utils::prettify_bytes;
 use std::fs;
@@ -15,9 +19,10 @@ use std::sync::atomic::Ordering;
 use tauri_plugin_clipboard_manager::ClipboardExt;
 
 use crate::DownloadState;
-use crate::downloading::ExtrasDownloadPayload;
+use crate::downloading::{DownloadGamePayload, ExtrasDownloadPayload};
 use crate::downloading::queue::QueueJobKind;
 use crate::downloading::QueueJobPayload;
+use crate::downloading::verify::delete_file_manifest;
 use crate::utils::models::XXMISettings;
 #[cfg(target_os = "linux")]
 use std::collections::HashMap;
@@ -26,7 +31,7 @@ use crate::downloading::RunnerDownloadPayload;
 #[cfg(target_os = "linux")]
 use crate::utils::db_manager::{create_installed_runner, get_installed_runner_info_by_version, update_install_shortcut_is_steam_by_id, update_installed_runner_is_installed_by_version};
 #[cfg(target_os = "linux")]
-use crate::utils::{is_flatpak, run_async_command, runner_from_runner_version, repo_manager::get_compatibility, shortcuts::{add_desktop_shortcut, add_steam_shortcut, remove_steam_shortcut}};
+use crate::utils::{is_flatpak, run_async_command, runner_from_runner_version, local_runners::runner_compat, repo_manager::get_compatibility, shortcuts::{add_desktop_shortcut, add_steam_shortcut, remove_steam_shortcut}};
 #[cfg(target_os = "linux")]
 use std::time::{SystemTime, UNIX_EPOCH};
 #[cfg(target_os = "linux")]
@@ -52,6 +57,11 @@ pub fn get_install_by_id<R: Runtime>(app: AppHandle<R>, id: String) -> Option<La
     get_install_info_by_id(&app, id)
 }
 
+#[tauri::command]
+pub fn get_install_integrity_report<R: Runtime>(app: AppHandle<R>, id: String) -> Option<LauncherIntegrityReport> {
+    get_integrity_report_by_install_id(&app, id)
+}
+
 #[allow(unused_mut, unused_variables)]
 #[tauri::command]
 pub fn add_install<R: Runtime>(app: AppHandle<R>, manifest_id: String, version: String, audio_lang: String, name: String, mut directory: String, mut runner_path: String, mut dxvk_path: String, mut runner_version: String, dxvk_version: String, game_icon: String, game_background: String, mut ignore_updates: bool, skip_hash_check: bool, mut use_jadeite: bool, use_xxmi: bool, use_fps_unlock: bool, env_vars: String, pre_launch_command: String, launch_command: String, fps_value: String, mut runner_prefix: String, launch_args: String, skip_game_dl: bool, region_code: String) -> Option<AddInstallRsp> {
@@ -141,7 +151,7 @@ pub fn add_install<R: Runtime>(app: AppHandle<R>, manifest_id: String, version:
             // Download runner via queue system (shows in downloads UI)
             let rm = get_compatibility(&app, &runner_from_runner_version(&app, runv.as_str().to_string()).unwrap_or_default());
             if let Some(rm) = rm {
-                let rv = rm.versions.into_iter().filter(|v| v.version.as_str() == runv.as_str()).collect::<Vec<_>>();
+                let rv = rm.versions.into_iter().filter(|v| v.version.as_str() == runv.as_str() && (!v.url.is_empty() || v.urls.is_some())).collect::<Vec<_>>();
                 if let Some(runnerp) = rv.get(0) {
                     let rp = Path::new(runpp.as_str()).to_path_buf();
 
@@ -208,8 +218,16 @@ pub fn add_install<R: Runtime>(app: AppHandle<R>, manifest_id: String, version:
             let downloading_marker = install_location.join("downloading");
             if !downloading_marker.exists() { let _ = fs::create_dir_all(&downloading_marker); }
         }
-        let default_graphics_api = gm.extra.graphics_api_options.default.clone();
-        create_installation(&app, cuid.clone(), dbm.id, version, audio_lang, g.metadata.versioned_name.clone(), directory, runner_path, dxvk_path, runner_version, dxvk_version, g.assets.game_icon.clone(), gbg.clone(), ignore_updates, skip_hash_check, use_jadeite, use_xxmi, use_fps_unlock, env_vars, pre_launch_command, launch_command, fps_value, runner_prefix, launch_args, false, false, gs.default_mangohud_config_path.clone(), region_code, steam_import, default_graphics_api).unwrap();
+        // Launcher wide defaults fill in whatever the new install dialog left empty
+        let defaults = gs.launch_defaults.0.clone();
+        let default_graphics_api = if !defaults.graphics_api.is_empty() && gm.extra.switches.graphics_api && gm.extra.graphics_api_options.options.iter().any(|o| o.value == defaults.graphics_api) { defaults.graphics_api.clone() } else { gm.extra.graphics_api_options.default.clone() };
+        let launch_args = if launch_args.trim().is_empty() { defaults.launch_args.clone() } else { launch_args };
+        let use_default_env = env_vars.trim().is_empty() && !defaults.env_list.is_empty();
+        create_installation(&app, cuid.clone(), dbm.id, version, audio_lang, g.metadata.versioned_name.clone(), directory, runner_path, dxvk_path, runner_version, dxvk_version, g.assets.game_icon.clone(), gbg.clone(), ignore_updates, skip_hash_check, use_jadeite, use_xxmi, use_fps_unlock, env_vars, String::new(), launch_command, fps_value, runner_prefix, launch_args, defaults.use_gamemode, defaults.use_mangohud, gs.default_mangohud_config_path.clone(), region_code, steam_import, default_graphics_api).unwrap();
+        if use_default_env { update_install_env_list_by_id(&app, cuid.clone(), Json(defaults.env_list)); }
+        if !pre_launch_command.trim().is_empty() { update_install_launch_hooks_by_id(&app, cuid.clone(), Json(vec![pre_launch_hook(pre_launch_command)])); }
+        if defaults.show_discord_rpc { update_install_show_drpc_by_id(&app, cuid.clone(), true); }
+        if defaults.disable_system_idle { update_install_disable_system_idle_by_id(&app, cuid.clone(), true); }
         log::info!("Created installation {} (\"{}\")", cuid, name);
         Some(AddInstallRsp {
             success: true,
@@ -221,6 +239,94 @@ pub fn add_install<R: Runtime>(app: AppHandle<R>, manifest_id: String, version:
     }
 }
 
+const INSTALL_BUNDLE_VERSION: u32 = 1;
+
+#[tauri::command]
+pub fn export_install_bundle<R: Runtime>(app: AppHandle<R>, id: String, path: String, include_game_directory: bool) -> Option<bool> {
+    let install = get_install_info_by_id(&app, id)?;
+    let manifest = get_manifest_info_by_id(&app, install.manifest_id.clone())?;
+    let bundle = InstallBundle {
+        bundle_version: INSTALL_BUNDLE_VERSION,
+        exported_at: std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs() as i64,
+        manifest: manifest.filename.trim_end_matches(".json").to_string(),
+        game_directory: if include_game_directory { Some(install.directory.clone()) } else { None },
+        profiles: get_launch_profiles_by_install_id(&app, install.id.clone()),
+        install,
+    };
+    match fs::write(&path, serde_json::to_string_pretty(&bundle).unwrap()) {
+        Ok(_) => { log::info!("Exported install {} to {}", bundle.install.id, path); Some(true) }
+        Err(e) => { log::warn!("Failed to export install {} to {}: {}", bundle.install.id, path, e); Some(false) }
+    }
+}
+
+/// Creates a new install from a bundle. With a game directory (passed in or stored in the bundle) that exists on this machine the folder gets adopted as is,
+/// otherwise the install is created empty and its download gets queued right away.
+#[tauri::command]
+pub fn import_install_bundle<R: Runtime>(app: AppHandle<R>, path: String, game_directory: Option<String>) -> Option<AddInstallRsp> {
+    let bundle = match fs::read_to_string(&path).ok().and_then(|c| serde_json::from_str::<InstallBundle>(&c).ok()) {
+        Some(b) if b.bundle_version <= INSTALL_BUNDLE_VERSION => b,
+        _ => { show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.install_bundle_invalid", None, None, None); return None; }
+    };
+    let Some(gm) = get_manifest(&app, format!("{}.json", bundle.manifest)) else {
+        show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.install_bundle_missing_manifest", None, None, Some(std::collections::HashMap::from([("manifest", bundle.manifest.as_str())])));
+        return None;
+    };
+    let gs = get_settings(&app)?;
+    let i = bundle.install;
+    let adopt = game_directory.or(bundle.game_directory).filter(|d| Path::new(d).exists());
+    let has_version = gm.game_versions.iter().any(|v| v.metadata.version == i.version);
+    if adopt.is_some() && !has_version {
+        show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.install_bundle_unknown_version", None, None, Some(std::collections::HashMap::from([("install_name", i.name.as_str()), ("version", i.version.as_str())])));
+        return None;
+    }
+    let version = if has_version { i.version.clone() } else { gm.latest_version.clone() };
+    let skip_dl = adopt.is_some();
+    let directory = adopt.unwrap_or(gs.default_game_path.clone());
+    // Paths in the bundle point into the other machine, add_install wants the folders of this one
+    let runner_path = Path::new(&gs.default_runner_path).join(&i.runner_version).to_str().unwrap().to_string();
+    let dxvk_path = Path::new(&gs.default_dxvk_path).join(&i.dxvk_version).to_str().unwrap().to_string();
+
+    log::info!("Importing install \"{}\" from {}{}", i.name, path, if skip_dl { format!(", adopting {}", directory) } else { String::new() });
+    let rsp = add_install(app.clone(), bundle.manifest.clone(), version, i.audio_langs.clone(), i.name.clone(), directory, runner_path, dxvk_path, i.runner_version.clone(), i.dxvk_version.clone(), i.game_icon.clone(), i.game_background.clone(), i.ignore_updates, i.skip_hash_check, i.use_jadeite, i.use_xxmi, i.use_fps_unlock, i.env_vars.clone(), String::new(), i.launch_command.clone(), i.fps_value.clone(), gs.default_runner_prefix_path.clone(), i.launch_args.clone(), skip_dl, i.region_code.clone())?;
+    if !rsp.success { return Some(rsp); }
+
+    // add_install only takes what the new install dialog offers, carry over the rest. Launch defaults of this launcher must not leak into the import either
+    let id = rsp.install_id.clone();
+    let env_list = install_env(&i);
+    update_install_launch_args_by_id(&app, id.clone(), i.launch_args.clone());
+    update_install_env_list_by_id(&app, id.clone(), Json(env_list));
+    update_install_xxmi_config_by_id(&app, id.clone(), i.xxmi_config);
+    update_install_use_gamemode_by_id(&app, id.clone(), i.use_gamemode);
+    update_install_use_mangohud_by_id(&app, id.clone(), i.use_mangohud);
+    if !i.mangohud_config_path.is_empty() { update_install_mangohud_config_location_by_id(&app, id.clone(), i.mangohud_config_path); }
+    update_install_show_drpc_by_id(&app, id.clone(), i.show_discord_rpc);
+    update_install_disable_system_idle_by_id(&app, id.clone(), i.disable_system_idle);
+    if !i.graphics_api.is_empty() { update_install_graphics_api_by_id(&app, id.clone(), i.graphics_api); }
+    // Bundles exported before launch hooks existed still carry the old pre launch command
+    let mut hooks = i.launch_hooks.0;
+    if !i.pre_launch_command.trim().is_empty() { hooks.insert(0, pre_launch_hook(i.pre_launch_command)); }
+    if !hooks.is_empty() { update_install_launch_hooks_by_id(&app, id.clone(), Json(hooks)); }
+    update_install_proton_log_by_id(&app, id.clone(), i.proton_log);
+    if !i.wine_debug.is_empty() { update_install_wine_debug_by_id(&app, id.clone(), i.wine_debug); }
+    update_install_runner_auto_upgrade_by_id(&app, id.clone(), i.runner_auto_upgrade);
+    update_install_total_playtime_by_id(&app, id.clone(), i.total_playtime.to_string());
+    if !i.last_played_time.is_empty() { update_install_last_played_by_id(&app, id.clone(), i.last_played_time); }
+    for p in bundle.profiles {
+        insert_launch_profile(&app, LaunchProfile { id: generate_cuid(), install_id: id.clone(), ..p });
+    }
+
+    if !skip_dl {
+        let payload = DownloadGamePayload { install: id.clone(), biz: String::new(), lang: i.audio_langs.clone(), region: i.region_code.clone(), is_latest: None };
+        let q = app.state::<DownloadState>().queue.lock().unwrap().clone();
+        match q {
+            Some(queue) => queue.enqueue(QueueJobKind::GameDownload, QueueJobPayload::Game(payload)),
+            None => log::warn!("Download queue is not running, imported install {} has to be downloaded manually", id),
+        }
+    }
+    app.emit("install_imported", id).unwrap();
+    Some(rsp)
+}
+
 #[tauri::command]
 pub async fn remove_install<R: Runtime>(app: AppHandle<R>, id: String, wipe_prefix: bool, keep_game_data: bool) -> Option<bool> {
     if id.is_empty() {
@@ -258,6 +364,14 @@ pub async fn remove_install<R: Runtime>(app: AppHandle<R>, id: String, wipe_pref
                 } else { show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.install_remove_dir_failed_generic", None, None, None); }
             }
             delete_installation_by_id(&app, id.clone()).unwrap();
+            delete_integrity_report_by_install_id(&app, id.clone());
+            delete_play_sessions_by_install_id(&app, id.clone());
+            sync_install_profile_shortcuts(&app, &id, &i.name, None);
+            delete_launch_profiles_by_install_id(&app, id.clone());
+            #[cfg(target_os = "linux")]
+            crate::utils::prefix_snapshots::delete_prefix_snapshots(&app, &id);
+            delete_file_manifest(&app, &id);
+            delete_session_logs(&app, &id);
             Some(true)
         } else {
             None
@@ -559,28 +673,70 @@ pub fn update_install_disable_system_idle<R: Runtime>(app: AppHandle<R>, id: Str
 
 #[tauri::command]
 pub fn update_install_env_vars<R: Runtime>(app: AppHandle<R>, id: String, env_vars: String) -> Option<bool> {
-    let install = get_install_info_by_id(&app, id);
+    update_install_env_list(app, id, parse_legacy_env_vars(&env_vars))
+}
 
-    if install.is_some() {
-        let m = install.unwrap();
-        update_install_env_vars_by_id(&app, m.id, env_vars);
-        Some(true)
-    } else {
-        None
-    }
+/// Saves the env var editor, nothing is stored while an entry fails validation.
+#[tauri::command]
+pub fn update_install_env_list<R: Runtime>(app: AppHandle<R>, id: String, vars: Vec<EnvVar>) -> Option<bool> {
+    let install = get_install_info_by_id(&app, id)?;
+    let vars = vars.into_iter().map(|v| EnvVar { key: v.key.trim().to_string(), ..v }).collect::<Vec<EnvVar>>();
+    let errors = validate_env_vars(&vars);
+    if !errors.is_empty() { log::warn!("Refusing to save environment variables of {}: {:?}", install.name, errors); return Some(false); }
+    update_install_env_list_by_id(&app, install.id, Json(vars));
+    Some(true)
+}
+
+#[derive(serde::Serialize)]
+pub struct EnvVarReport {
+    pub errors: Vec<EnvVarError>,
+    /// Variables the launcher sets as well and what the game ends up with, resolved like a real launch would.
+    pub conflicts: Vec<EnvConflict>,
 }
 
 #[tauri::command]
-pub fn update_install_pre_launch_cmd<R: Runtime>(app: AppHandle<R>, id: String, cmd: String) -> Option<bool> {
-    let install = get_install_info_by_id(&app, id);
+pub fn validate_install_env_vars<R: Runtime>(app: AppHandle<R>, id: String, vars: Vec<EnvVar>) -> Option<EnvVarReport> {
+    let mut install = get_install_info_by_id(&app, id)?;
+    let errors = validate_env_vars(&vars);
+    install.env_list = Json(vars);
+    let conflicts = match get_manifest_info_by_id(&app, install.manifest_id.clone()).and_then(|m| get_manifest(&app, m.filename)) {
+        Some(gm) => get_settings(&app).and_then(|gs| resolve_launch_preview(&app, install, gm, gs).ok().flatten()).map(|p| p.conflicts).unwrap_or_default(),
+        None => Vec::new(),
+    };
+    Some(EnvVarReport { errors, conflicts })
+}
 
-    if install.is_some() {
-        let m = install.unwrap();
-        update_install_pre_launch_cmd_by_id(&app, m.id, cmd);
-        Some(true)
-    } else {
-        None
-    }
+#[tauri::command]
+pub fn update_install_launch_hooks<R: Runtime>(app: AppHandle<R>, id: String, hooks: Vec<LaunchHook>) -> Option<bool> {
+    let install = get_install_info_by_id(&app, id)?;
+    if hooks.iter().any(|h| h.command.trim().is_empty()) { log::warn!("Refusing to save launch hooks for {}: empty hook command", install.name); return Some(false); }
+    update_install_launch_hooks_by_id(&app, install.id, Json(hooks));
+    Some(true)
+}
+
+#[tauri::command]
+pub fn update_install_proton_log<R: Runtime>(app: AppHandle<R>, id: String, enabled: bool) -> Option<bool> {
+    let install = get_install_info_by_id(&app, id)?;
+    update_install_proton_log_by_id(&app, install.id, enabled);
+    Some(true)
+}
+
+#[tauri::command]
+pub fn update_install_wine_debug<R: Runtime>(app: AppHandle<R>, id: String, channels: String) -> Option<bool> {
+    let install = get_install_info_by_id(&app, id)?;
+    let channels = channels.trim().to_string();
+    // WINEDEBUG is a comma separated list like "+loaddll,-fixme" or "warn+heap", anything else would end up in the environment as is
+    if !channels.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '_' | ',')) { log::warn!("Refusing to save WINEDEBUG channels \"{}\" for {}", channels, install.name); return Some(false); }
+    update_install_wine_debug_by_id(&app, install.id, channels);
+    Some(true)
+}
+
+/// Last `lines` lines (200 by default) of the newest session log of the install.
+#[tauri::command]
+pub fn get_session_log_tail<R: Runtime>(app: AppHandle<R>, id: String, lines: Option<usize>) -> Option<SessionLogTail> {
+    let install = get_install_info_by_id(&app, id)?;
+    let path = latest_session_log(&app, &install.id)?;
+    tail_session_log(&path, lines.unwrap_or(200))
 }
 
 #[tauri::command]
@@ -665,7 +821,7 @@ pub fn update_install_runner_version<R: Runtime>(app: AppHandle<R>, id: String,
             // Download runner via queue system (shows in downloads UI)
             let rm = get_compatibility(&app, &runner_from_runner_version(&app, version.clone()).unwrap_or_default());
             if let Some(rm) = rm {
-                let rv = rm.versions.into_iter().filter(|v| v.version.as_str() == version.as_str()).collect::<Vec<_>>();
+                let rv = rm.versions.into_iter().filter(|v| v.version.as_str() == version.as_str() && (!v.url.is_empty() || v.urls.is_some())).collect::<Vec<_>>();
                 if let Some(runnerp) = rv.get(0) {
                     let rp = Path::new(rpn.as_str()).to_path_buf();
 
@@ -705,6 +861,7 @@ pub fn update_install_runner_version<R: Runtime>(app: AppHandle<R>, id: String,
                 }
             }
         } else {}
+        if version != m.runner_version { crate::utils::prefix_snapshots::snapshot_prefix_in_background(&app, m.clone(), "runner_switch"); }
         log::info!("Updated runner for installation {} to {}", m.id, version);
         crate::utils::db_manager::update_install_runner_version_by_id(&app, m.id.clone(), version);
         crate::utils::db_manager::update_install_runner_location_by_id(&app, m.id, rpn);
@@ -720,6 +877,30 @@ pub fn update_install_runner_version<R: Runtime>(_app: AppHandle<R>, _id: String
     None
 }
 
+/// Opting in queues the newest runner of the family right away, the install switches once it is downloaded.
+#[tauri::command]
+pub fn update_install_runner_auto_upgrade<R: Runtime>(app: AppHandle<R>, id: String, enabled: bool) -> Option<bool> {
+    let install = get_install_info_by_id(&app, id)?;
+    update_install_runner_auto_upgrade_by_id(&app, install.id, enabled);
+    #[cfg(target_os = "linux")]
+    if enabled { crate::utils::runner_upgrades::check_runner_upgrades(&app); }
+    Some(true)
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn rollback_install_runner_version<R: Runtime>(app: AppHandle<R>, id: String) -> Option<bool> {
+    let install = get_install_info_by_id(&app, id)?;
+    let previous = crate::utils::runner_upgrades::rollback_install_runner(&app, &install)?;
+    update_install_runner_version(app, install.id, previous)
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn rollback_install_runner_version<R: Runtime>(_app: AppHandle<R>, _id: String) -> Option<bool> {
+    None
+}
+
 #[cfg(target_os = "linux")]
 #[tauri::command]
 pub fn update_install_dxvk_version<R: Runtime>(app: AppHandle<R>, id: String, version: String) -> Option<bool> {
@@ -740,7 +921,7 @@ pub fn update_install_dxvk_version<R: Runtime>(app: AppHandle<R>, id: String, ve
 
         if fs::read_dir(pn.as_str()).unwrap().next().is_none() {
             std::thread::spawn(move || {
-                let rm = get_compatibility(archandle.as_ref(), &runner_from_runner_version(archandle.as_ref(), runv.as_str().to_string()).unwrap_or_default()).unwrap();
+                let (_, is_proton) = runner_compat(archandle.as_ref(), runv.as_str()).unwrap();
                 //let dm = get_compatibility(archandle.as_ref(), &runner_from_runner_version(dxvkv.as_str().to_string()).unwrap()).unwrap();
                 //let dv = dm.versions.into_iter().filter(|v| v.version.as_str() == dxvkv.as_str()).collect::<Vec<_>>();
                 //let dxp = dv.get(0).unwrap().to_owned();
@@ -748,7 +929,6 @@ pub fn update_install_dxvk_version<R: Runtime>(app: AppHandle<R>, id: String, ve
                 //let rp = Path::new(runp.as_str()).to_path_buf();
 
                 let mut dlpayload = HashMap::new();
-                let is_proton = rm.display_name.to_ascii_lowercase().contains("proton") && !rm.display_name.to_ascii_lowercase().contains("wine");
 
                 if is_proton {} else {
                     dlpayload.insert("name", runv.to_string());
@@ -790,12 +970,26 @@ pub fn update_install_dxvk_version<R: Runtime>(_app: AppHandle<R>, _id: String,
 }
 
 #[tauri::command]
-pub fn game_launch<R: Runtime>(app: AppHandle<R>, id: String) -> Option<bool> {
+pub fn game_launch<R: Runtime>(app: AppHandle<R>, id: String, profile_id: Option<String>) -> Option<bool> {
     let install = get_install_info_by_id(&app, id.clone());
     let global_settings = get_settings(&app).unwrap();
 
     if install.is_some() {
-        let m = install.unwrap();
+        let mut m = install.unwrap();
+        if get_session(&app, &m.id).is_some() {
+            log::warn!("Refusing to launch {} ({}) while it still has a session", m.name, m.id);
+            show_dialog_with_callback(&app, "warning", "TwintailLauncher", "dialogs.game_already_running", None, None, Some(std::collections::HashMap::from([("install_name", m.name.as_str())])));
+            return None;
+        }
+        if let Some(pid) = profile_id {
+            let Some(profile) = get_launch_profile_by_id(&app, pid.clone()).filter(|p| p.install_id == m.id) else {
+                log::warn!("Failed to find launch profile {} of install {}", pid, m.id);
+                show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_profile_not_found", None, None, Some(std::collections::HashMap::from([("install_name", m.name.as_str())])));
+                return None;
+            };
+            log::info!("Using launch profile \"{}\" for \"{}\"", profile.name, m.name);
+            apply_launch_profile(&mut m, &profile);
+        }
         let gmm = get_manifest_info_by_id(&app, m.clone().manifest_id).unwrap();
         let gm = get_manifest(&app, gmm.filename).unwrap();
 
@@ -810,6 +1004,26 @@ pub fn game_launch<R: Runtime>(app: AppHandle<R>, id: String) -> Option<bool> {
     }
 }
 
+/// Returns the argv, env and cwd `game_launch` would use for the install without spawning anything.
+#[tauri::command]
+pub fn preview_launch<R: Runtime>(app: AppHandle<R>, id: String, profile_id: Option<String>) -> Option<LaunchPreview> {
+    let install = get_install_info_by_id(&app, id.clone());
+    let global_settings = get_settings(&app)?;
+
+    if let Some(mut m) = install {
+        if let Some(pid) = profile_id { apply_launch_profile(&mut m, &get_launch_profile_by_id(&app, pid).filter(|p| p.install_id == m.id)?); }
+        let gmm = get_manifest_info_by_id(&app, m.clone().manifest_id)?;
+        let gm = get_manifest(&app, gmm.filename)?;
+        match resolve_launch_preview(&app, m, gm, global_settings) {
+            Ok(preview) => preview,
+            Err(e) => { log::warn!("Failed to preview launch of install {}: {}", id, e); None }
+        }
+    } else {
+        log::warn!("Failed to find game installation with id {}", id);
+        None
+    }
+}
+
 #[tauri::command]
 pub fn check_game_running<R: Runtime>(app: AppHandle<R>, id: String) -> Option<String> {
     let install = get_install_info_by_id(&app, id.clone());
@@ -819,8 +1033,11 @@ pub fn check_game_running<R: Runtime>(app: AppHandle<R>, id: String) -> Option<S
         if let Some(manifest_info) = gmm {
             let gm = get_manifest(&app, manifest_info.filename);
             if let Some(manifest) = gm {
+                // Games launched from here are bound to their install, only fall back to a name lookup for games started elsewhere
+                if let Some(session) = get_session(&app, &id) { return Some(if session.status == "running" { "running" } else { "preparing" }.to_string()); }
                 if is_process_running("winetricks") || is_process_running("winetr") { return Some("preparing".to_string()); }
                 let exe_name = manifest.paths.exe_filename.split('/').last().unwrap_or("");
+                if list_sessions(&app).iter().any(|s| s.exe_name == exe_name) { return Some("idle".to_string()); }
                 let exe_stem = exe_name.split('.').next().unwrap_or(exe_name);
                 let exe_check = if exe_stem.len() > 15 { &exe_stem[..15] } else { exe_name };
                 if is_process_running(exe_check) { return Some("running".to_string()); }
@@ -831,6 +1048,18 @@ pub fn check_game_running<R: Runtime>(app: AppHandle<R>, id: String) -> Option<S
     None
 }
 
+#[tauri::command]
+pub fn list_game_sessions<R: Runtime>(app: AppHandle<R>) -> Vec<GameSession> {
+    list_sessions(&app)
+}
+
+/// Terminates the game, its helpers and the prefix's wineserver, graceful first and `kill -9` last. None when the install has no running session.
+#[tauri::command]
+pub async fn stop_game<R: Runtime>(app: AppHandle<R>, install_id: String) -> Option<StopGameReport> {
+    // Escalation waits on the processes, keep it off the main thread
+    tauri::async_runtime::spawn_blocking(move || stop_game_session(&app, &install_id)).await.ok().flatten()
+}
+
 #[tauri::command]
 pub fn get_download_sizes<R: Runtime>(app: AppHandle<R>, biz: String, version: String, lang: String, path: String, region: Option<String>) -> Option<DownloadSizesRsp> {
     let manifest = get_manifest(&app, biz + ".json");
@@ -920,14 +1149,18 @@ pub fn get_resume_states<R: Runtime>(app: AppHandle<R>, install: String) -> Opti
 }
 
 #[tauri::command]
-pub fn add_shortcut<R: Runtime>(app: AppHandle<R>, install_id: String, shortcut_type: String) {
+pub fn add_shortcut<R: Runtime>(app: AppHandle<R>, install_id: String, shortcut_type: String, profile_id: Option<String>) {
     let install = get_install_info_by_id(&app, install_id).unwrap();
+    // Shortcuts of a launch profile are named after it and are not tracked on the install
+    let profile = profile_id.and_then(|p| get_launch_profile_by_id(&app, p)).filter(|p| p.install_id == install.id);
+    let name = match profile.as_ref() { Some(p) => format!("{} ({})", install.name, p.name), None => install.name.clone() };
+    let launch_args = match profile.as_ref() { Some(p) => format!("--install={} --profile={}", install.id, p.id), None => format!("--install={}", install.id) };
     #[cfg(target_os = "linux")]
     {
         match shortcut_type.as_str() {
             "desktop" => {
                 let base = app.path().home_dir().unwrap().join(".local/share/applications");
-                let file = base.join(format!("{}.desktop", install.name.as_str()));
+                let file = base.join(format!("{}.desktop", name.as_str()));
                 let bin_name = if is_flatpak() { "flatpak run app.twintaillauncher.ttl" } else { "twintaillauncher" };
                 let icon = if is_flatpak() { "app.twintaillauncher.ttl" } else { "twintaillauncher" };
 
@@ -935,24 +1168,24 @@ pub fn add_shortcut<R: Runtime>(app: AppHandle<R>, install_id: String, shortcut_
                     r#"[Desktop Entry]
 Categories=Game;
 Comment=Launch this game using TwintailLauncher
-Exec={} --install={}
+Exec={} {}
 Icon={}
 Name={}
 Terminal=false
 Type=Application
 "#,
                     bin_name,
-                    install.id.as_str(),
+                    launch_args,
                     icon,
-                    install.name.as_str()
+                    name.as_str()
                 );
 
                 let status = add_desktop_shortcut(file.clone(), content);
                 if status {
                     log::info!("Created desktop shortcut for \"{}\" at {}", install.name, file.display());
-                    update_install_shortcut_location_by_id(&app, install.id.clone(), file.clone().to_str().unwrap().to_string(), );
-                    show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_desktop_created", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())])));
-                } else { log::warn!("Failed to create desktop shortcut for \"{}\"", install.name); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_desktop_create_failed", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())]))); }
+                    if profile.is_none() { update_install_shortcut_location_by_id(&app, install.id.clone(), file.clone().to_str().unwrap().to_string(), ); }
+                    show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_desktop_created", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())])));
+                } else { log::warn!("Failed to create desktop shortcut for \"{}\"", name); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_desktop_create_failed", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())]))); }
             }
             "steam" => {
                 let flatpak_steam = app.path().home_dir().unwrap().join(".var/app/com.valvesoftware.Steam/data/Steam/userdata");
@@ -960,12 +1193,12 @@ Type=Application
 
                 let manifest = get_manifest_info_by_id(&app, install.manifest_id).unwrap();
                 let m = get_manifest(&app, manifest.filename).unwrap();
-                let launchargs = format!("--install={}", install.id.as_str());
+                let launchargs = launch_args.clone();
 
                 let shortcut = Shortcut {
                     order: "",
-                    app_id: calculate_app_id(m.paths.exe_filename.as_str(), install.name.as_str()),
-                    app_name: install.name.as_str(),
+                    app_id: calculate_app_id(m.paths.exe_filename.as_str(), name.as_str()),
+                    app_name: name.as_str(),
                     exe: if is_flatpak() { "flatpak run app.twintaillauncher.ttl" } else { "twintaillauncher" },
                     start_dir: install.directory.as_str(),
                     icon: install.game_icon.as_str(),
@@ -983,21 +1216,21 @@ Type=Application
                 };
 
                 if flatpak_steam.exists() {
-                    let status = add_steam_shortcut(flatpak_steam, install.name.as_str(), shortcut.clone());
+                    let status = add_steam_shortcut(flatpak_steam, name.as_str(), shortcut.clone());
                     if status {
-                        log::info!("Added \"{}\" to Steam (Flatpak)", install.name);
-                        update_install_shortcut_is_steam_by_id(&app, install.id.clone(), true);
-                        show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_steam_flatpak_added", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())])));
-                    } else { log::warn!("Failed to add \"{}\" to Steam (Flatpak)", install.name); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_steam_flatpak_failed", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())]))); }
+                        log::info!("Added \"{}\" to Steam (Flatpak)", name);
+                        if profile.is_none() { update_install_shortcut_is_steam_by_id(&app, install.id.clone(), true); }
+                        show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_steam_flatpak_added", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())])));
+                    } else { log::warn!("Failed to add \"{}\" to Steam (Flatpak)", name); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_steam_flatpak_failed", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())]))); }
                 }
 
                 if normal_steam.exists() {
-                    let status = add_steam_shortcut(normal_steam, install.name.as_str(), shortcut);
+                    let status = add_steam_shortcut(normal_steam, name.as_str(), shortcut);
                     if status {
-                        log::info!("Added \"{}\" to Steam", install.name);
-                        update_install_shortcut_is_steam_by_id(&app, install.id.clone(), true);
-                        show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_steam_added", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())])));
-                    } else { log::warn!("Failed to add \"{}\" to Steam", install.name); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_steam_failed", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())]))); }
+                        log::info!("Added \"{}\" to Steam", name);
+                        if profile.is_none() { update_install_shortcut_is_steam_by_id(&app, install.id.clone(), true); }
+                        show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_steam_added", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())])));
+                    } else { log::warn!("Failed to add \"{}\" to Steam", name); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_steam_failed", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())]))); }
                 }
             }
             _ => {}
@@ -1010,14 +1243,14 @@ Type=Application
             "desktop" => {
                 let base = app.path().desktop_dir().unwrap();
                 let bin_name = app.path().app_local_data_dir().unwrap().join("twintaillauncher.exe");
-                let file = base.join(format!("{}.lnk", install.name.as_str()));
-                let sl = shortcuts_rs::ShellLink::new(bin_name.as_path(), Some(format!("--install={}", install.id.as_str())), Some(install.name.clone()), None).unwrap();
+                let file = base.join(format!("{}.lnk", name.as_str()));
+                let sl = shortcuts_rs::ShellLink::new(bin_name.as_path(), Some(launch_args.clone()), Some(name.clone()), None).unwrap();
                 let r = sl.create_lnk(file.as_path());
                 if r.is_ok() {
                     log::info!("Created desktop shortcut for \"{}\" at {}", install.name, file.display());
-                    update_install_shortcut_location_by_id(&app, install.id.clone(), file.clone().to_str().unwrap().to_string());
+                    if profile.is_none() { update_install_shortcut_location_by_id(&app, install.id.clone(), file.clone().to_str().unwrap().to_string()); }
                     show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_desktop_created_windows", None, None, None);
-                } else { log::warn!("Failed to create desktop shortcut for \"{}\"", install.name); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_create_failed_windows", None, None, None); }
+                } else { log::warn!("Failed to create desktop shortcut for \"{}\"", name); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_create_failed_windows", None, None, None); }
             }
             "steam" => { show_dialog_with_callback(&app, "warning", "TwintailLauncher", "dialogs.shortcut_steam_not_supported_windows", None, None, None); }
             _ => {}
@@ -1026,52 +1259,55 @@ Type=Application
 }
 
 #[tauri::command]
-pub fn remove_shortcut<R: Runtime>(app: AppHandle<R>, install_id: String, shortcut_type: String) {
+pub fn remove_shortcut<R: Runtime>(app: AppHandle<R>, install_id: String, shortcut_type: String, profile_id: Option<String>) {
     let install = get_install_info_by_id(&app, install_id).unwrap();
+    // Shortcuts of a launch profile are named after it and are not tracked on the install
+    let profile = profile_id.and_then(|p| get_launch_profile_by_id(&app, p)).filter(|p| p.install_id == install.id);
+    let name = match profile.as_ref() { Some(p) => format!("{} ({})", install.name, p.name), None => install.name.clone() };
     #[cfg(target_os = "linux")]
     {
         match shortcut_type.as_str() {
             "desktop" => {
                 let base = app.path().home_dir().unwrap().join(".local/share/applications");
-                let file = base.join(format!("{}.desktop", install.name.as_str()));
+                let file = base.join(format!("{}.desktop", name.as_str()));
                 if !file.exists() { fs::write(file.clone(), "").unwrap(); }
 
                 let status = remove_desktop_shortcut(file.clone());
                 if status {
-                    log::info!("Removed desktop shortcut for \"{}\"", install.name);
-                    update_install_shortcut_location_by_id(&app, install.id.clone(), "".to_string());
-                    show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_desktop_deleted", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())])));
-                } else { log::warn!("Desktop shortcut for \"{}\" does not exist, nothing to remove", install.name); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_desktop_not_exists", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())]))); }
+                    log::info!("Removed desktop shortcut for \"{}\"", name);
+                    if profile.is_none() { update_install_shortcut_location_by_id(&app, install.id.clone(), "".to_string()); }
+                    show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_desktop_deleted", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())])));
+                } else { log::warn!("Desktop shortcut for \"{}\" does not exist, nothing to remove", name); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_desktop_not_exists", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())]))); }
             }
             "steam" => {
                 let flatpak_steam = app.path().home_dir().unwrap().join(".var/app/com.valvesoftware.Steam/data/Steam/userdata");
                 let normal_steam = crate::utils::shortcuts::resolve_normal_steam_userdata(app.path().home_dir().unwrap());
 
                 if flatpak_steam.exists() {
-                    let status = remove_steam_shortcut(flatpak_steam, install.name.as_str());
+                    let status = remove_steam_shortcut(flatpak_steam, name.as_str());
                     if status {
-                        log::info!("Removed \"{}\" from Steam (Flatpak)", install.name);
-                        update_install_shortcut_is_steam_by_id(&app, install.id.clone(), false);
-                        show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_steam_flatpak_removed", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())])));
+                        log::info!("Removed \"{}\" from Steam (Flatpak)", name);
+                        if profile.is_none() { update_install_shortcut_is_steam_by_id(&app, install.id.clone(), false); }
+                        show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_steam_flatpak_removed", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())])));
                     } else {
                         // If flatpak Steam somehow exists but has no shortcut this will trigger an edge case with DB state
-                        log::warn!("Failed to remove \"{}\" from Steam (Flatpak), shortcut may have been manually deleted", install.name);
-                        update_install_shortcut_is_steam_by_id(&app, install.id.clone(), false);
-                        show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_steam_flatpak_remove_failed", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())])));
+                        log::warn!("Failed to remove \"{}\" from Steam (Flatpak), shortcut may have been manually deleted", name);
+                        if profile.is_none() { update_install_shortcut_is_steam_by_id(&app, install.id.clone(), false); }
+                        show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_steam_flatpak_remove_failed", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())])));
                     }
                 }
 
                 if normal_steam.exists() {
-                    let status = remove_steam_shortcut(normal_steam, install.name.as_str());
+                    let status = remove_steam_shortcut(normal_steam, name.as_str());
                     if status {
-                        log::info!("Removed \"{}\" from Steam", install.name);
-                        update_install_shortcut_is_steam_by_id(&app, install.id.clone(), false);
-                        show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_steam_removed", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())])));
+                        log::info!("Removed \"{}\" from Steam", name);
+                        if profile.is_none() { update_install_shortcut_is_steam_by_id(&app, install.id.clone(), false); }
+                        show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_steam_removed", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())])));
                     } else {
                         // If normal Steam somehow exists but has no shortcut this will trigger an edge case with DB state
-                        log::warn!("Failed to remove \"{}\" from Steam, shortcut may have been manually deleted", install.name);
-                        update_install_shortcut_is_steam_by_id(&app, install.id.clone(), false);
-                        show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_steam_remove_failed", None, None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())])));
+                        log::warn!("Failed to remove \"{}\" from Steam, shortcut may have been manually deleted", name);
+                        if profile.is_none() { update_install_shortcut_is_steam_by_id(&app, install.id.clone(), false); }
+                        show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.shortcut_steam_remove_failed", None, None, Some(std::collections::HashMap::from([("install_name", name.as_str())])));
                     }
                 }
             }
@@ -1084,14 +1320,14 @@ pub fn remove_shortcut<R: Runtime>(app: AppHandle<R>, install_id: String, shortc
         match shortcut_type.as_str() {
             "desktop" => {
                 let base = app.path().desktop_dir().unwrap();
-                let file = base.join(format!("{}.lnk", install.name.as_str()));
+                let file = base.join(format!("{}.lnk", name.as_str()));
 
                 let status = remove_desktop_shortcut(file.clone());
                 if status {
-                    log::info!("Removed desktop shortcut for \"{}\"", install.name);
-                    update_install_shortcut_location_by_id(&app, install.id.clone(), "".to_string());
+                    log::info!("Removed desktop shortcut for \"{}\"", name);
+                    if profile.is_none() { update_install_shortcut_location_by_id(&app, install.id.clone(), "".to_string()); }
                     show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.shortcut_desktop_deleted_windows", None, None, None);
-                } else { log::warn!("Desktop shortcut for \"{}\" does not exist, nothing to remove", install.name); show_dialog_with_callback(&app, "warning", "TwintailLauncher", "dialogs.shortcut_desktop_not_exists_generic", None, None, None); }
+                } else { log::warn!("Desktop shortcut for \"{}\" does not exist, nothing to remove", name); show_dialog_with_callback(&app, "warning", "TwintailLauncher", "dialogs.shortcut_desktop_not_exists_generic", None, None, None); }
             }
             "steam" => { show_dialog_with_callback(&app, "warning", "TwintailLauncher", "dialogs.shortcut_steam_not_supported_windows", None, None, None); }
             _ => {}
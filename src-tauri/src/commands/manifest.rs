@@ -93,7 +93,7 @@ pub fn list_compatibility_manifests<R: Runtime>(app: AppHandle<R>, biz: Option<S
         { if !value.aarch64_supported { continue; } }
         #[allow(unused_mut)] let mut v = value;
         #[cfg(target_arch = "aarch64")]
-        { v.versions = v.versions.into_iter().filter(|ver| ver.urls.as_ref().map(|u| !u.aarch64.is_empty()).unwrap_or(false)).collect(); }
+        { v.versions = v.versions.into_iter().filter(|ver| ver.urls.as_ref().map(|u| !u.aarch64.is_empty()).unwrap_or(ver.url.is_empty())).collect(); }
         if let Some(ref ov) = override_version { v.versions = v.versions.into_iter().filter(|ver| crate::utils::is_using_overriden_runner(ver.version.clone(), ov.clone())).collect(); }
         manifests.push(v);
     }
@@ -4,4 +4,7 @@ pub mod install;
 pub mod settings;
 pub mod runners;
 pub mod network;
-pub mod queue;
\ No newline at end of file
+pub mod queue;
+pub mod playtime;
+pub mod profiles;
+pub mod snapshots;
\ No newline at end of file
@@ -0,0 +1,30 @@
+use crate::utils::db_manager::{get_play_sessions, get_playtime_totals, PlaytimeGrouping};
+use crate::utils::models::{LauncherPlaySession, PlaytimeTotal};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Runtime};
+
+#[tauri::command]
+pub fn list_play_sessions<R: Runtime>(app: AppHandle<R>, install_id: Option<String>, limit: Option<i64>) -> Option<Vec<LauncherPlaySession>> {
+    get_play_sessions(&app, install_id, limit.unwrap_or(100))
+}
+
+/// Totals of the last `days` days, install_id None sums every install.
+#[tauri::command]
+pub fn get_playtime_daily<R: Runtime>(app: AppHandle<R>, install_id: Option<String>, days: Option<i64>) -> Option<Vec<PlaytimeTotal>> {
+    get_playtime_totals(&app, install_id, PlaytimeGrouping::Day, since_days(days.unwrap_or(30)))
+}
+
+#[tauri::command]
+pub fn get_playtime_weekly<R: Runtime>(app: AppHandle<R>, install_id: Option<String>, weeks: Option<i64>) -> Option<Vec<PlaytimeTotal>> {
+    get_playtime_totals(&app, install_id, PlaytimeGrouping::Week, since_days(weeks.unwrap_or(12) * 7))
+}
+
+#[tauri::command]
+pub fn get_playtime_by_version<R: Runtime>(app: AppHandle<R>, install_id: Option<String>) -> Option<Vec<PlaytimeTotal>> {
+    get_playtime_totals(&app, install_id, PlaytimeGrouping::Version, 0)
+}
+
+fn since_days(days: i64) -> i64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64;
+    (now - days.max(0) * 86400).max(0)
+}
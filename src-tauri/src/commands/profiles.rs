@@ -0,0 +1,67 @@
+use crate::utils::db_manager::{create_launch_profile as insert_launch_profile, delete_launch_profile_by_id, get_install_info_by_id, get_launch_profile_by_id, get_launch_profiles_by_install_id, update_launch_profile_by_id};
+use crate::utils::env_vars::install_env;
+use crate::utils::generate_cuid;
+use crate::utils::shortcuts::sync_profile_shortcuts;
+use crate::utils::models::LaunchProfile;
+use sqlx::types::Json;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Runtime};
+
+#[tauri::command]
+pub fn list_launch_profiles<R: Runtime>(app: AppHandle<R>, install_id: String) -> Option<Vec<LaunchProfile>> {
+    let install = get_install_info_by_id(&app, install_id)?;
+    Some(get_launch_profiles_by_install_id(&app, install.id))
+}
+
+/// Saves the current launch settings of the install as a new profile.
+#[tauri::command]
+pub fn create_launch_profile<R: Runtime>(app: AppHandle<R>, install_id: String, name: String) -> Option<LaunchProfile> {
+    let install = get_install_info_by_id(&app, install_id)?;
+    let name = name.trim().to_string();
+    if !is_valid_profile_name(&app, &install.id, &name, None) { log::warn!("Refusing to create launch profile \"{}\" for {}: empty or duplicate name", name, install.name); return None; }
+
+    // Legacy strings are converted here so profiles only ever carry the typed list
+    let env_list = install_env(&install);
+    let profile = LaunchProfile {
+        id: generate_cuid(),
+        install_id: install.id.clone(),
+        name,
+        launch_args: install.launch_args,
+        env_vars: String::new(),
+        env_list: Json(env_list),
+        use_mangohud: install.use_mangohud,
+        use_gamemode: install.use_gamemode,
+        use_xxmi: install.use_xxmi,
+        use_fps_unlock: install.use_fps_unlock,
+        fps_value: install.fps_value,
+        graphics_api: install.graphics_api,
+        created_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64,
+    };
+    log::info!("Created launch profile \"{}\" for \"{}\"", profile.name, install.name);
+    insert_launch_profile(&app, profile.clone());
+    Some(profile)
+}
+
+#[tauri::command]
+pub fn update_launch_profile<R: Runtime>(app: AppHandle<R>, profile: LaunchProfile) -> Option<bool> {
+    let existing = get_launch_profile_by_id(&app, profile.id.clone())?;
+    let name = profile.name.trim().to_string();
+    if !is_valid_profile_name(&app, &existing.install_id, &name, Some(&existing.id)) { log::warn!("Refusing to rename launch profile {} to \"{}\": empty or duplicate name", existing.id, name); return Some(false); }
+    if let Some(install) = get_install_info_by_id(&app, existing.install_id.clone()).filter(|_| name != existing.name) { sync_profile_shortcuts(&app, &install.name, &existing.name, Some(&name)); }
+    update_launch_profile_by_id(&app, LaunchProfile { name, install_id: existing.install_id, created_at: existing.created_at, ..profile });
+    Some(true)
+}
+
+#[tauri::command]
+pub fn delete_launch_profile<R: Runtime>(app: AppHandle<R>, profile_id: String) -> Option<bool> {
+    let profile = get_launch_profile_by_id(&app, profile_id)?;
+    if let Some(install) = get_install_info_by_id(&app, profile.install_id.clone()) { sync_profile_shortcuts(&app, &install.name, &profile.name, None); }
+    log::info!("Deleted launch profile \"{}\"", profile.name);
+    delete_launch_profile_by_id(&app, profile.id);
+    Some(true)
+}
+
+/// Names end up in shortcut file names, so they have to be unique per install.
+fn is_valid_profile_name<R: Runtime>(app: &AppHandle<R>, install_id: &str, name: &str, except: Option<&str>) -> bool {
+    !name.is_empty() && !name.contains(['/', '\\']) && !get_launch_profiles_by_install_id(app, install_id.to_string()).iter().any(|p| p.name.eq_ignore_ascii_case(name) && Some(p.id.as_str()) != except)
+}
@@ -1,7 +1,7 @@
 use std::sync::atomic::Ordering;
 use tauri::{AppHandle, Runtime, Manager};
 use crate::DownloadState;
-use crate::downloading::queue::QueueStatePayload;
+use crate::downloading::queue::{QueueLane, QueueStatePayload};
 
 #[tauri::command]
 pub fn pause_game_download<R: Runtime>(app: AppHandle<R>, install_id: String) -> bool {
@@ -69,7 +69,9 @@ pub fn queue_activate_job<R: Runtime>(app: AppHandle<R>, job_id: String) -> bool
         if let Some(ref queue_handle) = *queue_guard { queue_handle.activate_job(job_id.clone()) } else { None }
     };
 
-    if let Some(skip_id) = activated_install_id {
+    if let Some((skip_id, lane)) = activated_install_id {
+        // Misc lane jobs never hold a game slot, so there is nothing to cancel for them
+        if lane != QueueLane::Game { return true; }
         log::info!("Activating job {} (install {}), cancelling other running downloads", job_id, skip_id);
         // Now pause all currently running downloads by setting their cancel tokens
         // EXCEPT the one we just activated (if it already started).
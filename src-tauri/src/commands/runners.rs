@@ -4,6 +4,7 @@ use crate::utils::db_manager::{
     update_install_runner_version_by_id, update_installed_runner_is_installed_by_version,
 };
 use crate::utils::models::LauncherRunner;
+use crate::utils::runner_usage::{RunnerCleanupReport, RunnerUsage};
 use std::fs;
 use std::path::Path;
 use tauri::{AppHandle, Runtime};
@@ -19,7 +20,11 @@ use crate::utils::db_manager::create_installed_runner;
 #[cfg(target_os = "linux")]
 use crate::utils::repo_manager::get_compatibility;
 #[cfg(target_os = "linux")]
-use crate::utils::runner_from_runner_version;
+use crate::utils::{queue_runner_verify, runner_from_runner_version};
+#[cfg(target_os = "linux")]
+use crate::utils::local_runners::{forget_local_runner, import_local_runner};
+#[cfg(target_os = "linux")]
+use crate::utils::runner_usage::{cleanup_candidates, remove_runner, runner_usage_report};
 #[cfg(target_os = "linux")]
 use tauri::Manager;
 
@@ -153,12 +158,24 @@ pub fn remove_installed_runner<R: Runtime>(app: AppHandle<R>, runner_version: St
     } else {
         let gs = get_settings(&app).unwrap();
         let runner_path = Path::new(&gs.default_runner_path).join(runner_version.clone());
-        if !runner_path.exists() { if let Err(_) = fs::create_dir_all(&runner_path) { return Some(false) } }
+        #[cfg(target_os = "linux")]
+        let is_local = get_installed_runner_info_by_version(&app, runner_version.clone()).is_some_and(|r| r.local_runner.0.is_some());
+        #[cfg(target_os = "windows")]
+        let is_local = false;
+        if !is_local && !runner_path.exists() { if let Err(_) = fs::create_dir_all(&runner_path) { return Some(false) } }
 
-        if fs::read_dir(runner_path.as_path()).unwrap().next().is_some() {
-            fs::remove_dir_all(runner_path.as_path()).unwrap();
-            log::info!("Removed runner {} from {}", runner_version, runner_path.display());
-            update_installed_runner_is_installed_by_version(&app, runner_version.clone(), false);
+        if is_local || fs::read_dir(runner_path.as_path()).unwrap().next().is_some() {
+            if is_local {
+                // Imported runners only lose their link, the directory they came from belongs to the user
+                #[cfg(target_os = "linux")]
+                forget_local_runner(&app, &runner_version);
+            } else {
+                fs::remove_dir_all(runner_path.as_path()).unwrap();
+                log::info!("Removed runner {} from {}", runner_version, runner_path.display());
+                update_installed_runner_is_installed_by_version(&app, runner_version.clone(), false);
+            }
+            #[cfg(target_os = "linux")]
+            crate::downloading::verify::delete_runner_manifest(&app, &runner_version);
 
             // Set installations using the removed runner to first available one as fallback
             let installs = get_installs(&app);
@@ -186,6 +203,84 @@ pub fn remove_installed_runner<R: Runtime>(app: AppHandle<R>, runner_version: St
     }
 }
 
+/// Imports a Wine or Proton build from a local directory so installs can select it, `name` defaults to the directory name.
+#[allow(unused_variables)]
+#[tauri::command]
+pub fn add_local_runner<R: Runtime>(app: AppHandle<R>, path: String, name: Option<String>) -> Option<bool> {
+    #[cfg(target_os = "linux")]
+    {
+        let source = Path::new(&path);
+        let name = name.filter(|n| !n.trim().is_empty()).unwrap_or_else(|| source.file_name().and_then(|n| n.to_str()).unwrap_or_default().to_string());
+        match import_local_runner(&app, source, &name) {
+            Ok(_) => Some(true),
+            Err(key) => {
+                crate::utils::show_dialog_with_callback(&app, "warning", "TwintailLauncher", key, None, None, Some(std::collections::HashMap::from([("runner_version", name.trim()), ("path", path.as_str())])));
+                Some(false)
+            }
+        }
+    }
+    #[cfg(target_os = "windows")]
+    { None }
+}
+
+/// Queues a verify job for an installed runner, it downloads the runner again when binaries are missing or files changed.
+#[allow(unused_variables)]
+#[tauri::command]
+pub fn verify_installed_runner<R: Runtime>(app: AppHandle<R>, runner_version: String) -> Option<bool> {
+    #[cfg(target_os = "linux")]
+    {
+        let runner = get_installed_runner_info_by_version(&app, runner_version)?;
+        if !queue_runner_verify(&app, runner.version.clone(), runner.runner_path.clone()) {
+            crate::utils::show_dialog_with_callback(&app, "warning", "TwintailLauncher", "dialogs.runner_already_queued", None, None, Some(std::collections::HashMap::from([("runner_version", runner.version.as_str())])));
+            return Some(false);
+        }
+        Some(true)
+    }
+    #[cfg(target_os = "windows")]
+    { None }
+}
+
+#[allow(unused_variables)]
+#[tauri::command]
+pub fn get_runner_usage_report<R: Runtime>(app: AppHandle<R>) -> Option<Vec<RunnerUsage>> {
+    #[cfg(target_os = "linux")]
+    { Some(runner_usage_report(&app)) }
+    // Runners are a Linux thing, Windows has nothing to report
+    #[cfg(target_os = "windows")]
+    { Some(Vec::new()) }
+}
+
+/// Runners `cleanup_runners` would remove, shown to the user for confirmation first.
+#[allow(unused_variables)]
+#[tauri::command]
+pub fn preview_runner_cleanup<R: Runtime>(app: AppHandle<R>, keep_per_family: Option<usize>) -> Option<Vec<RunnerUsage>> {
+    #[cfg(target_os = "linux")]
+    { Some(cleanup_candidates(runner_usage_report(&app), keep_per_family.unwrap_or(0))) }
+    #[cfg(target_os = "windows")]
+    { Some(Vec::new()) }
+}
+
+/// Removes the confirmed runners. Candidates are computed again so a runner that got picked up since the preview survives.
+#[allow(unused_variables)]
+#[tauri::command]
+pub fn cleanup_runners<R: Runtime>(app: AppHandle<R>, versions: Vec<String>, keep_per_family: Option<usize>) -> Option<RunnerCleanupReport> {
+    #[cfg(target_os = "linux")]
+    {
+        let candidates = cleanup_candidates(runner_usage_report(&app), keep_per_family.unwrap_or(0));
+        let mut report = RunnerCleanupReport { removed: Vec::new(), skipped: Vec::new(), freed: 0 };
+        for v in versions {
+            match candidates.iter().find(|c| c.version == v) {
+                Some(c) if remove_runner(&app, c) => { report.freed += c.size; report.removed.push(v); }
+                _ => report.skipped.push(v),
+            }
+        }
+        log::info!("Runner cleanup removed {} runner(s), skipped {}", report.removed.len(), report.skipped.len());
+        Some(report)
+    }
+    #[cfg(target_os = "windows")]
+    { Some(RunnerCleanupReport { removed: Vec::new(), skipped: versions, freed: 0 }) }
+}
+
 #[allow(unused_variables)]
 #[tauri::command]
 pub fn is_steamrt_installed<R: Runtime>(app: AppHandle<R>) -> bool {
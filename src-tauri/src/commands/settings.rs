@@ -1,9 +1,16 @@
-use crate::utils::db_manager::{get_install_info_by_id, get_installed_runner_info_by_version, get_manifest_info_by_id, get_settings, update_settings_app_lang, update_settings_default_dxvk_location, update_settings_default_fps_unlock_location, update_settings_default_game_location, update_settings_default_jadeite_location, update_settings_default_mangohud_config_location, update_settings_default_prefix_location, update_settings_default_runner_location, update_settings_default_xxmi_location, update_settings_download_speed_limit, update_settings_hide_app_to_tray, update_settings_hide_manifests, update_settings_launch_action, update_settings_third_party_repo_update};
-use crate::utils::models::GlobalSettings;
+use crate::utils::db_manager::{get_install_info_by_id, get_installed_runner_info_by_version, get_manifest_info_by_id, get_settings, update_settings_app_lang, update_settings_auto_resume_queue, update_settings_default_dxvk_location, update_settings_default_fps_unlock_location, update_settings_default_game_location, update_settings_default_jadeite_location, update_settings_default_mangohud_config_location, update_settings_default_prefix_location, update_settings_default_runner_location, update_settings_default_xxmi_location, update_settings_download_schedule, update_settings_download_speed_limit, update_settings_download_speed_limit_while_playing, update_settings_download_speed_limits, update_settings_hide_app_to_tray, update_settings_hide_manifests, update_settings_launch_action, update_settings_launch_defaults, update_settings_max_concurrent_game_jobs, update_settings_max_concurrent_misc_jobs, update_settings_third_party_repo_update};
+use crate::utils::env_vars::validate_env_vars;
+use crate::utils::launch_defaults::{apply_launch_defaults, diff_launch_defaults, LaunchDefaultsDiff};
+use crate::utils::models::{GlobalSettings, LaunchDefaults};
+use crate::downloading::bandwidth::{format_speed_limits, parse_speed_limits, BandwidthLimits};
+use crate::downloading::queue::{enum_from_db_str, QueueJobKind, QueueLane};
+use crate::downloading::schedule::{parse_schedule_days, parse_schedule_time, DownloadSchedule};
+use crate::DownloadState;
 use crate::utils::repo_manager::get_manifest;
 use crate::utils::{compare_version, get_mi_path_from_game, show_dialog_with_callback};
 use std::fs;
 use std::path::Path;
+use sqlx::types::Json;
 use tauri::{AppHandle, Runtime,Manager};
 use tauri_plugin_opener::OpenerExt;
 
@@ -15,10 +22,8 @@ pub async fn list_settings<R: Runtime>(app: AppHandle<R>) -> Option<GlobalSettin
     let settings = get_settings(&app);
 
     if settings.is_some() {
-        let s = settings.unwrap();
-        // Ensure fischl's global limiter is synced with persisted settings (value in KB/s).
-        fischl::utils::downloader::set_global_download_speed_limit_kb(s.download_speed_limit.max(0) as u64);
-        Some(s)
+        // Speed limits are applied by the download queue, it loads them from settings on start
+        Some(settings.unwrap())
     } else {
         None
     }
@@ -28,10 +33,66 @@ pub async fn list_settings<R: Runtime>(app: AppHandle<R>) -> Option<GlobalSettin
 pub fn update_settings_download_speed_limit_cmd<R: Runtime>(app: AppHandle<R>, speed_limit: i64) -> Option<bool> {
     let clamped = speed_limit.max(0);
     update_settings_download_speed_limit(&app, clamped);
-    fischl::utils::downloader::set_global_download_speed_limit_kb(clamped as u64);
+    sync_bandwidth_limits(&app);
     Some(true)
 }
 
+/// Sets the limit for one job kind, a negative value removes the override so the kind follows the global limit again.
+#[tauri::command]
+pub fn update_settings_download_speed_limit_kind_cmd<R: Runtime>(app: AppHandle<R>, kind: String, speed_limit: i64) -> Option<bool> {
+    let s = get_settings(&app)?;
+    let kind = enum_from_db_str::<QueueJobKind>(&kind)?;
+    let mut limits = parse_speed_limits(&s.download_speed_limits).unwrap_or_default();
+    if speed_limit < 0 { limits.remove(&kind); } else { limits.insert(kind, speed_limit as u64); }
+    update_settings_download_speed_limits(&app, format_speed_limits(&limits));
+    sync_bandwidth_limits(&app);
+    Some(true)
+}
+
+#[tauri::command]
+pub fn update_settings_download_speed_limit_while_playing_cmd<R: Runtime>(app: AppHandle<R>, speed_limit: i64) -> Option<bool> {
+    update_settings_download_speed_limit_while_playing(&app, speed_limit.max(0));
+    sync_bandwidth_limits(&app);
+    Some(true)
+}
+
+fn sync_bandwidth_limits<R: Runtime>(app: &AppHandle<R>) {
+    let Some(s) = get_settings(app) else { return; };
+    let state = app.state::<DownloadState>();
+    let queue_guard = state.queue.lock().unwrap();
+    if let Some(ref queue_handle) = *queue_guard { queue_handle.set_bandwidth(BandwidthLimits::from_settings(&s)); }
+}
+
+#[tauri::command]
+pub fn update_settings_queue_max_concurrent_cmd<R: Runtime>(app: AppHandle<R>, lane: String, max_concurrent: i64) -> Option<bool> {
+    let clamped = max_concurrent.clamp(1, 4);
+    let lane = match lane.as_str() {
+        "game" => { update_settings_max_concurrent_game_jobs(&app, clamped); QueueLane::Game }
+        "misc" => { update_settings_max_concurrent_misc_jobs(&app, clamped); QueueLane::Misc }
+        _ => return None,
+    };
+    let state = app.state::<DownloadState>();
+    let queue_guard = state.queue.lock().unwrap();
+    if let Some(ref queue_handle) = *queue_guard { queue_handle.set_max_concurrent(lane, clamped as usize); }
+    Some(true)
+}
+
+#[tauri::command]
+pub fn update_settings_download_schedule_cmd<R: Runtime>(app: AppHandle<R>, enabled: bool, start: String, end: String, days: String) -> Option<bool> {
+    // An enabled schedule without days would never open a window and hold the queue forever
+    if parse_schedule_time(&start).is_none() || parse_schedule_time(&end).is_none() || parse_schedule_days(&days).is_none_or(|d| enabled && !d.iter().any(|x| *x)) { log::warn!("Rejected invalid download schedule {}-{} on {}", start, end, days); return None; }
+    update_settings_download_schedule(&app, enabled, start, end, days);
+    sync_download_schedule(&app);
+    Some(true)
+}
+
+fn sync_download_schedule<R: Runtime>(app: &AppHandle<R>) {
+    let Some(s) = get_settings(app) else { return; };
+    let state = app.state::<DownloadState>();
+    let queue_guard = state.queue.lock().unwrap();
+    if let Some(ref queue_handle) = *queue_guard { queue_handle.set_schedule(DownloadSchedule::from_settings(&s)); }
+}
+
 #[tauri::command]
 pub fn update_settings_third_party_repo_updates<R: Runtime>(app: AppHandle<R>, enabled: bool) -> Option<bool> {
     update_settings_third_party_repo_update(&app, enabled);
@@ -168,6 +229,35 @@ pub fn update_settings_hide_app_tray<R: Runtime>(app: AppHandle<R>, enabled: boo
     Some(true)
 }
 
+#[tauri::command]
+pub fn update_settings_queue_auto_resume<R: Runtime>(app: AppHandle<R>, enabled: bool) -> Option<bool> {
+    update_settings_auto_resume_queue(&app, enabled);
+    Some(true)
+}
+
+#[tauri::command]
+pub fn update_settings_launch_defaults_cmd<R: Runtime>(app: AppHandle<R>, defaults: LaunchDefaults) -> Option<bool> {
+    let errors = validate_env_vars(&defaults.env_list);
+    if !errors.is_empty() { log::warn!("Refusing to save default environment variables: {:?}", errors); return Some(false); }
+    update_settings_launch_defaults(&app, Json(LaunchDefaults { launch_args: defaults.launch_args.trim().to_string(), ..defaults }));
+    Some(true)
+}
+
+/// What applying the launch defaults would change, per install and field. An empty `fields` compares all of them.
+#[tauri::command]
+pub fn preview_apply_launch_defaults<R: Runtime>(app: AppHandle<R>, fields: Vec<String>) -> Option<Vec<LaunchDefaultsDiff>> {
+    let gs = get_settings(&app)?;
+    Some(diff_launch_defaults(&app, &gs.launch_defaults, &fields))
+}
+
+/// Overwrites `fields` of every install with the launch defaults, `install_ids` limits it to the installs picked in the preview.
+#[tauri::command]
+pub fn apply_launch_defaults_cmd<R: Runtime>(app: AppHandle<R>, fields: Vec<String>, install_ids: Option<Vec<String>>) -> Option<usize> {
+    let gs = get_settings(&app)?;
+    let diffs = diff_launch_defaults(&app, &gs.launch_defaults, &fields).into_iter().filter(|d| install_ids.as_ref().is_none_or(|ids| ids.contains(&d.install_id))).collect::<Vec<LaunchDefaultsDiff>>();
+    Some(apply_launch_defaults(&app, &gs.launch_defaults, diffs))
+}
+
 #[tauri::command]
 pub fn open_folder<R: Runtime>(app: AppHandle<R>, manifest_id: String, install_id: String, runner_version: String, path_type: String) {
     log::debug!("Opening {} folder for install {}", path_type, install_id);
@@ -244,6 +334,21 @@ pub fn open_folder<R: Runtime>(app: AppHandle<R>, manifest_id: String, install_i
                 } else { show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.prefix_dir_open_failed", None, None, None); };
             }
         }
+        "session_log" => {
+            let install = get_install_info_by_id(&app, install_id);
+            if install.is_some() {
+                let i = install.unwrap();
+                match crate::utils::session_logs::latest_session_log(&app, &i.id) {
+                    Some(fp) => {
+                        match app.opener().reveal_item_in_dir(fp.as_path()) {
+                            Ok(_) => {}
+                            Err(_e) => { show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.directory_open_failed", None, None, None); }
+                        }
+                    }
+                    None => { show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.session_log_not_found", None, None, None); }
+                }
+            }
+        }
         "engine_log" => {
             let install = get_install_info_by_id(&app, install_id);
             if install.is_some() {
@@ -0,0 +1,89 @@
+use crate::utils::models::PrefixSnapshot;
+use tauri::{AppHandle, Runtime};
+#[cfg(target_os = "linux")]
+use crate::utils::db_manager::{get_install_info_by_id, get_installed_runner_info_by_version, update_install_runner_location_by_id, update_install_runner_version_by_id};
+#[cfg(target_os = "linux")]
+use crate::utils::prefix_snapshots::{create_prefix_snapshot, delete_prefix_snapshot, is_prefix_busy, list_prefix_snapshots, restore_prefix_snapshot};
+#[cfg(target_os = "linux")]
+use crate::utils::show_dialog_with_callback;
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn list_install_prefix_snapshots<R: Runtime>(app: AppHandle<R>, id: String) -> Option<Vec<PrefixSnapshot>> {
+    let install = get_install_info_by_id(&app, id)?;
+    Some(list_prefix_snapshots(&app, &install.id))
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn list_install_prefix_snapshots<R: Runtime>(_app: AppHandle<R>, _id: String) -> Option<Vec<PrefixSnapshot>> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub async fn create_install_prefix_snapshot<R: Runtime>(app: AppHandle<R>, id: String) -> Option<PrefixSnapshot> {
+    let install = get_install_info_by_id(&app, id)?;
+    if is_prefix_busy(&install.id) {
+        show_dialog_with_callback(&app, "warning", "TwintailLauncher", "dialogs.prefix_snapshot_busy", None, None, Some(HashMap::from([("install_name", install.name.as_str())])));
+        return None;
+    }
+    let snapshot = create_prefix_snapshot(&app, &install, "manual");
+    if snapshot.is_none() { show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.prefix_snapshot_failed", None, None, Some(HashMap::from([("install_name", install.name.as_str())]))); }
+    snapshot
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn create_install_prefix_snapshot<R: Runtime>(_app: AppHandle<R>, _id: String) -> Option<PrefixSnapshot> {
+    None
+}
+
+/// With `restore_runner` the install also goes back to the runner of the snapshot, as long as that runner is still installed.
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub async fn restore_install_prefix_snapshot<R: Runtime>(app: AppHandle<R>, id: String, snapshot_id: String, restore_runner: bool) -> Option<bool> {
+    let install = get_install_info_by_id(&app, id)?;
+    let snapshot = list_prefix_snapshots(&app, &install.id).into_iter().find(|s| s.id == snapshot_id)?;
+    if !restore_prefix_snapshot(&app, &install, &snapshot) {
+        show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.prefix_snapshot_restore_failed", None, None, Some(HashMap::from([("install_name", install.name.as_str())])));
+        return Some(false);
+    }
+
+    if restore_runner && snapshot.runner_version != install.runner_version {
+        match get_installed_runner_info_by_version(&app, snapshot.runner_version.clone()).filter(|r| r.is_installed) {
+            Some(runner) => {
+                update_install_runner_version_by_id(&app, install.id.clone(), snapshot.runner_version.clone());
+                update_install_runner_location_by_id(&app, install.id.clone(), runner.runner_path);
+                log::info!("Switched runner of {} back to {} with its prefix snapshot", install.name, snapshot.runner_version);
+            }
+            None => log::warn!("Runner {} of prefix snapshot {} is not installed anymore, {} stays on {}", snapshot.runner_version, snapshot.id, install.name, install.runner_version),
+        }
+    }
+    show_dialog_with_callback(&app, "info", "TwintailLauncher", "dialogs.prefix_snapshot_restored", None, None, Some(HashMap::from([("install_name", install.name.as_str())])));
+    Some(true)
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub async fn restore_install_prefix_snapshot<R: Runtime>(_app: AppHandle<R>, _id: String, _snapshot_id: String, _restore_runner: bool) -> Option<bool> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+#[tauri::command]
+pub fn delete_install_prefix_snapshot<R: Runtime>(app: AppHandle<R>, id: String, snapshot_id: String) -> Option<bool> {
+    let install = get_install_info_by_id(&app, id)?;
+    let snapshot = list_prefix_snapshots(&app, &install.id).into_iter().find(|s| s.id == snapshot_id)?;
+    delete_prefix_snapshot(&app, &snapshot);
+    log::info!("Deleted prefix snapshot {} of {}", snapshot.id, install.name);
+    Some(true)
+}
+
+#[cfg(target_os = "windows")]
+#[tauri::command]
+pub fn delete_install_prefix_snapshot<R: Runtime>(_app: AppHandle<R>, _id: String, _snapshot_id: String) -> Option<bool> {
+    None
+}
@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::downloading::queue::{enum_from_db_str, enum_to_db_str, QueueJobKind};
+use crate::utils::models::GlobalSettings;
+
+static GAMES_RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+/// Download speed limits in KB/s, 0 means unlimited.
+/// fischl only has one process wide limiter, so while several jobs run at once they all get the strictest limit among them.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BandwidthLimits {
+    pub global: u64,
+    /// Overrides the global limit for a job kind, an explicit 0 makes that kind unlimited.
+    pub per_kind: HashMap<QueueJobKind, u64>,
+    /// Applied on top of everything else while a game launched from the launcher is running.
+    pub while_playing: u64,
+}
+
+impl BandwidthLimits {
+    pub fn from_settings(gs: &GlobalSettings) -> Self {
+        BandwidthLimits { global: gs.download_speed_limit.max(0) as u64, per_kind: parse_speed_limits(&gs.download_speed_limits).unwrap_or_default(), while_playing: gs.download_speed_limit_while_playing.max(0) as u64 }
+    }
+
+    pub fn limit_for(&self, kind: QueueJobKind) -> u64 {
+        self.per_kind.get(&kind).copied().unwrap_or(self.global)
+    }
+
+    /// Limit to apply for the given set of running jobs, idle queue falls back to the global limit.
+    pub fn effective<I: IntoIterator<Item = QueueJobKind>>(&self, running: I, game_running: bool) -> u64 {
+        let mut limits: Vec<u64> = running.into_iter().map(|k| self.limit_for(k)).collect();
+        if limits.is_empty() { limits.push(self.global); }
+        if game_running { limits.push(self.while_playing); }
+        limits.into_iter().filter(|l| *l > 0).min().unwrap_or(0)
+    }
+}
+
+/// Parses "game_update=5120,runner_download=0" into per job kind limits.
+pub fn parse_speed_limits(value: &str) -> Option<HashMap<QueueJobKind, u64>> {
+    let mut limits = HashMap::new();
+    for entry in value.split(',').map(|e| e.trim()).filter(|e| !e.is_empty()) {
+        let (kind, limit) = entry.split_once('=')?;
+        limits.insert(enum_from_db_str::<QueueJobKind>(kind.trim())?, limit.trim().parse::<u64>().ok()?);
+    }
+    Some(limits)
+}
+
+pub fn format_speed_limits(limits: &HashMap<QueueJobKind, u64>) -> String {
+    let mut entries = limits.iter().map(|(k, v)| format!("{}={}", enum_to_db_str(k), v)).collect::<Vec<String>>();
+    entries.sort();
+    entries.join(",")
+}
+
+pub fn set_game_running(running: bool) {
+    if running { GAMES_RUNNING.fetch_add(1, Ordering::SeqCst); } else { let _ = GAMES_RUNNING.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)); }
+}
+
+pub fn is_game_running() -> bool {
+    GAMES_RUNNING.load(Ordering::SeqCst) > 0
+}
+
+pub fn apply_speed_limit(limit_kb: u64) {
+    fischl::utils::downloader::set_global_download_speed_limit_kb(limit_kb);
+}
@@ -0,0 +1,63 @@
+use crate::utils::models::{DiffGameFile, FullGameFile};
+use crate::utils::show_dialog_with_callback;
+use fischl::utils::prettify_bytes;
+use std::path::Path;
+use tauri::{AppHandle, Runtime};
+
+/// Bytes a job needs on the install drive, `staging` for downloaded archives and `patch` for the files written out of them.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SpaceEstimate {
+    pub staging: u64,
+    pub patch: u64,
+}
+
+impl SpaceEstimate {
+    /// Diff archives are staged first and the patched files get written next to the old ones, so both have to fit at once.
+    /// Preloads only stage the archives, pass `with_patch` false for them.
+    pub fn for_diffs(diffs: &[&DiffGameFile], with_patch: bool) -> Self {
+        let staging = diffs.iter().map(|e| { let c = parse_size(&e.compressed_size); if c > 0 { c } else { parse_size(&e.decompressed_size) } }).sum();
+        let patch = if with_patch { diffs.iter().map(|e| parse_size(&e.decompressed_size)).sum() } else { 0 };
+        SpaceEstimate { staging, patch }
+    }
+
+    pub fn for_full(files: &[&FullGameFile]) -> Self {
+        SpaceEstimate { staging: files.iter().map(|e| parse_size(&e.compressed_size)).sum(), patch: files.iter().map(|e| parse_size(&e.decompressed_size)).sum() }
+    }
+
+    /// Full downloads over an existing install overwrite the files in place, only what the install grows by needs free space.
+    /// Zipped archives are always staged whole, Sophon only fetches chunks for what is missing so `chunked` scales staging down the same way.
+    pub fn over_existing(self, on_disk: u64, chunked: bool) -> Self {
+        let patch = self.patch.saturating_sub(on_disk);
+        let staging = if chunked && self.patch > 0 { (self.staging as u128 * patch as u128 / self.patch as u128) as u64 } else { self.staging };
+        SpaceEstimate { staging, patch }
+    }
+
+    pub fn total(&self) -> u64 { self.staging + self.patch }
+}
+
+/// Checks the estimate against free space on the install drive before any bytes are downloaded, so jobs are refused up front instead of failing halfway once the disk fills up.
+/// Archives a previous attempt already left in `staging_dir` count as done. Shows `dialog` and returns false when the job will not fit.
+pub fn preflight_disk_space<R: Runtime>(app: &AppHandle<R>, install_name: &str, directory: &str, staging_dir: &str, estimate: SpaceEstimate, dialog: &str) -> bool {
+    let staged = dir_size(&Path::new(directory).join(staging_dir));
+    let required = estimate.staging.saturating_sub(staged) + estimate.patch;
+    let Some(available) = fischl::utils::available(directory.to_string()) else {
+        log::warn!("Could not determine free space for {}, skipping disk space preflight", directory);
+        return true;
+    };
+    log::debug!("Disk space preflight for {}: staging {}, patch {}, already staged {}, available {}", install_name, estimate.staging, estimate.patch, staged, available);
+    if available >= required { return true; }
+
+    log::warn!("Not enough space for {}: required {}, available {}", install_name, required, available);
+    let (required, available) = (prettify_bytes(required), prettify_bytes(available));
+    show_dialog_with_callback(app, "warning", "TwintailLauncher", dialog, Some(vec!["dialogs.buttons.ok"]), None, Some(std::collections::HashMap::from([("install_name", install_name), ("required", required.as_str()), ("available", available.as_str())])));
+    false
+}
+
+fn parse_size(value: &str) -> u64 {
+    value.parse::<u64>().unwrap_or(0)
+}
+
+pub(crate) fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else { return 0; };
+    entries.flatten().map(|e| match e.metadata() { Ok(m) if m.is_dir() => dir_size(&e.path()), Ok(m) => m.len(), Err(_) => 0 }).sum()
+}
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sqlx::types::Json;
+use tauri::{AppHandle, Runtime};
+
+use crate::downloading::queue::QueueJobOutcome;
+use crate::utils::db_manager::save_integrity_report;
+use crate::utils::models::LauncherIntegrityReport;
+
+/// Staging folders the pipelines create inside the install directory, they are not part of the game.
+const SKIPPED_DIRS: [&str; 2] = ["downloading", "patching"];
+
+/// Size and modification time of every file in an install, keyed by path relative to the install directory.
+pub type InstallSnapshot = HashMap<String, (u64, Option<SystemTime>)>;
+
+/// Taken before a job starts and compared against the install once it is done to see what the job actually changed.
+pub fn snapshot_install_files(directory: &str) -> InstallSnapshot {
+    let mut files = HashMap::new();
+    let root = Path::new(directory);
+    if root.exists() { walk(root, root, &mut files); }
+    files
+}
+
+fn walk(root: &Path, dir: &Path, files: &mut InstallSnapshot) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return; };
+    for e in entries.flatten() {
+        let path = e.path();
+        let Ok(meta) = e.metadata() else { continue; };
+        if meta.is_dir() {
+            if dir == root && SKIPPED_DIRS.iter().any(|s| e.file_name() == *s) { continue; }
+            walk(root, &path, files);
+        } else if let Ok(rel) = path.strip_prefix(root) {
+            files.insert(rel.to_string_lossy().replace('\\', "/"), (meta.len(), meta.modified().ok()));
+        }
+    }
+}
+
+/// Compares the install against the snapshot taken before the job and stores the result as the install's integrity report.
+/// Anything written during the job was (re)downloaded, files that already existed are listed as changed as well.
+/// fischl does not report its hash check results, but it only rewrites files that failed them: every file a repair wrote and every existing file a resumed download rewrote.
+pub fn record_integrity_report<R: Runtime>(app: &AppHandle<R>, install_id: &str, directory: &str, job_kind: &str, outcome: &QueueJobOutcome, before: &InstallSnapshot, files_verified: usize, bytes_transferred: u64) -> LauncherIntegrityReport {
+    let after = snapshot_install_files(directory);
+    let mut redownloaded = after.iter().filter(|(path, meta)| before.get(*path) != Some(*meta)).map(|(path, _)| path.clone()).collect::<Vec<String>>();
+    redownloaded.sort();
+    let changed = redownloaded.iter().filter(|path| before.contains_key(*path)).cloned().collect::<Vec<String>>();
+    let failed = if job_kind == "game_repair" { redownloaded.clone() } else { changed.clone() };
+
+    let report = LauncherIntegrityReport {
+        install_id: install_id.to_string(),
+        job_kind: job_kind.to_string(),
+        status: outcome_str(outcome).to_string(),
+        files_verified: files_verified as i64,
+        files_redownloaded: Json(redownloaded),
+        files_changed: Json(changed),
+        files_failed: Json(failed),
+        bytes_transferred: bytes_transferred as i64,
+        created_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64,
+    };
+    log::info!("Integrity report for {}: {} verified, {} failed hash checks, {} redownloaded, {} of them changed existing files, {} bytes transferred", install_id, report.files_verified, report.files_failed.len(), report.files_redownloaded.len(), report.files_changed.len(), bytes_transferred);
+    save_integrity_report(app, report.clone());
+    report
+}
+
+pub(crate) fn outcome_str(outcome: &QueueJobOutcome) -> &'static str {
+    match outcome { QueueJobOutcome::Completed => "completed", QueueJobOutcome::Failed => "failed", QueueJobOutcome::Cancelled => "cancelled" }
+}
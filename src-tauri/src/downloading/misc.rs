@@ -1,11 +1,12 @@
 use crate::utils::{compare_version, db_manager::get_settings, empty_dir, find_package_version, run_async_command, show_dialog_with_callback};
 use fischl::download::Extras;
-use std::collections::HashMap;
 use std::fs;
 use std::path::{Path,PathBuf};
+use std::sync::{Arc,Mutex};
 use tauri::{AppHandle, Runtime,Emitter,Manager};
 use crate::DownloadState;
 use crate::downloading::{QueueJobPayload, queue::{QueueJobKind}};
+use crate::downloading::progress::{ProgressEvent, ProgressPhase};
 
 #[cfg(target_os = "linux")]
 use crate::downloading::queue::{QueueJobOutcome};
@@ -14,9 +15,11 @@ use crate::downloading::{RunnerDownloadPayload,SteamrtDownloadPayload};
 #[cfg(target_os = "linux")]
 use crate::utils::db_manager::update_installed_runner_is_installed_by_version;
 #[cfg(target_os = "linux")]
-use fischl::compat::{download_runner, download_steamrt};
+use crate::downloading::verify::write_runner_manifest;
 #[cfg(target_os = "linux")]
-use std::sync::{Arc,Mutex};
+use crate::utils::runner_upgrades::apply_runner_upgrades;
+#[cfg(target_os = "linux")]
+use fischl::compat::{download_runner, download_steamrt};
 
 #[cfg(target_os = "linux")]
 pub fn download_or_update_steamrt3<R: Runtime>(app: &AppHandle<R>) {
@@ -55,57 +58,39 @@ pub fn download_or_update_steamrt3<R: Runtime>(app: &AppHandle<R>) {
 
 #[cfg(target_os = "linux")]
 pub fn run_steamrt3_download<R: Runtime>(app: AppHandle<R>, payload: SteamrtDownloadPayload, job_id: String) -> QueueJobOutcome {
-    let job_id = Arc::new(job_id);
-    let dlpayload: Arc<Mutex<HashMap<String, String>>> = Arc::new(Mutex::new(HashMap::new()));
     let steamrt_path = PathBuf::from(&payload.steamrt_path);
     let event_name = if payload.is_update { "update_progress" } else { "download_progress" };
     let complete_event = if payload.is_update { "update_complete" } else { "download_complete" };
-    {
-        let mut dlp = dlpayload.lock().unwrap();
-        dlp.insert("job_id".to_string(), job_id.to_string());
-        dlp.insert("name".to_string(), String::from("SteamLinuxRuntime 3"));
-        dlp.insert("progress".to_string(), "0".to_string());
-        dlp.insert("total".to_string(), "1000".to_string());
-        dlp.insert("speed".to_string(), "0".to_string());
-        dlp.insert("disk".to_string(), "0".to_string());
-        dlp.insert("install_progress".to_string(), "0".to_string());
-        dlp.insert("install_total".to_string(), "1000".to_string());
-        app.emit(event_name, dlp.clone()).unwrap();
-    }
+    let dlpayload = Arc::new(Mutex::new(ProgressEvent::new(job_id.as_str(), "SteamLinuxRuntime 3")));
+    dlpayload.lock().unwrap().emit(&app, event_name);
 
     log::debug!("Starting SteamLinuxRuntime 3 {} process", if payload.is_update { "update" } else { "download" });
     let success = run_async_command(async {
         download_steamrt(steamrt_path.clone(), steamrt_path.clone(), "steamrt3".to_string(), payload.version, {
             let app = app.clone();
             let dlpayload = dlpayload.clone();
-            let job_id = job_id.clone();
             let event_name = event_name.to_string();
             move |current, total, net_speed, disk_speed| {
                 let mut dlp = dlpayload.lock().unwrap();
-                dlp.insert("job_id".to_string(), job_id.to_string());
-                dlp.insert("name".to_string(), "SteamLinuxRuntime 3".to_string());
-                dlp.insert("progress".to_string(), current.to_string());
-                dlp.insert("total".to_string(), total.to_string());
-                dlp.insert("speed".to_string(), net_speed.to_string());
-                dlp.insert("disk".to_string(), disk_speed.to_string());
-                dlp.insert("install_progress".to_string(), "0".to_string());
-                dlp.insert("install_total".to_string(), "1000".to_string());
-                dlp.insert("phase".to_string(), "2".to_string()); // downloading phase
-                app.emit(&event_name, dlp.clone()).unwrap();
+                dlp.progress = current;
+                dlp.total = total;
+                dlp.speed = net_speed;
+                dlp.disk = disk_speed;
+                dlp.install_progress = 0;
+                dlp.install_total = 1000;
+                dlp.phase = ProgressPhase::Download;
+                dlp.emit(&app, &event_name);
             }
         }, {
             let app = app.clone();
             let dlpayload = dlpayload.clone();
-            let job_id = job_id.clone();
             let event_name = event_name.to_string();
             move |current, total| {
                 let mut dlp = dlpayload.lock().unwrap();
-                dlp.insert("job_id".to_string(), job_id.to_string());
-                dlp.insert("name".to_string(), "SteamLinuxRuntime 3".to_string());
-                dlp.insert("install_progress".to_string(), current.to_string());
-                dlp.insert("install_total".to_string(), total.to_string());
-                dlp.insert("phase".to_string(), "3".to_string()); // installing phase
-                app.emit(&event_name, dlp.clone()).unwrap();
+                dlp.install_progress = current;
+                dlp.install_total = total;
+                dlp.phase = ProgressPhase::Extract;
+                dlp.emit(&app, &event_name);
             }
         }).await
     });
@@ -159,57 +144,39 @@ pub fn download_or_update_steamrt4<R: Runtime>(app: &AppHandle<R>) {
 
 #[cfg(target_os = "linux")]
 pub fn run_steamrt4_download<R: Runtime>(app: AppHandle<R>, payload: SteamrtDownloadPayload, job_id: String) -> QueueJobOutcome {
-    let job_id = Arc::new(job_id);
-    let dlpayload: Arc<Mutex<HashMap<String, String>>> = Arc::new(Mutex::new(HashMap::new()));
     let steamrt_path = PathBuf::from(&payload.steamrt_path);
     let event_name = if payload.is_update { "update_progress" } else { "download_progress" };
     let complete_event = if payload.is_update { "update_complete" } else { "download_complete" };
-    {
-        let mut dlp = dlpayload.lock().unwrap();
-        dlp.insert("job_id".to_string(), job_id.to_string());
-        dlp.insert("name".to_string(), String::from("SteamLinuxRuntime 4"));
-        dlp.insert("progress".to_string(), "0".to_string());
-        dlp.insert("total".to_string(), "1000".to_string());
-        dlp.insert("speed".to_string(), "0".to_string());
-        dlp.insert("disk".to_string(), "0".to_string());
-        dlp.insert("install_progress".to_string(), "0".to_string());
-        dlp.insert("install_total".to_string(), "1000".to_string());
-        app.emit(event_name, dlp.clone()).unwrap();
-    }
+    let dlpayload = Arc::new(Mutex::new(ProgressEvent::new(job_id.as_str(), "SteamLinuxRuntime 4")));
+    dlpayload.lock().unwrap().emit(&app, event_name);
 
     log::debug!("Starting SteamLinuxRuntime 4 {} process", if payload.is_update { "update" } else { "download" });
     let success = run_async_command(async {
         download_steamrt(steamrt_path.clone(), steamrt_path.clone(), "steamrt4".to_string(), payload.version, {
             let app = app.clone();
             let dlpayload = dlpayload.clone();
-            let job_id = job_id.clone();
             let event_name = event_name.to_string();
             move |current, total, net_speed, disk_speed| {
                 let mut dlp = dlpayload.lock().unwrap();
-                dlp.insert("job_id".to_string(), job_id.to_string());
-                dlp.insert("name".to_string(), "SteamLinuxRuntime 4".to_string());
-                dlp.insert("progress".to_string(), current.to_string());
-                dlp.insert("total".to_string(), total.to_string());
-                dlp.insert("speed".to_string(), net_speed.to_string());
-                dlp.insert("disk".to_string(), disk_speed.to_string());
-                dlp.insert("install_progress".to_string(), "0".to_string());
-                dlp.insert("install_total".to_string(), "1000".to_string());
-                dlp.insert("phase".to_string(), "2".to_string()); // downloading phase
-                app.emit(&event_name, dlp.clone()).unwrap();
+                dlp.progress = current;
+                dlp.total = total;
+                dlp.speed = net_speed;
+                dlp.disk = disk_speed;
+                dlp.install_progress = 0;
+                dlp.install_total = 1000;
+                dlp.phase = ProgressPhase::Download;
+                dlp.emit(&app, &event_name);
             }
         }, {
             let app = app.clone();
             let dlpayload = dlpayload.clone();
-            let job_id = job_id.clone();
             let event_name = event_name.to_string();
             move |current, total| {
                 let mut dlp = dlpayload.lock().unwrap();
-                dlp.insert("job_id".to_string(), job_id.to_string());
-                dlp.insert("name".to_string(), "SteamLinuxRuntime 4".to_string());
-                dlp.insert("install_progress".to_string(), current.to_string());
-                dlp.insert("install_total".to_string(), total.to_string());
-                dlp.insert("phase".to_string(), "3".to_string()); // installing phase
-                app.emit(&event_name, dlp.clone()).unwrap();
+                dlp.install_progress = current;
+                dlp.install_total = total;
+                dlp.phase = ProgressPhase::Extract;
+                dlp.emit(&app, &event_name);
             }
         }).await
     });
@@ -228,61 +195,43 @@ pub fn run_steamrt4_download<R: Runtime>(app: AppHandle<R>, payload: SteamrtDown
 
 #[cfg(target_os = "linux")]
 pub fn run_runner_download<R: Runtime>(app: AppHandle<R>, payload: RunnerDownloadPayload, job_id: String) -> QueueJobOutcome {
-    let job_id = Arc::new(job_id);
-    let dlpayload: Arc<Mutex<HashMap<String, String>>> = Arc::new(Mutex::new(HashMap::new()));
     let runner_name = payload.runner_version.clone();
-    {
-        let mut dlp = dlpayload.lock().unwrap();
-        dlp.insert("job_id".to_string(), job_id.to_string());
-        dlp.insert("name".to_string(), runner_name.clone());
-        dlp.insert("progress".to_string(), "0".to_string());
-        dlp.insert("total".to_string(), "1000".to_string());
-        dlp.insert("speed".to_string(), "0".to_string());
-        dlp.insert("disk".to_string(), "0".to_string());
-        dlp.insert("install_progress".to_string(), "0".to_string());
-        dlp.insert("install_total".to_string(), "1000".to_string());
-        app.emit("download_progress", dlp.clone()).unwrap();
-    }
+    let dlpayload = Arc::new(Mutex::new(ProgressEvent::new(job_id.as_str(), runner_name.as_str())));
+    dlpayload.lock().unwrap().emit(&app, "download_progress");
 
     log::debug!("Starting download process for runner {}", runner_name);
     let success = run_async_command(async {
         download_runner(payload.runner_url.clone(), payload.runner_path.clone(), payload.runner_hash.clone(), true, {
             let app = app.clone();
             let dlpayload = dlpayload.clone();
-            let job_id = job_id.clone();
-            let runner_name = runner_name.clone();
             move |current, total, net_speed, disk_speed| {
                 let mut dlp = dlpayload.lock().unwrap();
-                dlp.insert("job_id".to_string(), job_id.to_string());
-                dlp.insert("name".to_string(), runner_name.clone());
-                dlp.insert("progress".to_string(), current.to_string());
-                dlp.insert("total".to_string(), total.to_string());
-                dlp.insert("speed".to_string(), net_speed.to_string());
-                dlp.insert("disk".to_string(), disk_speed.to_string());
-                dlp.insert("install_progress".to_string(), "0".to_string());
-                dlp.insert("install_total".to_string(), "1000".to_string());
-                dlp.insert("phase".to_string(), "2".to_string()); // downloading phase
-                app.emit("download_progress", dlp.clone()).unwrap();
+                dlp.progress = current;
+                dlp.total = total;
+                dlp.speed = net_speed;
+                dlp.disk = disk_speed;
+                dlp.install_progress = 0;
+                dlp.install_total = 1000;
+                dlp.phase = ProgressPhase::Download;
+                dlp.emit(&app, "download_progress");
             }
         }, {
             let app = app.clone();
             let dlpayload = dlpayload.clone();
-            let job_id = job_id.clone();
-            let runner_name = runner_name.clone();
             move |current, total| {
                 let mut dlp = dlpayload.lock().unwrap();
-                dlp.insert("job_id".to_string(), job_id.to_string());
-                dlp.insert("name".to_string(), runner_name.clone());
-                dlp.insert("install_progress".to_string(), current.to_string());
-                dlp.insert("install_total".to_string(), total.to_string());
-                dlp.insert("phase".to_string(), "3".to_string()); // installing phase
-                app.emit("download_progress", dlp.clone()).unwrap();
+                dlp.install_progress = current;
+                dlp.install_total = total;
+                dlp.phase = ProgressPhase::Extract;
+                dlp.emit(&app, "download_progress");
             }
         }).await
     });
 
     if success {
+        write_runner_manifest(&app, &payload.runner_version, &payload.runner_path);
         update_installed_runner_is_installed_by_version(&app, payload.runner_version.clone(), true);
+        apply_runner_upgrades(&app, &payload.runner_version);
         app.emit("download_complete", payload.runner_version.clone()).unwrap();
         log::info!("Finished downloading and extracting runner {}", runner_name);
         QueueJobOutcome::Completed
@@ -467,12 +416,9 @@ pub fn download_or_update_extra<R: Runtime>(app: &AppHandle<R>, path: PathBuf, p
         let entries: Vec<_> = fs::read_dir(&ap).ok().map(|r| r.filter_map(|e| e.ok()).collect()).unwrap_or_default();
         let is_effectively_empty = if package_type == "xxmi" { entries.iter().all(|e| { let name = e.file_name(); e.path().is_dir() && (name == "gimi" || name == "srmi" || name == "zzmi" || name == "himi" || name == "wwmi" || name == "ssmi" || name == "efmi") }) } else { entries.is_empty() || entries.iter().all(|e| e.file_name().to_str().unwrap().contains("Mods") || e.file_name().to_str().unwrap().contains("ShaderCache") || e.file_name() == "d3dx_user.ini") };
         if is_effectively_empty {
-                let mut dlpayload = HashMap::new();
-                dlpayload.insert("name", package_id.clone().chars().next().map(|first| first.to_uppercase().collect::<String>() + &package_id[first.len_utf8()..]).unwrap_or_default());
-                dlpayload.insert("progress", "0".to_string());
-                dlpayload.insert("total", "1000".to_string());
-                if let Some(ref jid) = job_id { if !jid.is_empty() { dlpayload.insert("job_id", jid.clone()); } }
-                app.emit("download_progress", dlpayload.clone()).unwrap();
+                let display_name = package_id.clone().chars().next().map(|first| first.to_uppercase().collect::<String>() + &package_id[first.len_utf8()..]).unwrap_or_default();
+                let dlpayload = Arc::new(Mutex::new(ProgressEvent::new(job_id.as_deref().unwrap_or_default(), display_name.as_str())));
+                dlpayload.lock().unwrap().emit(&app, "download_progress");
 
                 log::debug!("Starting download process for {package_id} ({package_type})");
                 if !ap.exists() { let _ = fs::create_dir_all(&ap); }
@@ -481,14 +427,13 @@ pub fn download_or_update_extra<R: Runtime>(app: &AppHandle<R>, path: PathBuf, p
                     let needs_append = if package_type.as_str() == "gimi" || package_type.as_str() == "srmi" || package_type.as_str() == "zzmi" || package_type.as_str() == "himi" || package_type.as_str() == "wwmi" || package_type.as_str() == "ssmi" || package_type.as_str() == "efmi" { true } else { false };
                     Extras::download_extra_package(package_id.clone(), package_type.clone(), needs_extract, false, needs_append, ap.as_path().to_str().unwrap().parse().unwrap(), {
                         let app = app.clone();
-                        let pkg_id = package_id.clone();
                         let dlpayload = dlpayload.clone();
                         move |current, total| {
-                            let mut dlpayload = dlpayload.clone();
-                            dlpayload.insert("name", pkg_id.clone().chars().next().map(|first| first.to_uppercase().collect::<String>() + &pkg_id[first.len_utf8()..]).unwrap_or_default());
-                            dlpayload.insert("progress", current.to_string());
-                            dlpayload.insert("total", total.to_string());
-                            app.emit("download_progress", dlpayload.clone()).unwrap();
+                            let mut dlp = dlpayload.lock().unwrap();
+                            dlp.progress = current;
+                            dlp.total = total;
+                            dlp.phase = ProgressPhase::Download;
+                            dlp.emit(&app, "download_progress");
                         }
                     }).await
                 });
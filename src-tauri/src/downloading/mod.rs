@@ -1,12 +1,18 @@
 use serde::{Deserialize, Serialize};
 
+pub mod bandwidth;
 pub mod connection_monitor;
+pub mod disk_space;
 pub mod download;
+pub mod integrity;
 pub mod misc;
 pub mod preload;
+pub mod progress;
 pub mod queue;
 pub mod repair;
+pub mod schedule;
 pub mod update;
+pub mod verify;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DownloadGamePayload {
@@ -41,7 +47,7 @@ pub struct XXMIDownloadPayload {
     pub is_update: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ExtrasDownloadPayload {
     pub path: String,
     pub package_id: String,
@@ -49,7 +55,7 @@ pub struct ExtrasDownloadPayload {
     pub update_mode: bool,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum QueueJobPayload {
     Game(DownloadGamePayload),
     #[cfg(target_os = "linux")]
@@ -1,5 +1,7 @@
 use crate::DownloadState;
 use crate::downloading::{DownloadGamePayload, QueueJobPayload};
+use crate::downloading::disk_space::{preflight_disk_space, SpaceEstimate};
+use crate::downloading::progress::{ProgressEvent, ProgressPhase};
 use crate::downloading::queue::{QueueJobKind, QueueJobOutcome};
 use crate::utils::db_manager::{get_install_info_by_id, get_manifest_info_by_id};
 use crate::utils::repo_manager::get_manifest;
@@ -48,18 +50,13 @@ pub fn run_game_preload<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePaylo
 
             let pmd = picked.metadata.unwrap();
             log::info!("Starting game preload for \"{}\" ({})", install.name, install.id);
+            if let Some(pg) = picked.game.as_ref() {
+                let diffs = pg.diff.iter().filter(|e| e.original_version == install.version).collect::<Vec<&DiffGameFile>>();
+                if !diffs.is_empty() && !preflight_disk_space(&h5, &install.name, &install.directory, "patching", SpaceEstimate::for_diffs(&diffs, false), "dialogs.preload_insufficient_space_required") { h5.emit("preload_complete", ()).unwrap(); return QueueJobOutcome::Failed; }
+            }
             let instn = Arc::new(install.name.replace(install.version.as_str(), pmd.version.as_str()).clone());
-            let dlpayload = Arc::new(Mutex::new(HashMap::new()));
-
-            let mut dlp = dlpayload.lock().unwrap();
-            dlp.insert("job_id", job_id.to_string());
-            dlp.insert("name", instn.to_string());
-            dlp.insert("progress", "0".to_string());
-            dlp.insert("total", "1000".to_string());
-            dlp.insert("speed", "0".to_string());
-            dlp.insert("disk", "0".to_string());
-            h5.emit("preload_progress", dlp.clone()).unwrap();
-            drop(dlp);
+            let dlpayload = Arc::new(Mutex::new(ProgressEvent::new(job_id.as_str(), instn.as_str())));
+            dlpayload.lock().unwrap().emit(&h5, "preload_progress");
 
             let cancel_token = Arc::new(AtomicBool::new(false));
             {
@@ -100,6 +97,7 @@ pub fn run_game_preload<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePaylo
                             let total_manifests = urls.len();
                             let mut ok = true;
                             for (manifest_idx, e) in urls.clone().into_iter().enumerate() {
+                                { let mut dlp = dlpayload.lock().unwrap(); dlp.files_done = manifest_idx as u64; dlp.files_total = total_manifests as u64; }
                                 let h5 = h5.clone();
                                 let cancel_token = cancel_token.clone();
                                 let cumulative_download = cumulative_download.clone();
@@ -108,25 +106,21 @@ pub fn run_game_preload<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePaylo
                                 let rslt = run_async_command(async {
                                     <Game as Sophon>::preload(e.file_url.clone(), install.version.clone(), e.file_path.clone(), install.directory.clone(), {
                                             let dlpayload = dlpayload.clone();
-                                            let instn = instn.clone();
-                                            let job_id = job_id.clone();
                                             let cumulative_download = cumulative_download.clone();
                                             let cumulative_install = cumulative_install.clone();
                                             move |download_current, _download_total, install_current, _install_total, net_speed, disk_speed, phase| {
                                                 let mut dlp = dlpayload.lock().unwrap();
                                                 let total_download_progress = cumulative_download.load(Ordering::SeqCst) + download_current;
                                                 let total_install_progress = cumulative_install.load(Ordering::SeqCst) + install_current;
-                                                dlp.insert("job_id", job_id.to_string());
-                                                dlp.insert("name", instn.to_string());
-                                                dlp.insert("progress", total_download_progress.to_string());
-                                                dlp.insert("total", total_size.to_string());
-                                                dlp.insert("speed", net_speed.to_string());
-                                                dlp.insert("disk", disk_speed.to_string());
-                                                dlp.insert("install_progress", total_install_progress.to_string());
-                                                dlp.insert("install_total", combined_install_total.to_string());
+                                                dlp.progress = total_download_progress;
+                                                dlp.total = total_size;
+                                                dlp.speed = net_speed;
+                                                dlp.disk = disk_speed;
+                                                dlp.install_progress = total_install_progress;
+                                                dlp.install_total = combined_install_total;
                                                 let effective_phase = if phase == 5 && !is_last_manifest { 2 } else { phase };
-                                                dlp.insert("phase", effective_phase.to_string());
-                                                h5.emit("preload_progress", dlp.clone()).unwrap();
+                                                dlp.phase = ProgressPhase::from_patch_code(effective_phase);
+                                                dlp.emit(&h5, "preload_progress");
                                                 drop(dlp);
                                             }
                                         }, Some(cancel_token.clone()), Some(verified_files.clone())).await
@@ -170,20 +164,16 @@ pub fn run_game_preload<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePaylo
                                 <Game as Kuro>::preload(manifest.file_url.clone(), manifest.file_path.clone(), pmd.res_list_url.clone(), install.directory.clone(), {
                                         let dlpayload = dlpayload.clone();
                                         let tmp = tmp.clone();
-                                        let instn = instn.clone();
-                                        let job_id = job_id.clone();
                                         move |download_current, download_total, install_current, install_total, net_speed, disk_speed, phase| {
                                             let mut dlp = dlpayload.lock().unwrap();
-                                            dlp.insert("job_id", job_id.to_string());
-                                            dlp.insert("name", instn.to_string());
-                                            dlp.insert("progress", download_current.to_string());
-                                            dlp.insert("total", download_total.to_string());
-                                            dlp.insert("speed", net_speed.to_string());
-                                            dlp.insert("disk", disk_speed.to_string());
-                                            dlp.insert("install_progress", install_current.to_string());
-                                            dlp.insert("install_total", install_total.to_string());
-                                            dlp.insert("phase", phase.to_string());
-                                            tmp.emit("preload_progress", dlp.clone()).unwrap();
+                                            dlp.progress = download_current;
+                                            dlp.total = download_total;
+                                            dlp.speed = net_speed;
+                                            dlp.disk = disk_speed;
+                                            dlp.install_progress = install_current;
+                                            dlp.install_total = install_total;
+                                            dlp.phase = ProgressPhase::from_patch_code(phase);
+                                            dlp.emit(&tmp, "preload_progress");
                                             drop(dlp);
                                         }
                                     }, Some(cancel_token.clone()), Some(verified_files.clone())).await
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Bumped whenever a field of `ProgressEvent` is renamed, removed or changes meaning. Adding fields does not bump it.
+pub const PROGRESS_EVENT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressPhase {
+    #[default]
+    Idle,
+    /// Checking files already on disk before downloading, fischl code 1.
+    Verify,
+    Download,
+    Install,
+    /// Checking the written files after the install step, fischl code 4.
+    Validate,
+    /// Moving files into the game directory, fischl code 5.
+    Move,
+    /// Applying diffs, the install step of diff based updates and preloads.
+    Patch,
+    /// Unpacking archives of zipped downloads.
+    Extract,
+}
+
+impl ProgressPhase {
+    /// Maps the numeric phase reported by fischl callbacks (0=idle, 1=verifying, 2=downloading, 3=installing, 4=validating, 5=moving), one variant per code.
+    /// For Sophon chunk and Kuro raw downloads the install step assembles the files, zipped downloads set `Extract` themselves.
+    pub fn from_code(code: u8) -> Self {
+        match code {
+            1 => ProgressPhase::Verify,
+            2 => ProgressPhase::Download,
+            3 => ProgressPhase::Install,
+            4 => ProgressPhase::Validate,
+            5 => ProgressPhase::Move,
+            _ => ProgressPhase::Idle,
+        }
+    }
+
+    /// Same as `from_code` but for diff based updates and preloads where the install step applies patches.
+    pub fn from_patch_code(code: u8) -> Self {
+        match ProgressPhase::from_code(code) {
+            ProgressPhase::Install => ProgressPhase::Patch,
+            p => p,
+        }
+    }
+}
+
+/// Payload of every `*_progress` event emitted by the download, update, repair and preload pipelines.
+/// Byte counters are absolute, `speed` and `disk` are bytes per second.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProgressEvent {
+    pub version: u32,
+    pub job_id: String,
+    pub name: String,
+    pub phase: ProgressPhase,
+    pub progress: u64,
+    pub total: u64,
+    pub speed: u64,
+    pub disk: u64,
+    pub install_progress: u64,
+    pub install_total: u64,
+    /// Archives or manifests processed so far, 0 when the pipeline works on a single one.
+    pub files_done: u64,
+    pub files_total: u64,
+    pub eta_secs: Option<u64>,
+}
+
+impl ProgressEvent {
+    pub fn new(job_id: &str, name: &str) -> Self {
+        ProgressEvent { version: PROGRESS_EVENT_VERSION, job_id: job_id.to_string(), name: name.to_string(), total: 1000, install_total: 1000, ..Default::default() }
+    }
+
+    /// Estimated seconds left for the current phase, based on the last reported speed.
+    pub fn compute_eta(&self) -> Option<u64> {
+        let (done, total, rate) = match self.phase {
+            ProgressPhase::Download => (self.progress, self.total, self.speed),
+            ProgressPhase::Install | ProgressPhase::Patch | ProgressPhase::Extract | ProgressPhase::Validate | ProgressPhase::Move => (self.install_progress, self.install_total, self.disk),
+            _ => return None,
+        };
+        if rate == 0 || total == 0 { return None; }
+        Some(total.saturating_sub(done) / rate)
+    }
+
+    pub fn emit<R: Runtime>(&mut self, app: &AppHandle<R>, event: &str) {
+        self.eta_secs = self.compute_eta();
+        app.emit(event, &*self).unwrap();
+    }
+}
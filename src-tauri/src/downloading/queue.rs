@@ -2,29 +2,57 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Runtime, Emitter};
 
 use crate::downloading::QueueJobPayload;
-use crate::utils::db_manager::{get_install_info_by_id,get_manifest_info_by_id};
+use crate::downloading::bandwidth::{apply_speed_limit, is_game_running, BandwidthLimits};
+use crate::downloading::schedule::DownloadSchedule;
+use crate::utils::db_manager::{get_download_queue_jobs,get_install_info_by_id,get_manifest_info_by_id,get_settings,replace_download_queue_jobs};
+use crate::utils::models::LauncherQueueJob;
 use crate::utils::repo_manager::get_manifest;
 
 static JOB_COUNTER: AtomicU64 = AtomicU64::new(1);
 
-#[derive(Clone, Copy, Debug, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum QueueJobKind {
     GameDownload,
     GameUpdate,
     GamePreload,
     GameRepair,
+    GameVerify,
     RunnerDownload,
+    RunnerVerify,
     SteamrtDownload,
     Steamrt4Download,
     XxmiDownload,
     ExtrasDownload,
 }
 
+/// Jobs only compete for slots with other jobs in the same lane, so a small runner or extras
+/// download does not have to wait behind a full game download.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueLane {
+    Game,
+    Misc,
+}
+
+impl QueueJobKind {
+    pub fn lane(&self) -> QueueLane {
+        match self {
+            QueueJobKind::GameDownload | QueueJobKind::GameUpdate | QueueJobKind::GamePreload | QueueJobKind::GameRepair | QueueJobKind::GameVerify => QueueLane::Game,
+            QueueJobKind::RunnerDownload | QueueJobKind::RunnerVerify | QueueJobKind::SteamrtDownload | QueueJobKind::Steamrt4Download | QueueJobKind::XxmiDownload | QueueJobKind::ExtrasDownload => QueueLane::Misc,
+        }
+    }
+
+    /// Local only jobs keep running while the queue is held for a lost connection or a closed download window.
+    pub fn needs_network(&self) -> bool {
+        !matches!(self, QueueJobKind::GameVerify)
+    }
+}
+
 #[derive(Debug)]
 pub struct QueueJob {
     pub id: String,
@@ -32,7 +60,7 @@ pub struct QueueJob {
     pub payload: QueueJobPayload,
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum QueueJobStatus {
     Queued,
@@ -48,17 +76,28 @@ pub enum QueueJobStatus {
 pub struct QueueJobView {
     pub id: String,
     pub kind: QueueJobKind,
+    pub lane: QueueLane,
     pub install_id: String,
     pub name: String,
     pub status: QueueJobStatus,
 }
 
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueLaneState {
+    pub lane: QueueLane,
+    pub max_concurrent: usize,
+    pub running: usize,
+}
+
 #[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct QueueStatePayload {
     pub max_concurrent: usize,
+    pub lanes: Vec<QueueLaneState>,
     pub paused: bool,
     pub auto_paused: bool,
+    pub next_window_start: Option<i64>,
     pub running: Vec<QueueJobView>,
     pub queued: Vec<QueueJobView>,
     pub completed: Vec<QueueJobView>,
@@ -106,11 +145,23 @@ impl DownloadQueueHandle {
         rx.recv().unwrap_or(false)
     }
 
+    pub fn set_max_concurrent(&self, lane: QueueLane, max_concurrent: usize) {
+        let _ = self.tx.send(QueueCommand::SetMaxConcurrent(lane, max_concurrent));
+    }
+
+    pub fn set_bandwidth(&self, limits: BandwidthLimits) {
+        let _ = self.tx.send(QueueCommand::SetBandwidth(limits));
+    }
+
+    pub fn set_schedule(&self, schedule: DownloadSchedule) {
+        let _ = self.tx.send(QueueCommand::SetSchedule(schedule));
+    }
+
     pub fn set_paused(&self, paused: bool) {
         let _ = self.tx.send(QueueCommand::SetPaused(paused));
     }
 
-    pub fn activate_job(&self, job_id: String) -> Option<String> {
+    pub fn activate_job(&self, job_id: String) -> Option<(String, QueueLane)> {
         let (tx, rx) = std::sync::mpsc::channel();
         let _ = self.tx.send(QueueCommand::ActivateJob(job_id, tx));
         rx.recv().unwrap_or(None)
@@ -168,14 +219,16 @@ impl DownloadQueueHandle {
 #[allow(unused)]
 pub enum QueueCommand {
     Enqueue(QueueJob),
-    SetMaxConcurrent(usize),
+    SetMaxConcurrent(QueueLane, usize),
+    SetSchedule(DownloadSchedule),
+    SetBandwidth(BandwidthLimits),
     SetPaused(bool),
     SetPausing(String, bool),
     MoveUp(String, std::sync::mpsc::Sender<bool>),
     MoveDown(String, std::sync::mpsc::Sender<bool>),
     Remove(String, std::sync::mpsc::Sender<bool>),
     RemoveByInstallId(String, std::sync::mpsc::Sender<bool>),
-    ActivateJob(String, std::sync::mpsc::Sender<Option<String>>),
+    ActivateJob(String, std::sync::mpsc::Sender<Option<(String, QueueLane)>>),
     Reorder(String, usize, std::sync::mpsc::Sender<bool>),
     GetState(std::sync::mpsc::Sender<QueueStatePayload>),
     ResumeJob(String, std::sync::mpsc::Sender<bool>),
@@ -194,29 +247,77 @@ pub enum QueueJobOutcome {
     Cancelled,
 }
 
-fn emit_queue_state<R: Runtime>(app: &AppHandle<R>, max_concurrent: usize, paused: bool, auto_paused: bool, active: &HashMap<String, QueueJobView>, queued: &VecDeque<QueueJobView>, completed: &VecDeque<QueueJobView>, paused_jobs: &HashMap<String, QueueJobView>, pausing_installs: &HashSet<String>) {
-    let payload = QueueStatePayload {
-        max_concurrent,
-        paused,
-        auto_paused,
+fn build_queue_state(lane_limits: &HashMap<QueueLane, usize>, schedule: &DownloadSchedule, paused: bool, auto_paused: bool, active: &HashMap<String, QueueJobView>, queued: &VecDeque<QueueJobView>, completed: &VecDeque<QueueJobView>, paused_jobs: &HashMap<String, QueueJobView>, pausing_installs: &HashSet<String>) -> QueueStatePayload {
+    let lanes: Vec<QueueLaneState> = [QueueLane::Game, QueueLane::Misc].into_iter().map(|lane| QueueLaneState { lane, max_concurrent: lane_limit(lane_limits, lane), running: active.values().filter(|v| v.lane == lane).count() }).collect();
+    // Being outside the download window looks like an auto-pause to the frontend
+    let outside_window = !schedule.is_open_now();
+    QueueStatePayload {
+        max_concurrent: lanes.iter().map(|l| l.max_concurrent).sum(),
+        lanes,
+        paused: paused || outside_window,
+        auto_paused: auto_paused || outside_window,
+        next_window_start: if outside_window { schedule.next_window_start_now() } else { None },
         running: active.values().cloned().collect(),
         queued: queued.iter().cloned().collect(),
         completed: completed.iter().cloned().collect(),
         paused_jobs: paused_jobs.values().cloned().collect(),
         pausing_installs: pausing_installs.iter().cloned().collect(),
-    };
+    }
+}
+
+fn emit_queue_state<R: Runtime>(app: &AppHandle<R>, lane_limits: &HashMap<QueueLane, usize>, schedule: &DownloadSchedule, paused: bool, auto_paused: bool, active: &HashMap<String, QueueJobView>, queued: &VecDeque<QueueJobView>, completed: &VecDeque<QueueJobView>, paused_jobs: &HashMap<String, QueueJobView>, pausing_installs: &HashSet<String>) {
+    let payload = build_queue_state(lane_limits, schedule, paused, auto_paused, active, queued, completed, paused_jobs, pausing_installs);
     let _ = app.emit("download_queue_state", payload);
 }
 
-pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_concurrent: usize, run_job: fn(AppHandle<R>, QueueJob) -> QueueJobOutcome) -> DownloadQueueHandle {
+fn lane_limit(lane_limits: &HashMap<QueueLane, usize>, lane: QueueLane) -> usize {
+    lane_limits.get(&lane).copied().unwrap_or(1).max(1)
+}
+
+pub(crate) fn enum_to_db_str<T: Serialize>(value: &T) -> String {
+    serde_json::to_value(value).ok().and_then(|v| v.as_str().map(|s| s.to_string())).unwrap_or_default()
+}
+
+pub(crate) fn enum_from_db_str<T: for<'de> Deserialize<'de>>(value: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string())).ok()
+}
+
+fn to_queue_record(view: &QueueJobView, job: Option<&QueueJob>, position: usize) -> LauncherQueueJob {
+    LauncherQueueJob {
+        id: view.id.clone(),
+        kind: enum_to_db_str(&view.kind),
+        install_id: view.install_id.clone(),
+        name: view.name.clone(),
+        status: enum_to_db_str(&view.status),
+        payload: job.and_then(|j| serde_json::to_string(&j.payload).ok()),
+        position: position as i64,
+    }
+}
+
+/// Flattens the worker state into rows for the `download_queue` table.
+/// Running jobs come first so they are picked up before anything else when restored.
+fn snapshot_queue(active: &HashMap<String, QueueJobView>, active_jobs: &HashMap<String, QueueJob>, queued: &VecDeque<QueueJob>, queued_views: &VecDeque<QueueJobView>, completed: &VecDeque<QueueJobView>, paused_jobs: &HashMap<String, QueueJobView>, paused_jobs_data: &HashMap<String, QueueJob>) -> Vec<LauncherQueueJob> {
+    let mut rows = Vec::new();
+    let mut running: Vec<&QueueJobView> = active.values().collect();
+    running.sort_by(|a, b| a.id.cmp(&b.id));
+    for view in running { rows.push(to_queue_record(view, active_jobs.get(&view.id), rows.len())); }
+    for (job, view) in queued.iter().zip(queued_views.iter()) { rows.push(to_queue_record(view, Some(job), rows.len())); }
+    let mut paused: Vec<&QueueJobView> = paused_jobs.values().collect();
+    paused.sort_by(|a, b| a.id.cmp(&b.id));
+    for view in paused { rows.push(to_queue_record(view, paused_jobs_data.get(&view.install_id), rows.len())); }
+    for view in completed.iter() { rows.push(to_queue_record(view, None, rows.len())); }
+    rows
+}
+
+pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_lane_limits: HashMap<QueueLane, usize>, run_job: fn(AppHandle<R>, QueueJob) -> QueueJobOutcome) -> DownloadQueueHandle {
     let (tx, rx) = std::sync::mpsc::channel::<QueueCommand>();
     let (done_tx, done_rx) = std::sync::mpsc::channel::<(String, QueueJobOutcome)>();
 
     std::thread::spawn(move || {
-        let mut max_concurrent = initial_max_concurrent.max(1);
+        let mut lane_limits = initial_lane_limits;
         let mut paused = false;
         let mut auto_paused = false; // True if paused due to connection loss (not manual)
-        let mut activating = false; // Flag to prevent auto-pause during job activation
+        let mut activating: Option<String> = None; // Job being activated, prevents auto-pause while other jobs get cancelled
         let mut queued: VecDeque<QueueJob> = VecDeque::new();
         let mut queued_views: VecDeque<QueueJobView> = VecDeque::new();
         let mut active: HashMap<String, QueueJobView> = HashMap::new();
@@ -225,8 +326,52 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
         let mut paused_jobs: HashMap<String, QueueJobView> = HashMap::new(); // Jobs paused by user (keyed by install_id)
         let mut paused_jobs_data: HashMap<String, QueueJob> = HashMap::new(); // Job data for paused jobs
         let mut pausing_installs: HashSet<String> = HashSet::new(); // Installs currently transitioning to paused
+        let mut persisted: Vec<LauncherQueueJob> = Vec::new(); // Last snapshot written to the database
+        let mut schedule = get_settings(&app).map(|s| DownloadSchedule::from_settings(&s)).unwrap_or_default();
+        let mut outside_window = !schedule.is_open_now(); // True while the download schedule does not allow starting jobs
+        let mut bandwidth = get_settings(&app).map(|s| BandwidthLimits::from_settings(&s)).unwrap_or_default();
+        let mut applied_limit: Option<u64> = None; // Last limit pushed to fischl
+
+        // Restore whatever was left in the queue when the launcher was closed
+        if let Some(saved) = get_download_queue_jobs(&app) {
+            for row in saved {
+                let (Some(kind), Some(status)) = (enum_from_db_str::<QueueJobKind>(&row.kind), enum_from_db_str::<QueueJobStatus>(&row.status)) else { continue; };
+                let job_id = format!("job_{}", JOB_COUNTER.fetch_add(1, Ordering::Relaxed));
+                let mut view = QueueJobView { id: job_id.clone(), kind, lane: kind.lane(), install_id: row.install_id, name: row.name, status };
+                match view.status {
+                    QueueJobStatus::Completed | QueueJobStatus::Failed | QueueJobStatus::Cancelled => completed_views.push_back(view),
+                    _ => {
+                        let Some(payload) = row.payload.and_then(|p| serde_json::from_str::<QueueJobPayload>(&p).ok()) else { log::warn!("Dropping saved {:?} job for {}: payload could not be restored", kind, view.install_id); continue; };
+                        // Install may have been removed while the launcher was closed
+                        if let QueueJobPayload::Game(ref p) = payload { if get_install_info_by_id(&app, p.install.clone()).is_none() { continue; } }
+                        let job = QueueJob { id: job_id, kind, payload };
+                        if let QueueJobStatus::Paused = view.status {
+                            paused_jobs_data.insert(view.install_id.clone(), job);
+                            paused_jobs.insert(view.install_id.clone(), view);
+                        } else {
+                            view.status = QueueJobStatus::Queued;
+                            queued.push_back(job);
+                            queued_views.push_back(view);
+                        }
+                    }
+                }
+            }
+            if !queued.is_empty() || !paused_jobs.is_empty() {
+                paused = !get_settings(&app).map(|s| s.queue_auto_resume).unwrap_or(true);
+                log::info!("Restored {} queued and {} paused download job(s) from previous session{}", queued.len(), paused_jobs.len(), if paused { ", queue is paused" } else { "" });
+            }
+            persisted = snapshot_queue(&active, &active_jobs, &queued, &queued_views, &completed_views, &paused_jobs, &paused_jobs_data);
+            replace_download_queue_jobs(&app, persisted.clone());
+            emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+        }
 
         loop {
+            if outside_window == schedule.is_open_now() {
+                outside_window = !outside_window;
+                log::info!("Download window {}", if outside_window { "closed, holding queued jobs" } else { "opened, resuming queue" });
+                emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+            }
+
             while let Ok((job_id, outcome)) = done_rx.try_recv() {
                 if let Some(mut view) = active.remove(&job_id) {
                     let removed_job = active_jobs.remove(&job_id);
@@ -251,7 +396,7 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                         }
                         QueueJobOutcome::Cancelled => {
                             // When cancelled during activation, put the job back in queue
-                            if activating {
+                            if activating.is_some() {
                                 if let Some(job) = removed_job {
                                     log::debug!("Job {} ({:?}) cancelled for requeue during activation", job_id, view.kind);
                                     // Put the cancelled job back at the front of the queue (after the activating job)
@@ -274,14 +419,17 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                         }
                     };
                 }
-                emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
             }
 
-            // Only auto-start next job if not paused
-            if !paused {
-                while active.len() < max_concurrent {
-                    let Some(job) = queued.pop_front() else { break; };
-                    let Some(mut view) = queued_views.pop_front() else { break; };
+            // Only auto-start next job if not paused and inside the download window, verify only jobs just need the queue to not be paused by the user
+            let network_held = paused || outside_window;
+            if !paused || auto_paused {
+                loop {
+                    // Pick the first queued job whose lane still has a free slot
+                    let Some(idx) = queued.iter().position(|j| { let lane = j.kind.lane(); (!network_held || !j.kind.needs_network()) && active.values().filter(|v| v.lane == lane).count() < lane_limit(&lane_limits, lane) }) else { break; };
+                    let Some(job) = queued.remove(idx) else { break; };
+                    let Some(mut view) = queued_views.remove(idx) else { break; };
 
                     view.status = QueueJobStatus::Running;
                     let job_id = job.id.clone();
@@ -289,10 +437,10 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                     active.insert(job_id.clone(), view);
                     active_jobs.insert(job_id.clone(), QueueJob { id: job.id.clone(), kind: job.kind, payload: job.payload.clone() });
 
-                    // Clear the activating flag since the new job is now starting
-                    activating = false;
+                    // Clear the activating flag once the activated job is actually starting
+                    if activating.as_deref() == Some(job_id.as_str()) { activating = None; }
 
-                    emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                    emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
 
                     let app2 = app.clone();
                     let done_tx2 = done_tx.clone();
@@ -306,36 +454,59 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                 }
             }
 
+            // Running jobs or games may have changed since the last pass, keep the limiter in sync
+            let limit = bandwidth.effective(active.values().map(|v| v.kind).filter(|k| k.needs_network()), is_game_running());
+            if applied_limit != Some(limit) {
+                log::debug!("Download speed limit set to {}", if limit == 0 { "unlimited".to_string() } else { format!("{} KB/s", limit) });
+                apply_speed_limit(limit);
+                applied_limit = Some(limit);
+            }
+
             match rx.recv_timeout(Duration::from_millis(200)) {
                 Ok(cmd) => match cmd {
                     QueueCommand::Enqueue(job) => {
                         let install_id = job.payload.get_id();
+                        // Startup checks may re-enqueue something that was already restored from the database
+                        if queued_views.iter().any(|v| v.kind == job.kind && v.install_id == install_id) || paused_jobs.get(&install_id).is_some_and(|v| v.kind == job.kind) {
+                            log::debug!("Skipping duplicate {:?} job for install {}", job.kind, install_id);
+                            continue;
+                        }
                         let name = if let (QueueJobKind::GamePreload, QueueJobPayload::Game(p)) = (&job.kind, &job.payload) { get_install_info_by_id(&app, p.install.clone()).map(|install| { let fallback = install.name.clone(); let ver = install.version.clone(); get_manifest_info_by_id(&app, install.manifest_id.clone()).and_then(|gid| get_manifest(&app, gid.filename)).and_then(|gm| gm.extra.preload).and_then(|pl| pl.metadata).map(|pmd| fallback.replace(ver.as_str(), pmd.version.as_str())).unwrap_or(fallback) }) } else if let QueueJobPayload::Game(ref p) = job.payload { get_install_info_by_id(&app, p.install.clone()).map(|i| i.name) } else { None }.unwrap_or_else(|| job.payload.get_name());
                         log::info!("Enqueued {:?} job {} for install {}", job.kind, job.id, install_id);
                         queued_views.push_back(QueueJobView {
                             id: job.id.clone(),
                             kind: job.kind,
+                            lane: job.kind.lane(),
                             install_id,
                             name,
                             status: QueueJobStatus::Queued,
                         });
                         queued.push_back(job);
-                        emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                        emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                    }
+                    QueueCommand::SetMaxConcurrent(lane, n) => {
+                        log::info!("Queue {:?} lane now allows {} concurrent job(s)", lane, n.max(1));
+                        lane_limits.insert(lane, n.max(1));
+                        emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                     }
-                    QueueCommand::SetMaxConcurrent(n) => {
-                        max_concurrent = n.max(1);
-                        emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                    QueueCommand::SetBandwidth(limits) => {
+                        bandwidth = limits;
+                        applied_limit = None;
+                    }
+                    QueueCommand::SetSchedule(s) => {
+                        schedule = s;
+                        emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                     }
                     QueueCommand::SetPaused(p) => {
                         log::info!("Queue {} manually", if p { "paused" } else { "resumed" });
                         paused = p;
                         // Clear auto_paused when user manually changes pause state
                         if !p { auto_paused = false; }
-                        emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                        emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                     }
                     QueueCommand::SetPausing(install_id, is_pausing) => {
                         if is_pausing { pausing_installs.insert(install_id); } else { pausing_installs.remove(&install_id); }
-                        emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                        emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                     }
                     QueueCommand::MoveUp(job_id, reply) => {
                         let mut success = false;
@@ -344,7 +515,7 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                                 queued.swap(idx, idx - 1);
                                 queued_views.swap(idx, idx - 1);
                                 success = true;
-                                emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                                emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                             }
                         }
                         let _ = reply.send(success);
@@ -356,7 +527,7 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                                 queued.swap(idx, idx + 1);
                                 queued_views.swap(idx, idx + 1);
                                 success = true;
-                                emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                                emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                             }
                         }
                         let _ = reply.send(success);
@@ -368,7 +539,7 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                             queued.remove(idx);
                             queued_views.remove(idx);
                             success = true;
-                            emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                            emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                         }
                         let _ = reply.send(success);
                     }
@@ -418,7 +589,7 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
 
                         if removed_any {
                             for jid in removed_job_ids { let _ = app.emit("download_removed", jid); }
-                            emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                            emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                         }
                         let _ = reply.send(removed_any);
                     }
@@ -431,7 +602,7 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                             queued.insert(insert_pos, job);
                             queued_views.insert(insert_pos, view);
                             success = true;
-                            emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                            emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                         }
                         let _ = reply.send(success);
                     }
@@ -451,28 +622,19 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                             let job = queued.remove(idx).unwrap();
                             let view = queued_views.remove(idx).unwrap();
                             log::info!("Activating {:?} job {} for install {}", job.kind, job_id, view.install_id);
-                            install_id = Some(view.install_id.clone());
+                            install_id = Some((view.install_id.clone(), view.lane));
+                            // Only game lane jobs get cancelled to make room, misc jobs just jump the line
+                            if view.lane == QueueLane::Game { activating = Some(job_id.clone()); } // Prevent auto-pause when current job is cancelled
                             queued.push_front(job);
                             queued_views.push_front(view);
-                            activating = true; // Prevent auto-pause when current job is cancelled
                             paused = false; // Unpause to start this job
-                            emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                            emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                         }
                         let _ = reply.send(install_id);
                     }
                     QueueCommand::GetState(reply) => {
                         // Return current queue state for initial sync
-                        let payload = QueueStatePayload {
-                            max_concurrent,
-                            paused,
-                            auto_paused,
-                            running: active.values().cloned().collect(),
-                            queued: queued_views.iter().cloned().collect(),
-                            completed: completed_views.iter().cloned().collect(),
-                            paused_jobs: paused_jobs.values().cloned().collect(),
-                            pausing_installs: pausing_installs.iter().cloned().collect(),
-                        };
-                        let _ = reply.send(payload);
+                        let _ = reply.send(build_queue_state(&lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs));
                     }
                     QueueCommand::ResumeJob(install_id, reply) => {
                         // Resume a paused job - move it from paused to front of queue and unpause
@@ -485,14 +647,14 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                                 queued_views.push_front(view);
                                 paused = false; // Unpause to start this job
                                 success = true;
-                                emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                                emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                             }
                         }
                         let _ = reply.send(success);
                     }
                     QueueCommand::ClearCompleted => {
                         completed_views.clear();
-                        emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                        emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                     }
                     QueueCommand::AutoPause => {
                         // Auto-pause due to connection loss - only if not already paused
@@ -500,7 +662,7 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                             log::info!("Queue auto-paused due to connection loss");
                             paused = true;
                             auto_paused = true;
-                            emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                            emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                         }
                     }
                     QueueCommand::AutoResume(reply) => {
@@ -509,7 +671,7 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                             log::info!("Queue auto-resumed after connection restored");
                             auto_paused = false;
                             paused = false;
-                            emit_queue_state(&app, max_concurrent, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
+                            emit_queue_state(&app, &lane_limits, &schedule, paused, auto_paused, &active, &queued_views, &completed_views, &paused_jobs, &pausing_installs);
                             true
                         } else { false };
                         let _ = reply.send(success);
@@ -526,6 +688,13 @@ pub fn start_download_queue_worker<R: Runtime>(app: AppHandle<R>, initial_max_co
                 Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {}
                 Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
             }
+
+            // Keep the database copy of the queue in sync so it survives a restart
+            let snapshot = snapshot_queue(&active, &active_jobs, &queued, &queued_views, &completed_views, &paused_jobs, &paused_jobs_data);
+            if snapshot != persisted {
+                replace_download_queue_jobs(&app, snapshot.clone());
+                persisted = snapshot;
+            }
         }
     });
     DownloadQueueHandle { tx }
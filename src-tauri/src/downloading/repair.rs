@@ -1,6 +1,9 @@
 use crate::DownloadState;
 use crate::downloading::queue::{QueueJobKind, QueueJobOutcome};
 use crate::downloading::{DownloadGamePayload, QueueJobPayload};
+use crate::downloading::integrity::{record_integrity_report, snapshot_install_files};
+use crate::downloading::progress::{ProgressEvent, ProgressPhase};
+use crate::downloading::verify::cache_file_manifest;
 use crate::utils::db_manager::{get_install_info_by_id, get_manifest_info_by_id};
 use crate::utils::repo_manager::get_manifest;
 use crate::utils::{models::{FullGameFile, GameVersion}, run_async_command, show_dialog_with_callback};
@@ -53,16 +56,8 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
     };
 
     let tmp = Arc::new(h5.clone());
-    let instn = Arc::new(i.name.clone());
-    let dlpayload = Arc::new(Mutex::new(HashMap::new()));
-
-    let mut dlp = dlpayload.lock().unwrap();
-    dlp.insert("job_id", job_id.to_string());
-    dlp.insert("name", i.name.clone());
-    dlp.insert("progress", "0".to_string());
-    dlp.insert("total", "1000".to_string());
-    h5.emit("repair_progress", dlp.clone()).unwrap();
-    drop(dlp);
+    let dlpayload = Arc::new(Mutex::new(ProgressEvent::new(job_id.as_str(), i.name.as_str())));
+    dlpayload.lock().unwrap().emit(&h5, "repair_progress");
 
     let cancel_token = Arc::new(AtomicBool::new(false));
     {
@@ -76,6 +71,7 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
         let mut vf = state.verified_files.lock().unwrap();
         vf.entry(payload.install.clone()).or_insert_with(|| Arc::new(Mutex::new(std::collections::HashSet::new()))).clone()
     };
+    let files_before = snapshot_install_files(&i.directory);
 
     let mut success = false;
     match picked.metadata.download_mode.as_str() {
@@ -90,7 +86,8 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
             let combined_install_total: u64 = files.iter().map(|e| e.decompressed_size.parse::<u64>().unwrap_or(0)).sum();
             let cumulative_download = Arc::new(std::sync::atomic::AtomicU64::new(0));
             let mut ok = true;
-            for e in files.iter() {
+            for (file_idx, e) in files.iter().enumerate() {
+                { let mut dlp = dlpayload.lock().unwrap(); dlp.files_done = file_idx as u64; dlp.files_total = files.len() as u64; }
                 let url = e.file_url.clone();
                 let hash = e.file_hash.clone();
                 let cancel_token = cancel_token.clone();
@@ -98,22 +95,18 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                     <Game as Zipped>::download(url.clone(), hash.clone(), i.directory.clone(), false, true, {
                             let dlpayload = dlpayload.clone();
                             let h5 = h5.clone();
-                            let instn = instn.clone();
-                            let job_id = job_id.clone();
                             let cumulative_download = cumulative_download.clone();
                             move |current, _total, net_speed, disk_speed| {
                                 let mut dlp = dlpayload.lock().unwrap();
                                 let total_dl_progress = cumulative_download.load(Ordering::SeqCst) + current;
-                                dlp.insert("job_id", job_id.to_string());
-                                dlp.insert("name", instn.to_string());
-                                dlp.insert("progress", total_dl_progress.to_string());
-                                dlp.insert("total", combined_download_total.to_string());
-                                dlp.insert("speed", net_speed.to_string());
-                                dlp.insert("disk", disk_speed.to_string());
-                                dlp.insert("install_progress", "0".to_string());
-                                dlp.insert("install_total", combined_install_total.to_string());
-                                dlp.insert("phase", "2".to_string());
-                                h5.emit("repair_progress", dlp.clone()).unwrap();
+                                dlp.progress = total_dl_progress;
+                                dlp.total = combined_download_total;
+                                dlp.speed = net_speed;
+                                dlp.disk = disk_speed;
+                                dlp.install_progress = 0;
+                                dlp.install_total = combined_install_total;
+                                dlp.phase = ProgressPhase::Download;
+                                dlp.emit(&h5, "repair_progress");
                                 drop(dlp);
                             }
                         }, Some(cancel_token.clone()), Some(verified_files.clone())).await
@@ -131,16 +124,12 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                 let ext = fischl::utils::extract_archive_with_progress(far, i.directory.clone(), false, None, {
                     let dlpayload = dlpayload.clone();
                     let h5 = h5.clone();
-                    let instn = instn.clone();
-                    let job_id = job_id.clone();
                     move |current, total| {
                         let mut dlp = dlpayload.lock().unwrap();
-                        dlp.insert("job_id", job_id.to_string());
-                        dlp.insert("name", instn.to_string());
-                        dlp.insert("install_progress", current.to_string());
-                        dlp.insert("install_total", total.to_string());
-                        dlp.insert("phase", "3".to_string());
-                        h5.emit("repair_progress", dlp.clone()).unwrap();
+                        dlp.install_progress = current;
+                        dlp.install_total = total;
+                        dlp.phase = ProgressPhase::Extract;
+                        dlp.emit(&h5, "repair_progress");
                     }
                 });
                 if ext {
@@ -171,6 +160,7 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
             let total_manifests = urls.len();
             let mut ok = true;
             for (manifest_idx, e) in urls.clone().into_iter().enumerate() {
+                { let mut dlp = dlpayload.lock().unwrap(); dlp.files_done = manifest_idx as u64; dlp.files_total = total_manifests as u64; }
                 let h5 = h5.clone();
                 let cancel_token = cancel_token.clone();
                 let cumulative_download = cumulative_download.clone();
@@ -179,30 +169,24 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                 let rslt = run_async_command(async {
                     <Game as Sophon>::repair_game(e.file_url.clone(), e.file_path.clone(), i.directory.clone(), i.skip_hash_check.clone(), {
                         let dlpayload = dlpayload.clone();
-                        let instn = instn.clone();
-                        let job_id = job_id.clone();
                         let cumulative_download = cumulative_download.clone();
                         let cumulative_install = cumulative_install.clone();
                         move |download_current, _download_total, install_current, _install_total, net_speed, disk_speed, phase| {
                             let mut dlp = dlpayload.lock().unwrap();
-                            let instn = instn.to_string();
                             // Add cumulative progress from previous manifests to current progress
                             let total_download_progress = cumulative_download.load(Ordering::SeqCst) + download_current;
                             let total_install_progress = cumulative_install.load(Ordering::SeqCst) + install_current;
-                            dlp.insert("job_id", job_id.to_string());
-                            dlp.insert("name", instn.clone());
-                            dlp.insert("progress", total_download_progress.to_string());
-                            dlp.insert("total", combined_download_total.to_string());
-                            dlp.insert("speed", net_speed.to_string());
-                            dlp.insert("disk", disk_speed.to_string());
+                            dlp.progress = total_download_progress;
+                            dlp.total = combined_download_total;
+                            dlp.speed = net_speed;
+                            dlp.disk = disk_speed;
                             // Include install progress in same event to avoid flickering
-                            dlp.insert("install_progress", total_install_progress.to_string());
-                            dlp.insert("install_total", combined_install_total.to_string());
-                            // Phase: 0=idle, 1=verifying, 2=downloading, 3=installing, 4=validating, 5=moving
+                            dlp.install_progress = total_install_progress;
+                            dlp.install_total = combined_install_total;
                             // Override phase 5 (moving) to phase 2 (downloading) if not on last manifest
                             let effective_phase = if phase == 5 && !is_last_manifest { 2 } else { phase };
-                            dlp.insert("phase", effective_phase.to_string());
-                            h5.emit("repair_progress", dlp.clone()).unwrap();
+                            dlp.phase = ProgressPhase::from_code(effective_phase);
+                            dlp.emit(&h5, "repair_progress");
                             drop(dlp);
                         }
                     }, Some(cancel_token.clone()), Some(verified_files.clone())).await
@@ -237,20 +221,16 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
             let rslt = run_async_command(async {
                 <Game as Kuro>::repair_game(manifest.to_owned(), picked.metadata.res_list_url.clone(), i.directory.clone(), i.skip_hash_check.clone(), {
                         let dlpayload = dlpayload.clone();
-                        let job_id = job_id.clone();
                         move |download_current, download_total, install_current, install_total, net_speed, disk_speed, phase| {
                             let mut dlp = dlpayload.lock().unwrap();
-                            dlp.insert("job_id", job_id.to_string());
-                            dlp.insert("name", instn.to_string());
-                            dlp.insert("progress", download_current.to_string());
-                            dlp.insert("total", download_total.to_string());
-                            dlp.insert("speed", net_speed.to_string());
-                            dlp.insert("disk", disk_speed.to_string());
-                            dlp.insert("install_progress", install_current.to_string());
-                            dlp.insert("install_total", install_total.to_string());
-                            // Phase: 0=idle, 1=verifying, 2=downloading, 3=installing, 4=validating, 5=moving
-                            dlp.insert("phase", phase.to_string());
-                            tmp.emit("repair_progress", dlp.clone()).unwrap();
+                            dlp.progress = download_current;
+                            dlp.total = download_total;
+                            dlp.speed = net_speed;
+                            dlp.disk = disk_speed;
+                            dlp.install_progress = install_current;
+                            dlp.install_total = install_total;
+                            dlp.phase = ProgressPhase::from_code(phase);
+                            dlp.emit(&tmp, "repair_progress");
                             drop(dlp);
                         }
                     }, Some(cancel_token.clone()), Some(verified_files.clone())).await
@@ -280,7 +260,8 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
             let cumulative_install = Arc::new(std::sync::atomic::AtomicU64::new(0));
             let total_files = files.len();
             let mut ok = true;
-            for (_file_idx, e) in files.iter().enumerate() {
+            for (file_idx, e) in files.iter().enumerate() {
+                { let mut dlp = dlpayload.lock().unwrap(); dlp.files_done = file_idx as u64; dlp.files_total = total_files as u64; }
                 let url = e.file_url.clone();
                 let hash = e.file_hash.clone();
                 let cancel_token = cancel_token.clone();
@@ -288,22 +269,18 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                     <Game as Zipped>::download(url.clone(), hash.clone(), i.directory.clone(), false, true,{
                             let dlpayload = dlpayload.clone();
                             let h5 = h5.clone();
-                            let instn = instn.clone();
-                            let job_id = job_id.clone();
                             let cumulative_download = cumulative_download.clone();
                             move |current, _total, net_speed, disk_speed| {
                                 let mut dlp = dlpayload.lock().unwrap();
                                 let total_dl_progress = cumulative_download.load(Ordering::SeqCst) + current;
-                                dlp.insert("job_id", job_id.to_string());
-                                dlp.insert("name", instn.to_string());
-                                dlp.insert("progress", total_dl_progress.to_string());
-                                dlp.insert("total", combined_download_total.to_string());
-                                dlp.insert("speed", net_speed.to_string());
-                                dlp.insert("disk", disk_speed.to_string());
-                                dlp.insert("install_progress", "0".to_string());
-                                dlp.insert("install_total", combined_install_total.to_string());
-                                dlp.insert("phase", "2".to_string());
-                                h5.emit("repair_progress", dlp.clone()).unwrap();
+                                dlp.progress = total_dl_progress;
+                                dlp.total = combined_download_total;
+                                dlp.speed = net_speed;
+                                dlp.disk = disk_speed;
+                                dlp.install_progress = 0;
+                                dlp.install_total = combined_install_total;
+                                dlp.phase = ProgressPhase::Download;
+                                dlp.emit(&h5, "repair_progress");
                                 drop(dlp);
                             }
                         }, Some(cancel_token.clone()), Some(verified_files.clone())).await
@@ -315,6 +292,7 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                 let repairing_path = install_dir.join("repairing");
                 ok = true;
                 for (file_idx, e) in files.iter().enumerate() {
+                    { let mut dlp = dlpayload.lock().unwrap(); dlp.files_done = file_idx as u64; dlp.files_total = total_files as u64; }
                     let fnn = e.file_url.split('/').last().unwrap_or_default().to_string();
                     let archive_path = repairing_path.join("staging").join(&fnn);
                     let far = archive_path.to_str().unwrap().to_string();
@@ -324,18 +302,14 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                     let ext = fischl::utils::extract_archive_with_progress(far, i.directory.clone(), false, None, {
                         let dlpayload = dlpayload.clone();
                         let h5 = h5.clone();
-                        let instn = instn.clone();
-                        let job_id = job_id.clone();
                         let cumulative_install = cumulative_install.clone();
                         move |current, _total| {
                             let mut dlp = dlpayload.lock().unwrap();
                             let total_inst_progress = cumulative_install.load(Ordering::SeqCst) + current;
-                            dlp.insert("job_id", job_id.to_string());
-                            dlp.insert("name", instn.to_string());
-                            dlp.insert("install_progress", total_inst_progress.to_string());
-                            dlp.insert("install_total", combined_install_total.to_string());
-                            dlp.insert("phase", "3".to_string());
-                            h5.emit("repair_progress", dlp.clone()).unwrap();
+                            dlp.install_progress = total_inst_progress;
+                            dlp.install_total = combined_install_total;
+                            dlp.phase = ProgressPhase::Extract;
+                            dlp.emit(&h5, "repair_progress");
                         }
                     });
                     if !ext { ok = false; break; }
@@ -381,13 +355,10 @@ pub fn run_game_repair<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
         h5.emit("repair_paused", dlp).unwrap();
         return QueueJobOutcome::Cancelled;
     }
-    if success {
-        log::info!("Repair completed for \"{}\" ({})", i.name, i.id);
-        { verified_files.lock().unwrap().clear(); }
-        QueueJobOutcome::Completed
-    } else {
-        log::warn!("Repair failed for \"{}\" ({})", i.name, i.id);
-        { verified_files.lock().unwrap().clear(); }
-        QueueJobOutcome::Failed
-    }
+    let outcome = if success { log::info!("Repair completed for \"{}\" ({})", i.name, i.id); QueueJobOutcome::Completed } else { log::warn!("Repair failed for \"{}\" ({})", i.name, i.id); QueueJobOutcome::Failed };
+    let files_verified = { let mut vf = verified_files.lock().unwrap(); let n = vf.len(); vf.clear(); n };
+    let bytes_transferred = dlpayload.lock().unwrap().progress;
+    record_integrity_report(&h5, &i.id, &i.directory, "game_repair", &outcome, &files_before, files_verified, bytes_transferred);
+    if success { cache_file_manifest(&h5, &i.id, &i.directory, &picked.metadata.version, &files_before, true, i.skip_hash_check); }
+    outcome
 }
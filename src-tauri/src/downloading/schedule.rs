@@ -0,0 +1,101 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{Local, TimeZone};
+
+use crate::utils::models::GlobalSettings;
+
+pub const SCHEDULE_DAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// Time window in which the download queue is allowed to start jobs.
+/// Times are minutes since local midnight, the UTC offset is looked up for every checked instant so DST changes apply right away.
+/// A window where `start > end` runs past midnight and belongs to the day it started on.
+#[derive(Clone, Debug)]
+pub struct DownloadSchedule {
+    pub enabled: bool,
+    pub start: u32,
+    pub end: u32,
+    pub days: [bool; 7],
+}
+
+impl Default for DownloadSchedule {
+    fn default() -> Self {
+        DownloadSchedule { enabled: false, start: 0, end: 0, days: [true; 7] }
+    }
+}
+
+impl DownloadSchedule {
+    pub fn from_settings(gs: &GlobalSettings) -> Self {
+        DownloadSchedule {
+            enabled: gs.download_schedule_enabled,
+            start: parse_schedule_time(&gs.download_schedule_start).unwrap_or(0),
+            end: parse_schedule_time(&gs.download_schedule_end).unwrap_or(0),
+            // Schedules saved before empty day sets were rejected would otherwise hold the queue forever
+            days: parse_schedule_days(&gs.download_schedule_days).filter(|d| d.iter().any(|x| *x)).unwrap_or([true; 7]),
+        }
+    }
+
+    pub fn is_open_now(&self) -> bool {
+        self.is_open(now_unix())
+    }
+
+    pub fn next_window_start_now(&self) -> Option<i64> {
+        self.next_window_start(now_unix())
+    }
+
+    pub fn is_open(&self, unix: i64) -> bool {
+        if !self.enabled { return true; }
+        let (day, minute) = self.local_day_minute(unix);
+        let weekday = weekday_from_day(day);
+        let yesterday = (weekday + 6) % 7;
+        if self.start == self.end { return self.days[weekday]; }
+        if self.start < self.end { self.days[weekday] && minute >= self.start && minute < self.end } else { (self.days[weekday] && minute >= self.start) || (self.days[yesterday] && minute < self.end) }
+    }
+
+    /// Unix timestamp of the next time a window opens, None if the schedule is disabled or has no days selected.
+    pub fn next_window_start(&self, unix: i64) -> Option<i64> {
+        if !self.enabled || !self.days.iter().any(|d| *d) { return None; }
+        let (day, _) = self.local_day_minute(unix);
+        (0..=7).map(|offset| day + offset).filter(|d| self.days[weekday_from_day(*d)]).map(|d| {
+            let local = d * 86400 + self.start as i64 * 60;
+            // The offset at the window start can differ from the current one when a DST change lies in between
+            let guess = local - utc_offset_at(unix);
+            local - utc_offset_at(guess)
+        }).find(|start| *start > unix)
+    }
+
+    fn local_day_minute(&self, unix: i64) -> (i64, u32) {
+        let local = unix + utc_offset_at(unix);
+        (local.div_euclid(86400), (local.rem_euclid(86400) / 60) as u32)
+    }
+}
+
+/// Parses "HH:MM" into minutes since midnight.
+pub fn parse_schedule_time(value: &str) -> Option<u32> {
+    let (h, m) = value.trim().split_once(':')?;
+    let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+    if h < 24 && m < 60 { Some(h * 60 + m) } else { None }
+}
+
+/// Parses a comma separated list like "sat,sun" into a Monday-first day mask.
+pub fn parse_schedule_days(value: &str) -> Option<[bool; 7]> {
+    let mut days = [false; 7];
+    for d in value.split(',').map(|d| d.trim().to_ascii_lowercase()).filter(|d| !d.is_empty()) {
+        let idx = SCHEDULE_DAYS.iter().position(|sd| *sd == d.as_str())?;
+        days[idx] = true;
+    }
+    Some(days)
+}
+
+fn weekday_from_day(day: i64) -> usize {
+    // 1970-01-01 was a Thursday
+    (day + 3).rem_euclid(7) as usize
+}
+
+/// Seconds east of UTC of the system timezone at `unix`.
+fn utc_offset_at(unix: i64) -> i64 {
+    Local.timestamp_opt(unix, 0).single().map(|t| t.offset().local_minus_utc() as i64).unwrap_or(0)
+}
+
+fn now_unix() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64
+}
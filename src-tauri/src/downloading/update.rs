@@ -1,6 +1,10 @@
 use crate::DownloadState;
 use crate::downloading::queue::{QueueJobKind, QueueJobOutcome};
 use crate::downloading::{DownloadGamePayload, QueueJobPayload};
+use crate::downloading::disk_space::{dir_size, preflight_disk_space, SpaceEstimate};
+use crate::downloading::integrity::snapshot_install_files;
+use crate::downloading::progress::{ProgressEvent, ProgressPhase};
+use crate::downloading::verify::cache_file_manifest;
 use crate::utils::db_manager::{get_install_info_by_id, get_manifest_info_by_id, update_install_after_update_by_id};
 use crate::utils::repo_manager::get_manifest;
 use crate::utils::{models::{DiffGameFile,FullGameFile,GameVersion}, run_async_command, show_dialog_with_callback};
@@ -62,15 +66,19 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
 
         let instn = Arc::new(install.name.clone());
         log::info!("Starting game update for \"{}\" ({})", install.name, install.id);
-        let dlpayload = Arc::new(Mutex::new(HashMap::new()));
-
-        let mut dlp = dlpayload.lock().unwrap();
-        dlp.insert("job_id", job_id.to_string());
-        dlp.insert("name", install.name.clone());
-        dlp.insert("progress", "0".to_string());
-        dlp.insert("total", "1000".to_string());
-        h5.emit("update_progress", dlp.clone()).unwrap();
-        drop(dlp);
+        let diffs = picked.game.diff.iter().filter(|e| e.original_version == install.version).collect::<Vec<&DiffGameFile>>();
+        let estimate = match picked.metadata.download_mode.as_str() {
+            "DOWNLOAD_MODE_FILE" | "DOWNLOAD_MODE_CHUNK" if diffs.is_empty() => {
+                let install_dir = Path::new(&install.directory);
+                let on_disk = dir_size(install_dir).saturating_sub(dir_size(&install_dir.join("patching")));
+                Some(SpaceEstimate::for_full(&picked.game.full.iter().filter(|e| gbiz != "bh3_global" || e.region_code == install.region_code).collect::<Vec<&FullGameFile>>()).over_existing(on_disk, picked.metadata.download_mode == "DOWNLOAD_MODE_CHUNK"))
+            }
+            "DOWNLOAD_MODE_FILE" | "DOWNLOAD_MODE_CHUNK" | "DOWNLOAD_MODE_RAW" if !diffs.is_empty() => Some(SpaceEstimate::for_diffs(&diffs, true)),
+            _ => None,
+        };
+        if let Some(estimate) = estimate { if !preflight_disk_space(&h5, &install.name, &install.directory, "patching", estimate, "dialogs.update_insufficient_space_required") { h5.emit("update_complete", ()).unwrap(); return QueueJobOutcome::Failed; } }
+        let dlpayload = Arc::new(Mutex::new(ProgressEvent::new(job_id.as_str(), instn.as_str())));
+        dlpayload.lock().unwrap().emit(&h5, "update_progress");
 
         let cancel_token = Arc::new(AtomicBool::new(false));
         {
@@ -80,6 +88,8 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
         }
 
         let verified_files = { let state = h5.state::<DownloadState>(); let mut vf = state.verified_files.lock().unwrap(); vf.entry(install_id.clone()).or_insert_with(|| Arc::new(Mutex::new(std::collections::HashSet::new()))).clone() };
+        // Files the update writes get rehashed into the file manifest afterwards when the game has no pkg_version
+        let files_before = snapshot_install_files(&install.directory);
         let mut success = false;
         match picked.metadata.download_mode.as_str() {
             "DOWNLOAD_MODE_FILE" => {
@@ -95,7 +105,8 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                     let combined_install_total: u64 = files.iter().map(|e| e.decompressed_size.parse::<u64>().unwrap_or(0)).sum();
                     let cumulative_download = Arc::new(AtomicU64::new(0));
                     let mut ok = true;
-                    for e in files.iter() {
+                    for (file_idx, e) in files.iter().enumerate() {
+                        { let mut dlp = dlpayload.lock().unwrap(); dlp.files_done = file_idx as u64; dlp.files_total = files.len() as u64; }
                         let url = e.file_url.clone();
                         let hash = e.file_hash.clone();
                         let cancel_token = cancel_token.clone();
@@ -103,22 +114,18 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                             <Game as Zipped>::download(url.clone(), hash.clone(), install.directory.clone(), true, false, {
                                     let dlpayload = dlpayload.clone();
                                     let h5 = h5.clone();
-                                    let instn = instn.clone();
-                                    let job_id = job_id.clone();
                                     let cumulative_download = cumulative_download.clone();
                                     move |current, _total, net_speed, disk_speed| {
                                         let mut dlp = dlpayload.lock().unwrap();
                                         let total_dl_progress = cumulative_download.load(Ordering::SeqCst) + current;
-                                        dlp.insert("job_id", job_id.to_string());
-                                        dlp.insert("name", instn.to_string());
-                                        dlp.insert("progress", total_dl_progress.to_string());
-                                        dlp.insert("total", combined_download_total.to_string());
-                                        dlp.insert("speed", net_speed.to_string());
-                                        dlp.insert("disk", disk_speed.to_string());
-                                        dlp.insert("install_progress", "0".to_string());
-                                        dlp.insert("install_total", combined_install_total.to_string());
-                                        dlp.insert("phase", "2".to_string());
-                                        h5.emit("update_progress", dlp.clone()).unwrap();
+                                        dlp.progress = total_dl_progress;
+                                        dlp.total = combined_download_total;
+                                        dlp.speed = net_speed;
+                                        dlp.disk = disk_speed;
+                                        dlp.install_progress = 0;
+                                        dlp.install_total = combined_install_total;
+                                        dlp.phase = ProgressPhase::Download;
+                                        dlp.emit(&h5, "update_progress");
                                         drop(dlp);
                                     }
                                 }, Some(cancel_token.clone()), Some(verified_files.clone())).await
@@ -135,16 +142,12 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                         let ext = fischl::utils::extract_archive_with_progress(far, install.directory.clone(), false, None, {
                             let dlpayload = dlpayload.clone();
                             let h5 = h5.clone();
-                            let instn = instn.clone();
-                            let job_id = job_id.clone();
                             move |current, total| {
                                 let mut dlp = dlpayload.lock().unwrap();
-                                dlp.insert("job_id", job_id.to_string());
-                                dlp.insert("name", instn.to_string());
-                                dlp.insert("install_progress", current.to_string());
-                                dlp.insert("install_total", total.to_string());
-                                dlp.insert("phase", "3".to_string());
-                                h5.emit("update_progress", dlp.clone()).unwrap();
+                                dlp.install_progress = current;
+                                dlp.install_total = total;
+                                dlp.phase = ProgressPhase::Patch;
+                                dlp.emit(&h5, "update_progress");
                             }
                         });
                         if ext {
@@ -154,7 +157,7 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                             log::debug!("Successfully updated {} using DOWNLOAD_MODE_FILE (full), marking as complete", install.name);
                             success = true;
                             #[cfg(target_os = "linux")]
-                            crate::utils::shortcuts::sync_desktop_shortcut(&h5, install.id.clone(), picked.metadata.versioned_name.clone());
+                            crate::utils::shortcuts::sync_desktop_shortcut(&h5, install.id.clone(), install.name.clone(), picked.metadata.versioned_name.clone());
                         } else {
                             if !cancel_token.load(Ordering::Relaxed) { show_dialog_with_callback(&h5, "warning", "TwintailLauncher", "dialogs.game_update_error", Some(vec!["dialogs.buttons.ok"]), None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())]))); }
                             h5.emit("update_complete", ()).unwrap();
@@ -175,7 +178,8 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                             let combined_install_total: u64 = diff_files.iter().map(|e| e.decompressed_size.parse::<u64>().unwrap_or(0)).sum();
                             let cumulative_download = Arc::new(AtomicU64::new(0));
                             let mut ok = true;
-                            for e in diff_files.iter() {
+                            for (file_idx, e) in diff_files.iter().enumerate() {
+                                { let mut dlp = dlpayload.lock().unwrap(); dlp.files_done = file_idx as u64; dlp.files_total = diff_files.len() as u64; }
                                 let url = e.file_url.clone();
                                 let hash = e.file_hash.clone();
                                 let cancel_token = cancel_token.clone();
@@ -183,22 +187,18 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                                     <Game as Zipped>::download(url.clone(), hash.clone(), install.directory.clone(), true, false,{
                                             let dlpayload = dlpayload.clone();
                                             let h5 = h5.clone();
-                                            let instn = instn.clone();
-                                            let job_id = job_id.clone();
                                             let cumulative_download = cumulative_download.clone();
                                             move |current, _total, net_speed, disk_speed| {
                                                 let mut dlp = dlpayload.lock().unwrap();
                                                 let total_dl_progress = cumulative_download.load(Ordering::SeqCst) + current;
-                                                dlp.insert("job_id", job_id.to_string());
-                                                dlp.insert("name", instn.to_string());
-                                                dlp.insert("progress", total_dl_progress.to_string());
-                                                dlp.insert("total", combined_download_total.to_string());
-                                                dlp.insert("speed", net_speed.to_string());
-                                                dlp.insert("disk", disk_speed.to_string());
-                                                dlp.insert("install_progress", "0".to_string());
-                                                dlp.insert("install_total", combined_install_total.to_string());
-                                                dlp.insert("phase", "2".to_string());
-                                                h5.emit("update_progress", dlp.clone()).unwrap();
+                                                dlp.progress = total_dl_progress;
+                                                dlp.total = combined_download_total;
+                                                dlp.speed = net_speed;
+                                                dlp.disk = disk_speed;
+                                                dlp.install_progress = 0;
+                                                dlp.install_total = combined_install_total;
+                                                dlp.phase = ProgressPhase::Download;
+                                                dlp.emit(&h5, "update_progress");
                                                 drop(dlp);
                                             }
                                         }, Some(cancel_token.clone()), Some(verified_files.clone())).await
@@ -217,16 +217,12 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                                     <Game as Zipped>::patch(far, hash, install.directory.clone(), Some(picked.metadata.diff_list_url.game.clone()), {
                                         let dlpayload = dlpayload.clone();
                                         let h5 = h5.clone();
-                                        let instn = instn.clone();
-                                        let job_id = job_id.clone();
                                         move |_download_current, _download_total, install_current, install_total, _net_speed, _disk_speed, phase| {
                                             let mut dlp = dlpayload.lock().unwrap();
-                                            dlp.insert("job_id", job_id.to_string());
-                                            dlp.insert("name", instn.to_string());
-                                            dlp.insert("install_progress", install_current.to_string());
-                                            dlp.insert("install_total", install_total.to_string());
-                                            dlp.insert("phase", phase.to_string());
-                                            h5.emit("update_progress", dlp.clone()).unwrap();
+                                            dlp.install_progress = install_current;
+                                            dlp.install_total = install_total;
+                                            dlp.phase = ProgressPhase::from_patch_code(phase);
+                                            dlp.emit(&h5, "update_progress");
                                         }
                                     }, Some(cancel_token.clone()), Some(verified_files.clone())).await
                                 });
@@ -238,7 +234,7 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                                     log::debug!("Successfully updated {} using DOWNLOAD_MODE_FILE (endfield_global), marking as complete", install.name);
                                     success = true;
                                     #[cfg(target_os = "linux")]
-                                    crate::utils::shortcuts::sync_desktop_shortcut(&h5, install.id.clone(), picked.metadata.versioned_name.clone());
+                                    crate::utils::shortcuts::sync_desktop_shortcut(&h5, install.id.clone(), install.name.clone(), picked.metadata.versioned_name.clone());
                                 } else {
                                     if !cancel_token.load(Ordering::Relaxed) { show_dialog_with_callback(&h5, "warning", "TwintailLauncher", "dialogs.game_update_error", Some(vec!["dialogs.buttons.ok"]), None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())]))); }
                                     h5.emit("update_complete", ()).unwrap();
@@ -272,6 +268,7 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                     let total_manifests = full_urls.len();
                     let mut ok = true;
                     for (manifest_idx, e) in full_urls.clone().into_iter().enumerate() {
+                        { let mut dlp = dlpayload.lock().unwrap(); dlp.files_done = manifest_idx as u64; dlp.files_total = total_manifests as u64; }
                         let h5 = h5.clone();
                         let cancel_token = cancel_token.clone();
                         let cumulative_download = cumulative_download.clone();
@@ -280,25 +277,21 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                         let rslt = run_async_command(async {
                             <Game as Sophon>::download(e.file_url.clone(), e.file_path.clone(), install.directory.clone(), {
                                     let dlpayload = dlpayload.clone();
-                                    let instn = instn.clone();
-                                    let job_id = job_id.clone();
                                     let cumulative_download = cumulative_download.clone();
                                     let cumulative_install = cumulative_install.clone();
                                     move |download_current, _download_total, install_current, _install_total, net_speed, disk_speed, phase| {
                                         let mut dlp = dlpayload.lock().unwrap();
                                         let total_download_progress = cumulative_download.load(Ordering::SeqCst) + download_current;
                                         let total_install_progress = cumulative_install.load(Ordering::SeqCst) + install_current;
-                                        dlp.insert("job_id", job_id.to_string());
-                                        dlp.insert("name", instn.to_string());
-                                        dlp.insert("progress", total_download_progress.to_string());
-                                        dlp.insert("total", combined_download_total.to_string());
-                                        dlp.insert("speed", net_speed.to_string());
-                                        dlp.insert("disk", disk_speed.to_string());
-                                        dlp.insert("install_progress", total_install_progress.to_string());
-                                        dlp.insert("install_total", combined_install_total.to_string());
+                                        dlp.progress = total_download_progress;
+                                        dlp.total = combined_download_total;
+                                        dlp.speed = net_speed;
+                                        dlp.disk = disk_speed;
+                                        dlp.install_progress = total_install_progress;
+                                        dlp.install_total = combined_install_total;
                                         let effective_phase = if phase == 5 && !is_last_manifest { 2 } else { phase };
-                                        dlp.insert("phase", effective_phase.to_string());
-                                        h5.emit("update_progress", dlp.clone()).unwrap();
+                                        dlp.phase = ProgressPhase::from_patch_code(effective_phase);
+                                        dlp.emit(&h5, "update_progress");
                                         drop(dlp);
                                     }
                                 }, Some(cancel_token.clone()), Some(verified_files.clone())).await
@@ -313,7 +306,7 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                         log::debug!("Successfully updated {} using DOWNLOAD_MODE_CHUNK (full), marking as complete", install.name);
                         success = true;
                         #[cfg(target_os = "linux")]
-                        crate::utils::shortcuts::sync_desktop_shortcut(&h5, install.id.clone(), picked.metadata.versioned_name.clone());
+                        crate::utils::shortcuts::sync_desktop_shortcut(&h5, install.id.clone(), install.name.clone(), picked.metadata.versioned_name.clone());
                     } else {
                         if !cancel_token.load(Ordering::Relaxed) { show_dialog_with_callback(&h5, "warning", "TwintailLauncher", "dialogs.game_update_error", Some(vec!["dialogs.buttons.ok"]), None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())]))); }
                         h5.emit("update_complete", ()).unwrap();
@@ -335,6 +328,7 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                         let total_manifests = urls.len();
                         let mut ok = true;
                         for (manifest_idx, e) in urls.clone().into_iter().enumerate() {
+                            { let mut dlp = dlpayload.lock().unwrap(); dlp.files_done = manifest_idx as u64; dlp.files_total = total_manifests as u64; }
                             let h5 = h5.clone();
                             let cancel_token = cancel_token.clone();
                             let cumulative_download = cumulative_download.clone();
@@ -343,25 +337,21 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                             let rslt = run_async_command(async {
                                 <Game as Sophon>::patch(e.file_url.clone(), install.version.clone(), e.file_path.clone(), install.directory.clone(), is_preload, {
                                         let dlpayload = dlpayload.clone();
-                                        let instn = instn.clone();
-                                        let job_id = job_id.clone();
                                         let cumulative_download = cumulative_download.clone();
                                         let cumulative_install = cumulative_install.clone();
                                         move |download_current, _download_total, install_current, _install_total, net_speed, disk_speed, phase| {
                                             let mut dlp = dlpayload.lock().unwrap();
                                             let total_download_progress = cumulative_download.load(Ordering::SeqCst) + download_current;
                                             let total_install_progress = cumulative_install.load(Ordering::SeqCst) + install_current;
-                                            dlp.insert("job_id", job_id.to_string());
-                                            dlp.insert("name", instn.to_string());
-                                            dlp.insert("progress", total_download_progress.to_string());
-                                            dlp.insert("total", combined_download_total.to_string());
-                                            dlp.insert("speed", net_speed.to_string());
-                                            dlp.insert("disk", disk_speed.to_string());
-                                            dlp.insert("install_progress", total_install_progress.to_string());
-                                            dlp.insert("install_total", combined_install_total.to_string());
+                                            dlp.progress = total_download_progress;
+                                            dlp.total = combined_download_total;
+                                            dlp.speed = net_speed;
+                                            dlp.disk = disk_speed;
+                                            dlp.install_progress = total_install_progress;
+                                            dlp.install_total = combined_install_total;
                                             let effective_phase = if phase == 5 && !is_last_manifest { 2 } else { phase };
-                                            dlp.insert("phase", effective_phase.to_string());
-                                            h5.emit("update_progress", dlp.clone()).unwrap();
+                                            dlp.phase = ProgressPhase::from_patch_code(effective_phase);
+                                            dlp.emit(&h5, "update_progress");
                                             drop(dlp);
                                         }
                                     }, Some(cancel_token.clone()), Some(verified_files.clone())).await
@@ -376,7 +366,7 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                             h5.emit("update_complete", ()).unwrap();
                             log::debug!("Successfully updated {} using DOWNLOAD_MODE_CHUNK, marking as complete", install.name);
                             #[cfg(target_os = "linux")]
-                            crate::utils::shortcuts::sync_desktop_shortcut(&h5, install.id.clone(), picked.metadata.versioned_name.clone());
+                            crate::utils::shortcuts::sync_desktop_shortcut(&h5, install.id.clone(), install.name.clone(), picked.metadata.versioned_name.clone());
                             success = true;
                         } else {
                             if !cancel_token.load(Ordering::Relaxed) { show_dialog_with_callback(&h5, "warning", "TwintailLauncher", "dialogs.game_update_error", Some(vec!["dialogs.buttons.ok"]), None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())]))); }
@@ -412,20 +402,16 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                         let rslt = run_async_command(async {
                             <Game as Kuro>::patch(manifest.file_url.to_owned(), manifest.file_path.clone(), picked.metadata.res_list_url.clone(), install.directory.clone(), is_preload, {
                                     let dlpayload = dlpayload.clone();
-                                    let job_id = job_id.clone();
                                     move |download_current: u64, download_total: u64, install_current: u64, install_total: u64, net_speed: u64, disk_speed: u64, phase: u8| {
                                         let mut dlp = dlpayload.lock().unwrap();
-                                        dlp.insert("job_id", job_id.to_string());
-                                        dlp.insert("name", instn.to_string());
-                                        dlp.insert("progress", download_current.to_string());
-                                        dlp.insert("total", download_total.to_string());
-                                        dlp.insert("speed", net_speed.to_string());
-                                        dlp.insert("disk", disk_speed.to_string());
-                                        dlp.insert("install_progress", install_current.to_string());
-                                        dlp.insert("install_total", install_total.to_string());
-                                        // Phase: 0=idle, 1=verifying, 2=downloading, 3=installing, 4=validating, 5=moving
-                                        dlp.insert("phase", phase.to_string());
-                                        tmp.emit("update_progress", dlp.clone()).unwrap();
+                                        dlp.progress = download_current;
+                                        dlp.total = download_total;
+                                        dlp.speed = net_speed;
+                                        dlp.disk = disk_speed;
+                                        dlp.install_progress = install_current;
+                                        dlp.install_total = install_total;
+                                        dlp.phase = ProgressPhase::from_patch_code(phase);
+                                        dlp.emit(&tmp, "update_progress");
                                         drop(dlp);
                                     }
                                 }, Some(cancel_token.clone()), Some(verified_files.clone())).await
@@ -438,7 +424,7 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
                             success = true;
                             #[cfg(target_os = "linux")]
                             {
-                                crate::utils::shortcuts::sync_desktop_shortcut(&h5, install.id.clone(), picked.metadata.versioned_name.clone());
+                                crate::utils::shortcuts::sync_desktop_shortcut(&h5, install.id.clone(), install.name.clone(), picked.metadata.versioned_name.clone());
                                 crate::utils::apply_patch(&h5, install.directory.clone(), "aki".to_string(), "add".to_string());
                             }
                         } else {
@@ -479,6 +465,7 @@ pub fn run_game_update<R: Runtime>(h5: AppHandle<R>, payload: DownloadGamePayloa
         if success {
             log::info!("Update completed for \"{}\" ({})", install.name, install.id);
             { verified_files.lock().unwrap().clear(); }
+            cache_file_manifest(&h5, &install.id, &install.directory, &vc, &files_before, false, install.skip_hash_check);
             QueueJobOutcome::Completed
         } else {
             log::warn!("Update failed for \"{}\" ({})", install.name, install.id);
@@ -0,0 +1,349 @@
+use crate::DownloadState;
+use crate::downloading::integrity::{outcome_str, snapshot_install_files, InstallSnapshot};
+use crate::downloading::queue::{QueueJobKind, QueueJobOutcome};
+use crate::downloading::progress::{ProgressEvent, ProgressPhase};
+use crate::downloading::{DownloadGamePayload, QueueJobPayload};
+use crate::utils::db_manager::{get_install_info_by_id, save_integrity_report};
+use crate::utils::models::LauncherIntegrityReport;
+use crate::utils::show_dialog_with_callback;
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Listener, Manager, Runtime};
+
+#[cfg(target_os = "linux")]
+use crate::downloading::RunnerDownloadPayload;
+#[cfg(target_os = "linux")]
+use crate::downloading::misc::run_runner_download;
+#[cfg(target_os = "linux")]
+use crate::utils::db_manager::update_installed_runner_is_installed_by_version;
+#[cfg(target_os = "linux")]
+use crate::utils::{empty_dir, missing_runner_binaries};
+
+/// Hashes of every game file in an install, cached after each successful download, update or repair.
+/// fischl fetches Sophon and Kuro manifests fresh every time, so this is what verify only jobs check against while offline.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileManifest {
+    pub version: String,
+    pub files: HashMap<String, FileManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileManifestEntry {
+    pub size: u64,
+    /// Seconds since epoch, lets us skip rehashing files a job did not touch. Zero for entries taken from `pkg_version`.
+    #[serde(default)]
+    pub mtime: u64,
+    pub md5: String,
+}
+
+/// One line of the `pkg_version` files HoYo games ship next to the game, the same hashes their Sophon and zipped manifests carry.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PkgVersionEntry {
+    remote_name: String,
+    md5: String,
+    file_size: u64,
+}
+
+pub fn register_verify_handler<R: Runtime>(app: &AppHandle<R>) {
+    let a = app.clone();
+    app.listen("start_game_verify", move |event| {
+        let payload: DownloadGamePayload = serde_json::from_str(event.payload()).unwrap();
+        let state = a.state::<DownloadState>();
+        let q = state.queue.lock().unwrap().clone();
+        if let Some(queue) = q {
+            if queue.has_job_for_id(payload.install.clone()) { show_dialog_with_callback(&a, "warning", "TwintailLauncher", "dialogs.game_already_queued_verify", Some(vec!["dialogs.buttons.ok"]), None, None); return; }
+            queue.enqueue(QueueJobKind::GameVerify, QueueJobPayload::Game(payload));
+        } else {
+            let h = a.clone();
+            std::thread::spawn(move || {
+                let job_id = format!("direct_verify_{}", SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis());
+                let _ = run_game_verify(h, payload, job_id);
+            });
+        }
+    });
+}
+
+fn file_manifest_path<R: Runtime>(app: &AppHandle<R>, install_id: &str) -> PathBuf {
+    app.path().app_data_dir().unwrap().join("file_manifests").join(format!("{}.json", install_id))
+}
+
+pub fn get_file_manifest<R: Runtime>(app: &AppHandle<R>, install_id: &str) -> Option<FileManifest> {
+    let content = std::fs::read_to_string(file_manifest_path(app, install_id)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn delete_file_manifest<R: Runtime>(app: &AppHandle<R>, install_id: &str) {
+    let path = file_manifest_path(app, install_id);
+    if path.exists() { let _ = std::fs::remove_file(path); }
+}
+
+/// Reads `pkg_version` and the per language `*_pkg_version` files from the install root.
+/// Returns None for games that do not ship them (Kuro), those fall back to a hashed listing.
+pub fn read_pkg_versions(directory: &str) -> Option<HashMap<String, FileManifestEntry>> {
+    let mut files = HashMap::new();
+    let mut found = false;
+    for entry in std::fs::read_dir(directory).ok()?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name != "pkg_version" && !name.ends_with("_pkg_version") { continue; }
+        let Ok(content) = std::fs::read_to_string(entry.path()) else { continue; };
+        found = true;
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let Ok(e) = serde_json::from_str::<PkgVersionEntry>(line) else { continue; };
+            files.insert(e.remote_name.replace('\\', "/"), FileManifestEntry { size: e.file_size, mtime: 0, md5: e.md5.to_lowercase() });
+        }
+    }
+    if found && !files.is_empty() { Some(files) } else { None }
+}
+
+/// Caches the file listing of an install at the end of a successful job, before the next job can touch the files.
+/// Sophon and zipped installs carry `pkg_version` files with the manifest hashes, those get cached as is.
+/// fischl does not hand out the Kuro manifest, so only files it checked against it during this job are hashed: every file when `checked_all` (downloads and repairs), the written ones otherwise.
+/// Files nothing checked keep their previous entry or stay out of the listing, a file that broke since is never recorded as good.
+pub fn cache_file_manifest<R: Runtime>(app: &AppHandle<R>, install_id: &str, directory: &str, version: &str, before: &InstallSnapshot, checked_all: bool, skip_hash_check: bool) {
+    if let Some(files) = read_pkg_versions(directory) {
+        store_file_manifest(app, install_id, FileManifest { version: version.to_string(), files });
+        return;
+    }
+    if skip_hash_check { log::debug!("Not caching a file listing for install {}, hash checks are skipped for it", install_id); return; }
+    let previous = get_file_manifest(app, install_id);
+    if !checked_all && previous.is_none() { log::debug!("Not caching a file listing for install {}, the files this job did not write were never checked", install_id); return; }
+
+    let mut files = HashMap::new();
+    for (path, (size, modified)) in snapshot_install_files(directory).iter() {
+        let written = before.get(path) != Some(&(*size, *modified));
+        let prev = previous.as_ref().map(|m| m.files.get(path));
+        let reusable = match prev {
+            // Untracked and untouched, this is something the game created and not part of the manifest
+            Some(None) if !written => continue,
+            Some(Some(e)) if !written && !checked_all => { files.insert(path.clone(), e.clone()); continue; }
+            Some(Some(e)) if !written && e.size == *size && e.mtime == mtime_secs(*modified) => Some(e.md5.clone()),
+            _ => None,
+        };
+        let md5 = match reusable {
+            Some(v) => v,
+            None => match hash_file(&PathBuf::from(directory).join(path), None, |_| {}) { Some(v) => v, None => continue },
+        };
+        files.insert(path.clone(), FileManifestEntry { size: *size, mtime: mtime_secs(*modified), md5 });
+    }
+    store_file_manifest(app, install_id, FileManifest { version: version.to_string(), files });
+}
+
+fn store_file_manifest<R: Runtime>(app: &AppHandle<R>, install_id: &str, manifest: FileManifest) {
+    let path = file_manifest_path(app, install_id);
+    if let Some(parent) = path.parent() { let _ = std::fs::create_dir_all(parent); }
+    match std::fs::write(&path, serde_json::to_string(&manifest).unwrap_or_default()) {
+        Ok(_) => log::debug!("Stored file manifest with {} file(s) for install {}", manifest.files.len(), install_id),
+        Err(e) => log::warn!("Failed to store file manifest for install {}: {}", install_id, e),
+    }
+}
+
+pub fn run_game_verify<R: Runtime>(h: AppHandle<R>, payload: DownloadGamePayload, job_id: String) -> QueueJobOutcome {
+    let install_id = payload.install.clone();
+    let Some(i) = get_install_info_by_id(&h, payload.install.clone()) else { log::warn!("Cannot start verify: install {} not found", payload.install); return QueueJobOutcome::Failed; };
+    log::info!("Starting offline verify for \"{}\" ({})", i.name, i.id);
+
+    // pkg_version always matches what is installed, the cached listing only when it was taken for the installed version
+    let listing = read_pkg_versions(&i.directory).map(|files| FileManifest { version: i.version.clone(), files });
+    let Some(manifest) = listing.or_else(|| get_file_manifest(&h, &i.id).filter(|m| m.version == i.version)) else {
+        log::warn!("Cannot verify \"{}\": no file manifest stored for version {}", i.name, i.version);
+        show_dialog_with_callback(&h, "warning", "TwintailLauncher", "dialogs.game_verify_no_manifest", Some(vec!["dialogs.buttons.ok"]), None, Some(HashMap::from([("install_name", i.name.as_str())])));
+        h.emit("verify_complete", ()).unwrap();
+        return QueueJobOutcome::Failed;
+    };
+
+    let cancel_token = Arc::new(AtomicBool::new(false));
+    {
+        let state = h.state::<DownloadState>();
+        let mut tokens = state.tokens.lock().unwrap();
+        tokens.insert(install_id.clone(), cancel_token.clone());
+    }
+
+    let mut dlp = ProgressEvent::new(job_id.as_str(), i.name.as_str());
+    dlp.phase = ProgressPhase::Verify;
+    dlp.install_total = manifest.files.values().map(|e| e.size).sum();
+    dlp.files_total = manifest.files.len() as u64;
+    dlp.emit(&h, "verify_progress");
+
+    let mut paths = manifest.files.keys().cloned().collect::<Vec<String>>();
+    paths.sort();
+    let mut verified = 0usize;
+    let mut failed = Vec::new();
+    let started = std::time::Instant::now();
+    let mut hashed_before = 0u64;
+    let mut last_emit = std::time::Instant::now();
+    for (idx, path) in paths.iter().enumerate() {
+        if cancel_token.load(Ordering::Relaxed) { break; }
+        let entry = &manifest.files[path];
+        let full = PathBuf::from(&i.directory).join(path);
+        let size = std::fs::metadata(&full).map(|m| m.len()).ok();
+        let ok = size == Some(entry.size) && hash_file(&full, Some(&cancel_token), |read| {
+            dlp.install_progress = hashed_before + read;
+            if last_emit.elapsed() < Duration::from_millis(250) { return; }
+            last_emit = std::time::Instant::now();
+            dlp.disk = (dlp.install_progress as f64 / started.elapsed().as_secs_f64().max(0.001)) as u64;
+            dlp.emit(&h, "verify_progress");
+        }).is_some_and(|md5| md5 == entry.md5);
+        if cancel_token.load(Ordering::Relaxed) { break; }
+        if ok { verified += 1; } else { failed.push(path.clone()); }
+        hashed_before += entry.size;
+        dlp.install_progress = hashed_before;
+        dlp.files_done = idx as u64 + 1;
+        if last_emit.elapsed() >= Duration::from_millis(250) || idx + 1 == paths.len() { last_emit = std::time::Instant::now(); dlp.emit(&h, "verify_progress"); }
+    }
+
+    let cancelled = cancel_token.load(Ordering::Relaxed);
+    {
+        let state = h.state::<DownloadState>();
+        let mut tokens = state.tokens.lock().unwrap();
+        tokens.remove(&install_id);
+    }
+    if cancelled {
+        log::info!("Verify cancelled for \"{}\"", i.name);
+        let mut paused = HashMap::new();
+        paused.insert("job_id", job_id.to_string());
+        paused.insert("name", i.name.clone());
+        h.emit("verify_paused", paused).unwrap();
+        return QueueJobOutcome::Cancelled;
+    }
+
+    let outcome = QueueJobOutcome::Completed;
+    let report = LauncherIntegrityReport {
+        install_id: i.id.clone(),
+        job_kind: "game_verify".to_string(),
+        status: outcome_str(&outcome).to_string(),
+        files_verified: verified as i64,
+        files_redownloaded: Json(Vec::new()),
+        files_changed: Json(Vec::new()),
+        files_failed: Json(failed.clone()),
+        bytes_transferred: 0,
+        created_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64,
+    };
+    save_integrity_report(&h, report.clone());
+    log::info!("Verify completed for \"{}\" ({}): {} ok, {} mismatched or missing", i.name, i.id, verified, failed.len());
+
+    let count = failed.len().to_string();
+    if failed.is_empty() {
+        show_dialog_with_callback(&h, "info", "TwintailLauncher", "dialogs.game_verify_ok", Some(vec!["dialogs.buttons.ok"]), None, Some(HashMap::from([("install_name", i.name.as_str())])));
+    } else {
+        show_dialog_with_callback(&h, "warning", "TwintailLauncher", "dialogs.game_verify_mismatch", Some(vec!["dialogs.buttons.ok"]), None, Some(HashMap::from([("install_name", i.name.as_str()), ("count", count.as_str())])));
+    }
+    h.emit("verify_complete", report).unwrap();
+    outcome
+}
+
+#[cfg(target_os = "linux")]
+fn runner_manifest_path<R: Runtime>(app: &AppHandle<R>, runner_version: &str) -> PathBuf {
+    app.path().app_data_dir().unwrap().join("runner_manifests").join(format!("{}.json", runner_version))
+}
+
+#[cfg(target_os = "linux")]
+fn get_runner_manifest<R: Runtime>(app: &AppHandle<R>, runner_version: &str) -> Option<FileManifest> {
+    let content = std::fs::read_to_string(runner_manifest_path(app, runner_version)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+#[cfg(target_os = "linux")]
+pub fn delete_runner_manifest<R: Runtime>(app: &AppHandle<R>, runner_version: &str) {
+    let path = runner_manifest_path(app, runner_version);
+    if path.exists() { let _ = std::fs::remove_file(path); }
+}
+
+/// Hashes every file of a freshly extracted runner, `run_runner_verify` checks against this listing later.
+#[cfg(target_os = "linux")]
+pub fn write_runner_manifest<R: Runtime>(app: &AppHandle<R>, runner_version: &str, directory: &str) {
+    let mut files = HashMap::new();
+    for (path, (size, modified)) in snapshot_install_files(directory).iter() {
+        let Some(md5) = hash_file(&PathBuf::from(directory).join(path), None, |_| {}) else { continue; };
+        files.insert(path.clone(), FileManifestEntry { size: *size, mtime: mtime_secs(*modified), md5 });
+    }
+    let path = runner_manifest_path(app, runner_version);
+    if let Some(parent) = path.parent() { let _ = std::fs::create_dir_all(parent); }
+    let manifest = FileManifest { version: runner_version.to_string(), files };
+    match std::fs::write(&path, serde_json::to_string(&manifest).unwrap_or_default()) {
+        Ok(_) => log::debug!("Stored file listing with {} file(s) for runner {}", manifest.files.len(), runner_version),
+        Err(e) => log::warn!("Failed to store file listing for runner {}: {}", runner_version, e),
+    }
+}
+
+/// Checks the runner binaries and, when a listing was stored at download time, every file of the runner.
+/// A broken runner gets emptied and downloaded again in the same job, runners without a known archive are only reported.
+#[cfg(target_os = "linux")]
+pub fn run_runner_verify<R: Runtime>(h: AppHandle<R>, payload: RunnerDownloadPayload, job_id: String) -> QueueJobOutcome {
+    let runner_name = payload.runner_version.clone();
+    let directory = PathBuf::from(&payload.runner_path);
+    log::info!("Starting verify for runner {}", runner_name);
+
+    let mut failed = missing_runner_binaries(&h, &runner_name, &directory).into_iter().map(|b| b.strip_prefix(&directory).unwrap_or(&b).to_string_lossy().to_string()).collect::<Vec<String>>();
+    let manifest = get_runner_manifest(&h, &runner_name);
+    let mut dlp = ProgressEvent::new(job_id.as_str(), runner_name.as_str());
+    dlp.phase = ProgressPhase::Verify;
+    if let Some(m) = manifest.as_ref() {
+        dlp.install_total = m.files.values().map(|e| e.size).sum();
+        dlp.files_total = m.files.len() as u64;
+        dlp.emit(&h, "verify_progress");
+        let mut paths = m.files.keys().cloned().collect::<Vec<String>>();
+        paths.sort();
+        let mut last_emit = std::time::Instant::now();
+        for (idx, path) in paths.iter().enumerate() {
+            let entry = &m.files[path];
+            let full = directory.join(path);
+            let ok = std::fs::metadata(&full).is_ok_and(|md| md.len() == entry.size) && hash_file(&full, None, |_| {}).is_some_and(|md5| md5 == entry.md5);
+            if !ok && !failed.contains(path) { failed.push(path.clone()); }
+            dlp.install_progress += entry.size;
+            dlp.files_done = idx as u64 + 1;
+            if last_emit.elapsed() >= Duration::from_millis(250) || idx + 1 == paths.len() { last_emit = std::time::Instant::now(); dlp.emit(&h, "verify_progress"); }
+        }
+    }
+
+    if failed.is_empty() {
+        // Runners installed before listings existed get one now that the binaries look fine
+        if manifest.is_none() { write_runner_manifest(&h, &runner_name, &payload.runner_path); }
+        log::info!("Runner {} verified, {} file(s) checked", runner_name, manifest.as_ref().map(|m| m.files.len()).unwrap_or(0));
+        show_dialog_with_callback(&h, "info", "TwintailLauncher", "dialogs.runner_verify_ok", Some(vec!["dialogs.buttons.ok"]), None, Some(HashMap::from([("runner_name", runner_name.as_str())])));
+        h.emit("verify_complete", runner_name).unwrap();
+        return QueueJobOutcome::Completed;
+    }
+
+    log::warn!("Runner {} is broken, {} file(s) missing or corrupted: {:?}", runner_name, failed.len(), failed.iter().take(10).collect::<Vec<_>>());
+    update_installed_runner_is_installed_by_version(&h, runner_name.clone(), false);
+    if payload.runner_url.is_empty() {
+        let count = failed.len().to_string();
+        show_dialog_with_callback(&h, "warning", "TwintailLauncher", "dialogs.runner_verify_no_source", Some(vec!["dialogs.buttons.ok"]), None, Some(HashMap::from([("runner_name", runner_name.as_str()), ("count", count.as_str())])));
+        h.emit("verify_complete", runner_name).unwrap();
+        return QueueJobOutcome::Failed;
+    }
+
+    log::info!("Downloading runner {} again", runner_name);
+    delete_runner_manifest(&h, &runner_name);
+    if let Err(e) = empty_dir(&directory) { log::warn!("Failed to empty broken runner {}: {}", directory.display(), e); }
+    run_runner_download(h, payload, job_id)
+}
+
+/// Streams the file through md5, returns None when it can not be read or the token got cancelled midway.
+fn hash_file(path: &PathBuf, cancel_token: Option<&Arc<AtomicBool>>, mut on_progress: impl FnMut(u64)) -> Option<String> {
+    let mut file = std::fs::File::open(path).ok()?;
+    let mut hasher = Md5::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    let mut read_total = 0u64;
+    loop {
+        if cancel_token.is_some_and(|t| t.load(Ordering::Relaxed)) { return None; }
+        let n = file.read(&mut buf).ok()?;
+        if n == 0 { break; }
+        hasher.update(&buf[..n]);
+        read_total += n as u64;
+        on_progress(read_total);
+    }
+    Some(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn mtime_secs(modified: Option<SystemTime>) -> u64 {
+    modified.and_then(|m| m.duration_since(UNIX_EPOCH).ok()).map(|d| d.as_secs()).unwrap_or(0)
+}
@@ -2,26 +2,31 @@ extern crate core;
 
 use std::sync::{Mutex, Arc};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use tauri::{AppHandle, Runtime, Emitter, Manager, RunEvent, WindowEvent};
-use crate::commands::install::{add_install, check_game_running, game_launch, get_download_sizes, get_resume_states, get_install_by_id, list_installs, list_installs_by_manifest_id, remove_install, set_installs_order, update_install_dxvk_path, update_install_dxvk_version, update_install_env_vars, update_install_fps_value, update_install_game_background, update_install_game_path, update_install_graphics_api, update_install_launch_args, update_install_launch_cmd, update_install_pre_launch_cmd, update_install_prefix_path, update_install_runner_path, update_install_runner_version, update_install_skip_hash_valid, update_install_skip_version_updates, update_install_use_fps_unlock, update_install_use_jadeite, update_install_use_xxmi, update_install_use_gamemode, update_install_use_mangohud, update_install_mangohud_config_path, add_shortcut, remove_shortcut, update_install_xxmi_config, update_install_show_drpc, update_install_disable_system_idle, copy_authkey};
+use crate::commands::install::{add_install, check_game_running, list_game_sessions, stop_game, game_launch, preview_launch, get_download_sizes, get_resume_states, get_install_by_id, get_install_integrity_report, export_install_bundle, import_install_bundle, list_installs, list_installs_by_manifest_id, remove_install, set_installs_order, update_install_dxvk_path, update_install_dxvk_version, update_install_env_vars, update_install_env_list, validate_install_env_vars, update_install_fps_value, update_install_game_background, update_install_game_path, update_install_graphics_api, update_install_launch_args, update_install_launch_cmd, update_install_launch_hooks, update_install_proton_log, update_install_wine_debug, get_session_log_tail, update_install_prefix_path, update_install_runner_path, update_install_runner_version, update_install_runner_auto_upgrade, rollback_install_runner_version, update_install_skip_hash_valid, update_install_skip_version_updates, update_install_use_fps_unlock, update_install_use_jadeite, update_install_use_xxmi, update_install_use_gamemode, update_install_use_mangohud, update_install_mangohud_config_path, add_shortcut, remove_shortcut, update_install_xxmi_config, update_install_show_drpc, update_install_disable_system_idle, copy_authkey};
 use crate::commands::queue::{pause_game_download, queue_move_up, queue_move_down, queue_remove, queue_set_paused, queue_activate_job, queue_reorder, queue_resume_job, get_download_queue_state, queue_clear_completed};
 use crate::commands::manifest::{get_manifest_by_filename, get_manifest_by_id, list_game_manifests, get_game_manifest_by_filename, list_manifests_by_repository_id, update_manifest_enabled, get_game_manifest_by_manifest_id, list_compatibility_manifests, get_compatibility_manifest_by_manifest_id, override_manifest_url, clear_manifest_override};
 use crate::commands::repository::{list_repositories, remove_repository, add_repository, get_repository};
-use crate::commands::settings::{check_app_update, empty_folder, get_locale, list_locales, list_settings, open_folder, open_in_prefix, open_uri, update_settings_app_lang_cmd, update_settings_default_dxvk_path, update_settings_default_fps_unlock_path, update_settings_default_game_path, update_settings_default_jadeite_path, update_settings_default_mangohud_config_path, update_settings_default_prefix_path, update_settings_default_runner_path, update_settings_default_xxmi_path, update_settings_download_speed_limit_cmd, update_settings_hide_app_tray, update_settings_launcher_action, update_settings_manifests_hide, update_settings_third_party_repo_updates};
+use crate::commands::settings::{apply_launch_defaults_cmd, check_app_update, preview_apply_launch_defaults, update_settings_launch_defaults_cmd, empty_folder, get_locale, list_locales, list_settings, open_folder, open_in_prefix, open_uri, update_settings_app_lang_cmd, update_settings_default_dxvk_path, update_settings_default_fps_unlock_path, update_settings_default_game_path, update_settings_default_jadeite_path, update_settings_default_mangohud_config_path, update_settings_default_prefix_path, update_settings_default_runner_path, update_settings_default_xxmi_path, update_settings_download_speed_limit_cmd, update_settings_download_speed_limit_kind_cmd, update_settings_download_speed_limit_while_playing_cmd, update_settings_hide_app_tray, update_settings_launcher_action, update_settings_manifests_hide, update_settings_queue_auto_resume, update_settings_queue_max_concurrent_cmd, update_settings_download_schedule_cmd, update_settings_third_party_repo_updates};
 use crate::downloading::download::register_download_handler;
 use crate::downloading::preload::register_preload_handler;
 use crate::downloading::repair::register_repair_handler;
+use crate::downloading::verify::register_verify_handler;
 use crate::downloading::update::register_update_handler;
-use crate::downloading::queue::{start_download_queue_worker, QueueJob, QueueJobKind, QueueJobOutcome};
+use crate::downloading::queue::{start_download_queue_worker, QueueJob, QueueJobKind, QueueJobOutcome, QueueLane};
 use crate::downloading::QueueJobPayload;
 use crate::downloading::misc::check_extras_update;
 use crate::utils::db_manager::{init_db, DbInstances};
 use crate::utils::repo_manager::{load_manifests, ManifestLoader, ManifestLoaders};
 use crate::utils::{args, register_listeners, run_async_command, setup_or_fix_default_paths, sync_install_backgrounds};
 use crate::utils::system_tray::init_tray;
-use crate::commands::runners::{add_installed_runner, get_installed_runner_by_id, get_installed_runner_by_version, is_steamrt_installed, list_installed_runners, remove_installed_runner, update_installed_runner_install_status};
+use crate::commands::runners::{add_local_runner, verify_installed_runner, cleanup_runners, get_runner_usage_report, preview_runner_cleanup, add_installed_runner, get_installed_runner_by_id, get_installed_runner_by_version, is_steamrt_installed, list_installed_runners, remove_installed_runner, update_installed_runner_install_status};
 use crate::commands::network::check_network_connectivity;
+use crate::commands::playtime::{get_playtime_by_version, get_playtime_daily, get_playtime_weekly, list_play_sessions};
+use crate::commands::profiles::{create_launch_profile, delete_launch_profile, list_launch_profiles, update_launch_profile};
+use crate::commands::snapshots::{create_install_prefix_snapshot, delete_install_prefix_snapshot, list_install_prefix_snapshots, restore_install_prefix_snapshot};
 
 mod utils;
 mod commands;
@@ -33,9 +38,14 @@ pub struct DownloadState {
     pub verified_files: Mutex<HashMap<String, Arc<Mutex<std::collections::HashSet<String>>>>>,
 }
 
+pub struct GameState {
+    /// Games launched from the launcher, keyed by install id.
+    pub sessions: Mutex<HashMap<String, utils::game_sessions::GameSession>>,
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let logger = tauri_plugin_log::Builder::new().filter(|metadata| !metadata.target().contains("h2")).filter(|metadata| !metadata.target().contains("tracing")).filter(|metadata| !metadata.target().contains("hyper")).max_file_size(8000000).clear_targets().targets([tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::LogDir { file_name: Some("twintaillauncher".to_string()) })]).rotation_strategy(tauri_plugin_log::RotationStrategy::KeepSome(5)).timezone_strategy(tauri_plugin_log::TimezoneStrategy::UseLocal).level(if cfg!(debug_assertions) { log::LevelFilter::Trace } else { if std::env::var("TTL_DEBUG").is_ok() { log::LevelFilter::Debug } else { log::LevelFilter::Info } }).build();
+    if let Some(cmd) = args::get_headless_command() { std::process::exit(run_headless_cli(cmd)); }
     let builder = {
         #[cfg(target_os = "linux")]
         {
@@ -44,21 +54,23 @@ pub fn run() {
             let base = tauri::Builder::<tauri::Wry>::new()
                 .manage(ManifestLoaders {game: ManifestLoader::default(), runner: utils::repo_manager::RunnerLoader::default()})
                 .manage(DownloadState { tokens: Mutex::new(HashMap::new()), queue: Mutex::new(None), verified_files: Mutex::new(HashMap::new()) })
+                .manage(GameState { sessions: Mutex::new(HashMap::new()) })
                 .plugin(tauri_plugin_dialog::init())
                 .plugin(tauri_plugin_opener::init())
                 .plugin(tauri_plugin_clipboard_manager::init())
-                .plugin(logger);
+                .plugin(logger());
             if args::get_launch_install().is_none() { base.plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| { let _ = app.get_window("main").expect("no main window").show(); let _ = app.get_window("main").expect("no main window").set_focus(); })) } else { base }
         }
         #[cfg(target_os = "windows")]
         {
             let base = tauri::Builder::<tauri::Wry>::new()
                 .manage(DownloadState { tokens: Mutex::new(HashMap::new()), queue: Mutex::new(None), verified_files: Mutex::new(HashMap::new()) })
+                .manage(GameState { sessions: Mutex::new(HashMap::new()) })
                 .manage(ManifestLoaders {game: ManifestLoader::default()})
                 .plugin(tauri_plugin_dialog::init())
                 .plugin(tauri_plugin_opener::init())
                 .plugin(tauri_plugin_clipboard_manager::init())
-                .plugin(logger);
+                .plugin(logger());
             if args::get_launch_install().is_none() { base.plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| { let _ = app.get_window("main").expect("no main window").show(); let _ = app.get_window("main").expect("no main window").set_focus(); })) } else { base }
         }
     }.setup(|app| {
@@ -73,11 +85,9 @@ pub fn run() {
 
             #[cfg(any(target_arch = "x86_64", target_arch = "aarch64"))]
             {
-                // Why in the absolute fuck is fedora atomic garbage distros doing /home -> var/home symlink???
-                #[cfg(target_os = "linux")]
-                let data_dir = { let d = app.path().app_data_dir().unwrap(); if utils::is_flatpak() && std::fs::symlink_metadata("/home").map(|m| m.file_type().is_symlink()).unwrap_or(false) { std::fs::canonicalize(&d).unwrap_or(d) } else { d } };
-                #[cfg(target_os = "windows")]
-                let data_dir = app.path().app_data_dir().unwrap();
+                let data_dir = app_data_dir(handle);
+                // A headless update or repair owns the installs until it exits, it refuses anything sent to it
+                if let Some(reply) = utils::headless::forward_to_running_instance(&data_dir, &[]) { log::warn!("Not starting while a headless command is running: {}", reply.message); eprintln!("{}", reply.message); std::process::exit(1); }
 
                 run_async_command(async { init_db(handle, data_dir.clone()).await; });
 
@@ -88,9 +98,12 @@ pub fn run() {
                         (QueueJobKind::GameUpdate, QueueJobPayload::Game(p)) => downloading::update::run_game_update(app, p, job.id),
                         (QueueJobKind::GamePreload, QueueJobPayload::Game(p)) => downloading::preload::run_game_preload(app, p, job.id),
                         (QueueJobKind::GameRepair, QueueJobPayload::Game(p)) => downloading::repair::run_game_repair(app, p, job.id),
+                        (QueueJobKind::GameVerify, QueueJobPayload::Game(p)) => downloading::verify::run_game_verify(app, p, job.id),
                         #[cfg(target_os = "linux")]
                         (QueueJobKind::RunnerDownload, QueueJobPayload::Runner(p)) => downloading::misc::run_runner_download(app, p, job.id),
                         #[cfg(target_os = "linux")]
+                        (QueueJobKind::RunnerVerify, QueueJobPayload::Runner(p)) => downloading::verify::run_runner_verify(app, p, job.id),
+                        #[cfg(target_os = "linux")]
                         (QueueJobKind::SteamrtDownload, QueueJobPayload::Steamrt(p)) => downloading::misc::run_steamrt3_download(app, p, job.id),
                         #[cfg(target_os = "linux")]
                         (QueueJobKind::Steamrt4Download, QueueJobPayload::Steamrt4(p)) => downloading::misc::run_steamrt4_download(app, p, job.id),
@@ -103,28 +116,34 @@ pub fn run() {
                     }
                 }
 
-                // Only 1 game can download at a time - others wait in queue
-                let queue_handle = start_download_queue_worker(handle.clone(), 1, run_queued_job);
+                // Games and misc downloads (runners, steamrt, extras) get separate slots so small downloads are not stuck behind a game
+                let lane_limits = utils::db_manager::get_settings(handle).map(|s| HashMap::from([(QueueLane::Game, s.max_concurrent_game_jobs.max(1) as usize), (QueueLane::Misc, s.max_concurrent_misc_jobs.max(1) as usize)])).unwrap_or_else(|| HashMap::from([(QueueLane::Game, 1), (QueueLane::Misc, 1)]));
+                let queue_handle = start_download_queue_worker(handle.clone(), lane_limits, run_queued_job);
                 {
                     let state = handle.state::<DownloadState>();
                     let mut q = state.queue.lock().unwrap();
                     *q = Some(queue_handle);
                 }
+                // CLI `update`, `repair`, `verify` and `preload` started while the launcher is open end up in the queue above
+                utils::headless::start_cli_listener(handle, &data_dir);
 
                 // Start connection monitor for auto-pause/resume on connectivity changes
                 downloading::connection_monitor::start_connection_monitor(handle.clone());
                 load_manifests(handle, data_dir.clone());
+                #[cfg(target_os = "linux")]
+                utils::local_runners::register_local_runners(handle);
                 init_tray(handle).unwrap();
                 // Initialize the listeners
                 register_listeners(handle);
                 register_download_handler(handle);
                 register_update_handler(handle);
                 register_repair_handler(handle);
+                register_verify_handler(handle);
                 register_preload_handler(handle);
 
                 if args::get_launch_install().is_some() {
                     let id = args::get_launch_install().unwrap();
-                    game_launch(handle.clone(), id);
+                    game_launch(handle.clone(), id, args::get_launch_profile());
                     handle.get_window("main").unwrap().hide().unwrap();
                     app.emit("sync_tray_toggle", "Show").unwrap();
                 }
@@ -141,21 +160,23 @@ pub fn run() {
                 {
                     utils::fix_window_decorations(handle);
                     utils::sync_installed_runners(handle);
+                    // Needs the compatibility manifests, the runner paths set up above and the imported runners registered
+                    utils::runner_upgrades::check_runner_upgrades(handle);
                     downloading::misc::download_or_update_steamrt3(handle);
                     downloading::misc::download_or_update_steamrt4(handle);
                 }
             }
             Ok(())
-        }).invoke_handler(tauri::generate_handler![open_uri, open_folder, empty_folder, open_in_prefix, list_settings, update_settings_third_party_repo_updates, update_settings_default_game_path, update_settings_default_xxmi_path, update_settings_default_fps_unlock_path, update_settings_default_jadeite_path, update_settings_default_prefix_path, update_settings_default_runner_path, update_settings_default_dxvk_path, update_settings_default_mangohud_config_path, update_settings_download_speed_limit_cmd, update_settings_launcher_action, update_settings_manifests_hide, update_settings_hide_app_tray,
+        }).invoke_handler(tauri::generate_handler![add_local_runner, verify_installed_runner, get_runner_usage_report, preview_runner_cleanup, cleanup_runners, update_settings_launch_defaults_cmd, preview_apply_launch_defaults, apply_launch_defaults_cmd, open_uri, open_folder, empty_folder, open_in_prefix, list_settings, update_settings_third_party_repo_updates, update_settings_default_game_path, update_settings_default_xxmi_path, update_settings_default_fps_unlock_path, update_settings_default_jadeite_path, update_settings_default_prefix_path, update_settings_default_runner_path, update_settings_default_dxvk_path, update_settings_default_mangohud_config_path, update_settings_download_speed_limit_cmd, update_settings_download_speed_limit_kind_cmd, update_settings_download_speed_limit_while_playing_cmd, update_settings_launcher_action, update_settings_manifests_hide, update_settings_hide_app_tray, update_settings_queue_auto_resume, update_settings_queue_max_concurrent_cmd, update_settings_download_schedule_cmd,
             remove_repository, add_repository, get_repository, list_repositories,
             get_manifest_by_id, get_manifest_by_filename, list_manifests_by_repository_id, update_manifest_enabled,
             get_game_manifest_by_filename, list_game_manifests, get_game_manifest_by_manifest_id, override_manifest_url, clear_manifest_override,
-            list_installs, list_installs_by_manifest_id, get_install_by_id, add_install, remove_install, set_installs_order,
-            update_install_game_path, update_install_runner_path, update_install_dxvk_path, update_install_skip_version_updates, update_install_skip_hash_valid, update_install_use_jadeite, update_install_use_xxmi, update_install_use_fps_unlock, update_install_fps_value, update_install_graphics_api, update_install_env_vars, update_install_pre_launch_cmd, update_install_launch_cmd, update_install_game_background, update_install_prefix_path, update_install_launch_args, update_install_dxvk_version, update_install_runner_version, update_install_use_gamemode, update_install_use_mangohud, update_install_xxmi_config, update_install_show_drpc, update_install_disable_system_idle, copy_authkey,
+            list_installs, list_installs_by_manifest_id, get_install_by_id, get_install_integrity_report, add_install, export_install_bundle, import_install_bundle, remove_install, set_installs_order,
+            update_install_game_path, update_install_runner_path, update_install_dxvk_path, update_install_skip_version_updates, update_install_skip_hash_valid, update_install_use_jadeite, update_install_use_xxmi, update_install_use_fps_unlock, update_install_fps_value, update_install_graphics_api, update_install_env_vars, update_install_env_list, validate_install_env_vars, update_install_launch_cmd, update_install_proton_log, update_install_wine_debug, get_session_log_tail, update_install_launch_hooks, update_install_game_background, update_install_prefix_path, update_install_launch_args, update_install_dxvk_version, update_install_runner_version, update_install_runner_auto_upgrade, rollback_install_runner_version, update_install_use_gamemode, update_install_use_mangohud, update_install_xxmi_config, update_install_show_drpc, update_install_disable_system_idle, copy_authkey,
             list_compatibility_manifests, get_compatibility_manifest_by_manifest_id,
-            game_launch, check_game_running, get_download_sizes, get_resume_states, update_install_mangohud_config_path, update_settings_default_mangohud_config_path, add_shortcut, remove_shortcut, pause_game_download, queue_move_up, queue_move_down, queue_remove, queue_set_paused, queue_activate_job, queue_reorder, queue_resume_job, get_download_queue_state, queue_clear_completed,
-            add_installed_runner, remove_installed_runner, get_installed_runner_by_version, get_installed_runner_by_id, list_installed_runners, update_installed_runner_install_status, is_steamrt_installed, check_network_connectivity, check_app_update, get_locale, list_locales, update_settings_app_lang_cmd])
-        .build(tauri::generate_context!())
+            game_launch, preview_launch, check_game_running, list_game_sessions, stop_game, get_download_sizes, get_resume_states, update_install_mangohud_config_path, update_settings_default_mangohud_config_path, add_shortcut, remove_shortcut, pause_game_download, queue_move_up, queue_move_down, queue_remove, queue_set_paused, queue_activate_job, queue_reorder, queue_resume_job, get_download_queue_state, queue_clear_completed,
+            add_installed_runner, remove_installed_runner, get_installed_runner_by_version, get_installed_runner_by_id, list_installed_runners, update_installed_runner_install_status, is_steamrt_installed, check_network_connectivity, list_launch_profiles, create_launch_profile, update_launch_profile, delete_launch_profile, list_install_prefix_snapshots, create_install_prefix_snapshot, restore_install_prefix_snapshot, delete_install_prefix_snapshot, list_play_sessions, get_playtime_daily, get_playtime_weekly, get_playtime_by_version, check_app_update, get_locale, list_locales, update_settings_app_lang_cmd])
+        .build(context())
         .expect("Error while running TwintailLauncher!");
 
     builder.run(|app, event| {
@@ -181,3 +202,59 @@ pub fn run() {
         }
     })
 }
+
+fn logger<R: Runtime>() -> tauri::plugin::TauriPlugin<R> {
+    tauri_plugin_log::Builder::new().filter(|metadata| !metadata.target().contains("h2")).filter(|metadata| !metadata.target().contains("tracing")).filter(|metadata| !metadata.target().contains("hyper")).max_file_size(8000000).clear_targets().targets([tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::LogDir { file_name: Some("twintaillauncher".to_string()) })]).rotation_strategy(tauri_plugin_log::RotationStrategy::KeepSome(5)).timezone_strategy(tauri_plugin_log::TimezoneStrategy::UseLocal).level(if cfg!(debug_assertions) { log::LevelFilter::Trace } else { if std::env::var("TTL_DEBUG").is_ok() { log::LevelFilter::Debug } else { log::LevelFilter::Info } }).build()
+}
+
+fn context<R: Runtime>() -> tauri::Context<R> { tauri::generate_context!() }
+
+fn app_data_dir<R: Runtime>(app: &AppHandle<R>) -> PathBuf {
+    // Why in the absolute fuck is fedora atomic garbage distros doing /home -> var/home symlink???
+    #[cfg(target_os = "linux")]
+    { let d = app.path().app_data_dir().unwrap(); if utils::is_flatpak() && std::fs::symlink_metadata("/home").map(|m| m.file_type().is_symlink()).unwrap_or(false) { std::fs::canonicalize(&d).unwrap_or(d) } else { d } }
+    #[cfg(target_os = "windows")]
+    { app.path().app_data_dir().unwrap() }
+}
+
+/// CLI subcommands run on the mock runtime so no window, tray or GTK gets created.
+/// `update`, `repair`, `verify` and `preload` are handed to an open launcher when there is one and only run here otherwise.
+fn run_headless_cli(command: args::CliCommand) -> i32 {
+    #[cfg(target_os = "linux")]
+    utils::raise_fd_limit(999999);
+    let mut context = context::<tauri::test::MockRuntime>();
+    context.config_mut().app.windows.clear();
+    let builder = tauri::test::mock_builder()
+        .manage(DownloadState { tokens: Mutex::new(HashMap::new()), queue: Mutex::new(None), verified_files: Mutex::new(HashMap::new()) })
+        .manage(GameState { sessions: Mutex::new(HashMap::new()) });
+    #[cfg(target_os = "linux")]
+    let builder = builder.manage(ManifestLoaders {game: ManifestLoader::default(), runner: utils::repo_manager::RunnerLoader::default()});
+    #[cfg(target_os = "windows")]
+    let builder = builder.manage(ManifestLoaders {game: ManifestLoader::default()});
+    let app = match builder.plugin(logger()).build(context) {
+        Ok(app) => app,
+        Err(e) => { eprintln!("Failed to start TwintailLauncher: {}", e); return 1; }
+    };
+    let handle = app.handle();
+    let data_dir = app_data_dir(handle);
+    let json = args::is_json_output();
+
+    let read_only = command.is_read_only();
+    if !read_only {
+        let argv: Vec<String> = std::env::args().collect();
+        if let Some(reply) = utils::headless::forward_to_running_instance(&data_dir, &argv) { return utils::headless::print_forward_reply(reply, json); }
+    }
+    run_async_command(async { init_db(handle, data_dir.clone()).await; });
+    // Only the open launcher runs the queue, this turns away anything else started while the job runs
+    if !read_only { utils::headless::start_cli_listener(handle, &data_dir); }
+    downloading::connection_monitor::start_connection_monitor(handle.clone());
+    load_manifests(handle, data_dir.clone());
+    #[cfg(target_os = "linux")]
+    utils::local_runners::register_local_runners(handle);
+    setup_or_fix_default_paths(handle, data_dir.clone(), true);
+
+    let code = utils::headless::run_headless(handle, command, json);
+    if !read_only { utils::headless::stop_cli_listener(&data_dir); }
+    run_async_command(async { handle.state::<DbInstances>().0.lock().await.get("db").unwrap().close().await; });
+    code
+}
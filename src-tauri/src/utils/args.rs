@@ -2,16 +2,120 @@ use gumdrop::Options;
 
 #[derive(Debug, Options)]
 pub struct Args {
+    #[options(help = "Print this help message")]
+    pub help: bool,
     #[options(help = "Launch specific installation by its ID", meta = "ID")]
     pub install: Option<String>,
+    #[options(no_short, help = "Launch profile to use together with --install", meta = "ID")]
+    pub profile: Option<String>,
+    #[options(help = "Print headless command output as JSON lines")]
+    pub json: bool,
+    #[options(command)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Debug, Options)]
+pub enum CliCommand {
+    #[options(help = "List all installations")]
+    List(NoOpts),
+    #[options(help = "Update an installation to the latest version")]
+    Update(InstallOpts),
+    #[options(help = "Repair (verify and redownload broken files) an installation")]
+    Repair(InstallOpts),
+    #[options(help = "Verify an installation against its stored file hashes without downloading anything")]
+    Verify(InstallOpts),
+    #[options(help = "Predownload the upcoming version of an installation")]
+    Preload(InstallOpts),
+    #[options(help = "Inspect the download queue")]
+    Queue(QueueOpts),
+    #[options(help = "Launch an installation")]
+    Launch(LaunchOpts),
+}
+
+#[derive(Debug, Options)]
+pub struct NoOpts {
+    #[options(help = "Print this help message")]
+    pub help: bool,
+}
+
+#[derive(Debug, Options)]
+pub struct InstallOpts {
+    #[options(help = "Print this help message")]
+    pub help: bool,
+    #[options(free, help = "Installation ID")]
+    pub id: Option<String>,
+}
+
+#[derive(Debug, Options)]
+pub struct LaunchOpts {
+    #[options(help = "Print this help message")]
+    pub help: bool,
+    #[options(free, help = "Installation ID")]
+    pub id: Option<String>,
+    #[options(no_short, help = "Launch profile to use instead of the install settings", meta = "ID")]
+    pub profile: Option<String>,
+    #[options(no_short, help = "Print the resolved command, environment and working directory instead of launching")]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Options)]
+pub struct QueueOpts {
+    #[options(help = "Print this help message")]
+    pub help: bool,
+    #[options(command)]
+    pub command: Option<QueueCliCommand>,
+}
+
+#[derive(Debug, Options)]
+pub enum QueueCliCommand {
+    #[options(help = "Show queued, paused and recently finished jobs")]
+    Status(NoOpts),
 }
 
 impl Args {
     pub fn parse() -> Self {
         Args::parse_args_default_or_exit()
     }
+
+    /// Parses the command line another instance was started with, `argv` includes the program name.
+    pub fn parse_argv(argv: &[String]) -> Option<Self> {
+        Args::parse_args_default(argv.get(1..).unwrap_or_default()).ok()
+    }
+}
+
+impl CliCommand {
+    /// Commands that only read the database and may run next to an open launcher. The others are handed to its download queue instead.
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, CliCommand::List(_) | CliCommand::Queue(_) | CliCommand::Launch(_))
+    }
 }
 
 pub fn get_launch_install() -> Option<String> {
-    Args::parse().install
-}
\ No newline at end of file
+    let args = Args::parse();
+    match args.command {
+        Some(CliCommand::Launch(opts)) if !opts.dry_run => opts.id,
+        Some(CliCommand::Launch(_)) => None,
+        _ => args.install,
+    }
+}
+
+pub fn get_launch_profile() -> Option<String> {
+    let args = Args::parse();
+    match args.command {
+        Some(CliCommand::Launch(opts)) => opts.profile,
+        _ => args.profile,
+    }
+}
+
+/// Subcommands that run without any window or tray and exit when done, `launch` is handled like `--install` unless it is a dry run.
+pub fn get_headless_command() -> Option<CliCommand> {
+    match Args::parse().command {
+        Some(CliCommand::Launch(opts)) if !opts.dry_run => None,
+        None => None,
+        Some(cmd) => Some(cmd),
+    }
+}
+
+pub fn is_json_output() -> bool {
+    Args::parse().json
+}
@@ -1,5 +1,5 @@
 use crate::utils::models::{
-    GlobalSettings, LauncherInstall, LauncherManifest, LauncherRepository, LauncherRunner,
+    GlobalSettings, EnvVar, LaunchDefaults, LauncherInstall, LaunchHook, LaunchProfile, LauncherIntegrityReport, LauncherManifest, LauncherPlaySession, PlaytimeTotal, LauncherQueueJob, LauncherRepository, LauncherRunner, LocalRunner,
     XXMISettings,
 };
 use crate::utils::repo_manager::{setup_compatibility_repository, setup_official_repository};
@@ -191,6 +191,150 @@ pub async fn init_db<R: Runtime>(app: &AppHandle<R>, data_path: std::path::PathB
             sql: r#"ALTER TABLE settings ADD COLUMN app_lang TEXT DEFAULT 'en_US' NOT NULL;"#,
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 31,
+            description: "create_download_queue_table",
+            sql: r#"CREATE TABLE IF NOT EXISTS download_queue ("id" TEXT PRIMARY KEY NOT NULL, "kind" TEXT NOT NULL, "install_id" TEXT NOT NULL, "name" TEXT NOT NULL, "status" TEXT NOT NULL, "payload" TEXT, "position" INTEGER NOT NULL);"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 32,
+            description: "alter_settings_table_queue_auto_resume",
+            sql: r#"ALTER TABLE settings ADD COLUMN queue_auto_resume bool DEFAULT true NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 33,
+            description: "alter_settings_table_max_concurrent_game_jobs",
+            sql: r#"ALTER TABLE settings ADD COLUMN max_concurrent_game_jobs INTEGER DEFAULT 1 NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 34,
+            description: "alter_settings_table_max_concurrent_misc_jobs",
+            sql: r#"ALTER TABLE settings ADD COLUMN max_concurrent_misc_jobs INTEGER DEFAULT 1 NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 35,
+            description: "alter_settings_table_download_schedule_enabled",
+            sql: r#"ALTER TABLE settings ADD COLUMN download_schedule_enabled bool DEFAULT false NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 36,
+            description: "alter_settings_table_download_schedule_start",
+            sql: r#"ALTER TABLE settings ADD COLUMN download_schedule_start TEXT DEFAULT '01:00' NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 37,
+            description: "alter_settings_table_download_schedule_end",
+            sql: r#"ALTER TABLE settings ADD COLUMN download_schedule_end TEXT DEFAULT '07:00' NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 38,
+            description: "alter_settings_table_download_schedule_days",
+            sql: r#"ALTER TABLE settings ADD COLUMN download_schedule_days TEXT DEFAULT 'mon,tue,wed,thu,fri,sat,sun' NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 39,
+            description: "alter_settings_table_download_speed_limits",
+            sql: r#"ALTER TABLE settings ADD COLUMN download_speed_limits TEXT DEFAULT '' NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 40,
+            description: "alter_settings_table_download_speed_limit_while_playing",
+            sql: r#"ALTER TABLE settings ADD COLUMN download_speed_limit_while_playing integer DEFAULT 0 NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 41,
+            description: "create_integrity_reports_table",
+            sql: r#"CREATE TABLE IF NOT EXISTS integrity_reports ("install_id" TEXT PRIMARY KEY NOT NULL, "job_kind" TEXT NOT NULL, "status" TEXT NOT NULL, "files_verified" INTEGER NOT NULL DEFAULT 0, "files_redownloaded" TEXT NOT NULL DEFAULT '[]', "files_changed" TEXT NOT NULL DEFAULT '[]', "files_failed" TEXT NOT NULL DEFAULT '[]', "bytes_transferred" INTEGER NOT NULL DEFAULT 0, "created_at" INTEGER NOT NULL);"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 42,
+            description: "alter_install_table_launch_hooks",
+            sql: r#"ALTER TABLE install ADD COLUMN launch_hooks TEXT DEFAULT '[]' NOT NULL; UPDATE install SET launch_hooks = json_array(json_object('stage', 'pre_launch', 'command', pre_launch_command, 'enabled', json('true'), 'timeout_secs', 30, 'required', json('false'))), pre_launch_command = '' WHERE COALESCE(pre_launch_command, '') != '';"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 43,
+            description: "init_play_sessions_table",
+            sql: r#"CREATE TABLE IF NOT EXISTS play_sessions ("id" INTEGER PRIMARY KEY AUTOINCREMENT, "install_id" TEXT NOT NULL, "started_at" INTEGER NOT NULL, "ended_at" INTEGER NOT NULL, "duration" INTEGER NOT NULL DEFAULT 0, "game_version" TEXT NOT NULL DEFAULT '', "runner_version" TEXT NOT NULL DEFAULT '', "legacy" bool NOT NULL DEFAULT 0);"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 44,
+            description: "import_legacy_play_sessions",
+            sql: r#"INSERT INTO play_sessions (install_id, started_at, ended_at, duration, game_version, runner_version, legacy) SELECT id, MAX(CAST(last_played_time AS INTEGER) - total_playtime, 0), CAST(last_played_time AS INTEGER), total_playtime, 'legacy', '', 1 FROM install WHERE total_playtime > 0;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 45,
+            description: "alter_install_table_proton_log",
+            sql: r#"ALTER TABLE install ADD COLUMN proton_log bool DEFAULT false NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 46,
+            description: "alter_install_table_wine_debug",
+            sql: r#"ALTER TABLE install ADD COLUMN wine_debug TEXT DEFAULT '' NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 47,
+            description: "init_launch_profiles_table",
+            sql: r#"CREATE TABLE IF NOT EXISTS launch_profiles ("id" TEXT PRIMARY KEY NOT NULL, "install_id" TEXT NOT NULL, "name" TEXT NOT NULL, "launch_args" TEXT NOT NULL DEFAULT '', "env_vars" TEXT NOT NULL DEFAULT '', "use_mangohud" bool NOT NULL DEFAULT 0, "use_gamemode" bool NOT NULL DEFAULT 0, "use_xxmi" bool NOT NULL DEFAULT 0, "use_fps_unlock" bool NOT NULL DEFAULT 0, "fps_value" TEXT NOT NULL DEFAULT '60', "graphics_api" TEXT NOT NULL DEFAULT '', "created_at" INTEGER NOT NULL);"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 48,
+            description: "alter_install_table_env_list",
+            sql: r#"ALTER TABLE install ADD COLUMN env_list TEXT DEFAULT '[]' NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 49,
+            description: "alter_launch_profiles_table_env_list",
+            sql: r#"ALTER TABLE launch_profiles ADD COLUMN env_list TEXT DEFAULT '[]' NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 50,
+            description: "alter_settings_table_launch_defaults",
+            sql: r#"ALTER TABLE settings ADD COLUMN launch_defaults TEXT DEFAULT '{}' NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 51,
+            description: "alter_installed_runners_table_last_used",
+            sql: r#"ALTER TABLE installed_runners ADD COLUMN last_used INTEGER DEFAULT 0 NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 52,
+            description: "alter_installed_runners_table_local_runner",
+            sql: r#"ALTER TABLE installed_runners ADD COLUMN local_runner TEXT DEFAULT 'null' NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 53,
+            description: "alter_install_table_runner_auto_upgrade",
+            sql: r#"ALTER TABLE install ADD COLUMN runner_auto_upgrade bool DEFAULT 0 NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 54,
+            description: "alter_install_table_previous_runner_version",
+            sql: r#"ALTER TABLE install ADD COLUMN previous_runner_version TEXT DEFAULT '' NOT NULL;"#,
+            kind: MigrationKind::Up,
+        },
     ];
 
     let mut migrations = add_migrations("db", migrationsl);
@@ -251,6 +395,16 @@ pub fn get_settings<R: Runtime>(app: &AppHandle<R>) -> Option<GlobalSettings> {
             default_mangohud_config_path: rslt.get(0).unwrap().get("default_mangohud_config_path"),
             hide_app_to_tray: rslt.get(0).unwrap().get("hide_app_to_tray"),
             app_lang: rslt.get(0).unwrap().get("app_lang"),
+            queue_auto_resume: rslt.get(0).unwrap().get("queue_auto_resume"),
+            max_concurrent_game_jobs: rslt.get(0).unwrap().get("max_concurrent_game_jobs"),
+            max_concurrent_misc_jobs: rslt.get(0).unwrap().get("max_concurrent_misc_jobs"),
+            download_schedule_enabled: rslt.get(0).unwrap().get("download_schedule_enabled"),
+            download_schedule_start: rslt.get(0).unwrap().get("download_schedule_start"),
+            download_schedule_end: rslt.get(0).unwrap().get("download_schedule_end"),
+            download_schedule_days: rslt.get(0).unwrap().get("download_schedule_days"),
+            download_speed_limits: rslt.get(0).unwrap().get("download_speed_limits"),
+            download_speed_limit_while_playing: rslt.get(0).unwrap().get("download_speed_limit_while_playing"),
+            launch_defaults: rslt.get(0).unwrap().get("launch_defaults"),
         };
         Some(rsltt)
     } else { None }
@@ -410,6 +564,62 @@ pub fn update_settings_app_lang<R: Runtime>(app: &AppHandle<R>, lang: String) {
     });
 }
 
+pub fn update_settings_auto_resume_queue<R: Runtime>(app: &AppHandle<R>, enabled: bool) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE settings SET 'queue_auto_resume' = $1 WHERE id = 1").bind(enabled);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn update_settings_max_concurrent_game_jobs<R: Runtime>(app: &AppHandle<R>, max_concurrent: i64) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE settings SET 'max_concurrent_game_jobs' = $1 WHERE id = 1").bind(max_concurrent);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn update_settings_max_concurrent_misc_jobs<R: Runtime>(app: &AppHandle<R>, max_concurrent: i64) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE settings SET 'max_concurrent_misc_jobs' = $1 WHERE id = 1").bind(max_concurrent);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn update_settings_download_schedule<R: Runtime>(app: &AppHandle<R>, enabled: bool, start: String, end: String, days: String) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE settings SET 'download_schedule_enabled' = $1, 'download_schedule_start' = $2, 'download_schedule_end' = $3, 'download_schedule_days' = $4 WHERE id = 1").bind(enabled).bind(start).bind(end).bind(days);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn update_settings_download_speed_limits<R: Runtime>(app: &AppHandle<R>, limits: String) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE settings SET 'download_speed_limits' = $1 WHERE id = 1").bind(limits);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn update_settings_download_speed_limit_while_playing<R: Runtime>(app: &AppHandle<R>, limit_kb_per_sec: i64) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE settings SET 'download_speed_limit_while_playing' = $1 WHERE id = 1").bind(limit_kb_per_sec);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn update_settings_launch_defaults<R: Runtime>(app: &AppHandle<R>, defaults: Json<LaunchDefaults>) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE settings SET 'launch_defaults' = $1 WHERE id = 1").bind(defaults);
+        query.execute(&db).await.unwrap();
+    });
+}
+
 // === REPOSITORIES ===
 
 pub fn create_repository<R: Runtime>(app: &AppHandle<R>, id: String, github_id: &str) -> Result<bool, Error> {
@@ -889,6 +1099,12 @@ pub fn get_install_info_by_id<R: Runtime>(app: &AppHandle<R>, id: String) -> Opt
             shortcut_path: rslt.get(0).unwrap().get("shortcut_path"),
             region_code: rslt.get(0).unwrap().get("region_code"),
             xxmi_config: rslt.get(0).unwrap().get("xxmi_config"),
+            launch_hooks: rslt.get(0).unwrap().get("launch_hooks"),
+            proton_log: rslt.get(0).unwrap().get("proton_log"),
+            wine_debug: rslt.get(0).unwrap().get("wine_debug"),
+            env_list: rslt.get(0).unwrap().get("env_list"),
+            runner_auto_upgrade: rslt.get(0).unwrap().get("runner_auto_upgrade"),
+            previous_runner_version: rslt.get(0).unwrap().get("previous_runner_version"),
             sort_order: rslt.get(0).unwrap().get("sort_order"),
             last_played_time: rslt.get(0).unwrap().get("last_played_time"),
             total_playtime: rslt.get(0).unwrap().get("total_playtime"),
@@ -958,6 +1174,12 @@ pub fn get_installs_by_manifest_id<R: Runtime>(
                 shortcut_path: r.get("shortcut_path"),
                 region_code: r.get("region_code"),
                 xxmi_config: r.get("xxmi_config"),
+                launch_hooks: r.get("launch_hooks"),
+                proton_log: r.get("proton_log"),
+                wine_debug: r.get("wine_debug"),
+                env_list: r.get("env_list"),
+                runner_auto_upgrade: r.get("runner_auto_upgrade"),
+                previous_runner_version: r.get("previous_runner_version"),
                 sort_order: r.get("sort_order"),
                 last_played_time: r.get("last_played_time"),
                 total_playtime: r.get("total_playtime"),
@@ -1025,6 +1247,12 @@ pub fn get_installs<R: Runtime>(app: &AppHandle<R>) -> Option<Vec<LauncherInstal
                 shortcut_path: r.get("shortcut_path"),
                 region_code: r.get("region_code"),
                 xxmi_config: r.get("xxmi_config"),
+                launch_hooks: r.get("launch_hooks"),
+                proton_log: r.get("proton_log"),
+                wine_debug: r.get("wine_debug"),
+                env_list: r.get("env_list"),
+                runner_auto_upgrade: r.get("runner_auto_upgrade"),
+                previous_runner_version: r.get("previous_runner_version"),
                 sort_order: r.get("sort_order"),
                 last_played_time: r.get("last_played_time"),
                 total_playtime: r.get("total_playtime"),
@@ -1304,24 +1532,6 @@ pub fn update_install_env_vars_by_id<R: Runtime>(app: &AppHandle<R>, id: String,
     });
 }
 
-pub fn update_install_pre_launch_cmd_by_id<R: Runtime>(app: &AppHandle<R>, id: String, cmd: String) {
-    run_async_command(async {
-        let db = app
-            .state::<DbInstances>()
-            .0
-            .lock()
-            .await
-            .get("db")
-            .unwrap()
-            .clone();
-
-        let query = query("UPDATE install SET 'pre_launch_command' = $1 WHERE id = $2")
-            .bind(cmd)
-            .bind(id);
-        query.execute(&db).await.unwrap();
-    });
-}
-
 pub fn update_install_launch_cmd_by_id<R: Runtime>(app: &AppHandle<R>, id: String, cmd: String) {
     run_async_command(async {
         let db = app
@@ -1533,6 +1743,55 @@ pub fn update_install_xxmi_config_by_id<R: Runtime>(
     });
 }
 
+pub fn update_install_launch_hooks_by_id<R: Runtime>(app: &AppHandle<R>, id: String, hooks: Json<Vec<LaunchHook>>) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE install SET 'launch_hooks' = $1 WHERE id = $2").bind(hooks).bind(id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+/// Saving the typed list drops the legacy string so it can not come back once the list is emptied.
+pub fn update_install_env_list_by_id<R: Runtime>(app: &AppHandle<R>, id: String, env_list: Json<Vec<EnvVar>>) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE install SET 'env_list' = $1, 'env_vars' = '' WHERE id = $2").bind(env_list).bind(id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn update_install_runner_auto_upgrade_by_id<R: Runtime>(app: &AppHandle<R>, id: String, enabled: bool) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE install SET 'runner_auto_upgrade' = $1 WHERE id = $2").bind(enabled).bind(id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn update_install_previous_runner_version_by_id<R: Runtime>(app: &AppHandle<R>, id: String, version: String) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE install SET 'previous_runner_version' = $1 WHERE id = $2").bind(version).bind(id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn update_install_proton_log_by_id<R: Runtime>(app: &AppHandle<R>, id: String, enabled: bool) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE install SET 'proton_log' = $1 WHERE id = $2").bind(enabled).bind(id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn update_install_wine_debug_by_id<R: Runtime>(app: &AppHandle<R>, id: String, channels: String) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE install SET 'wine_debug' = $1 WHERE id = $2").bind(channels).bind(id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
 pub fn update_install_last_played_by_id<R: Runtime>(app: &AppHandle<R>, id: String, last_played_time: String) {
     run_async_command(async {
         let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
@@ -1596,6 +1855,8 @@ pub fn get_installed_runners<R: Runtime>(app: &AppHandle<R>) -> Option<Vec<Launc
                 version: r.get("version"),
                 value: r.get("version"),
                 name: r.get("version"),
+                last_used: r.get("last_used"),
+                local_runner: r.get("local_runner"),
             })
         }
 
@@ -1635,6 +1896,23 @@ pub fn create_installed_runner<R: Runtime>(
     }
 }
 
+#[allow(dead_code)]
+pub fn create_local_installed_runner<R: Runtime>(app: &AppHandle<R>, version: String, runner_path: String, local_runner: Json<LocalRunner>) -> Result<bool, Error> {
+    let mut rslt = SqliteQueryResult::default();
+
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("INSERT INTO installed_runners(runner_path, is_installed, version, local_runner, id) VALUES ($1, true, $2, $3, null)").bind(runner_path).bind(version).bind(local_runner);
+        rslt = query.execute(&db).await.unwrap();
+    });
+
+    if rslt.rows_affected() >= 1 {
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
 pub fn get_installed_runner_info_by_id<R: Runtime>(app: &AppHandle<R>, id: String) -> Option<LauncherRunner> {
     let mut rslt = vec![];
 
@@ -1660,6 +1938,8 @@ pub fn get_installed_runner_info_by_id<R: Runtime>(app: &AppHandle<R>, id: Strin
             version: rslt.get(0).unwrap().get("version"),
             name: rslt.get(0).unwrap().get("version"),
             value: rslt.get(0).unwrap().get("version"),
+            last_used: rslt.get(0).unwrap().get("last_used"),
+            local_runner: rslt.get(0).unwrap().get("local_runner"),
         };
 
         Some(rsltt)
@@ -1696,6 +1976,8 @@ pub fn get_installed_runner_info_by_version<R: Runtime>(
             version: rslt.get(0).unwrap().get("version"),
             name: rslt.get(0).unwrap().get("version"),
             value: rslt.get(0).unwrap().get("version"),
+            last_used: rslt.get(0).unwrap().get("last_used"),
+            local_runner: rslt.get(0).unwrap().get("local_runner"),
         };
 
         Some(rsltt)
@@ -1726,6 +2008,22 @@ pub fn update_installed_runner_is_installed_by_version<R: Runtime>(
     });
 }
 
+pub fn update_installed_runner_last_used_by_version<R: Runtime>(app: &AppHandle<R>, version: String, last_used: i64) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE installed_runners SET 'last_used' = $1 WHERE version = $2").bind(last_used).bind(version);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn delete_installed_runner_by_version<R: Runtime>(app: &AppHandle<R>, version: String) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("DELETE FROM installed_runners WHERE version = $1").bind(version);
+        query.execute(&db).await.unwrap();
+    });
+}
+
 #[allow(dead_code)]
 pub fn update_installed_runner_path_by_version<R: Runtime>(
     app: &AppHandle<R>,
@@ -1749,6 +2047,237 @@ pub fn update_installed_runner_path_by_version<R: Runtime>(
     });
 }
 
+// === DOWNLOAD QUEUE ===
+
+pub fn get_download_queue_jobs<R: Runtime>(app: &AppHandle<R>) -> Option<Vec<LauncherQueueJob>> {
+    let mut rslt = vec![];
+
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("SELECT * FROM download_queue ORDER BY position ASC");
+        rslt = query.fetch_all(&db).await.unwrap();
+    });
+
+    if rslt.len() >= 1 {
+        let mut rsltt = Vec::<LauncherQueueJob>::new();
+        for r in rslt {
+            rsltt.push(LauncherQueueJob {
+                id: r.get("id"),
+                kind: r.get("kind"),
+                install_id: r.get("install_id"),
+                name: r.get("name"),
+                status: r.get("status"),
+                payload: r.get("payload"),
+                position: r.get("position"),
+            })
+        }
+        Some(rsltt)
+    } else { None }
+}
+
+pub fn replace_download_queue_jobs<R: Runtime>(app: &AppHandle<R>, jobs: Vec<LauncherQueueJob>) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let mut tx = db.begin().await.unwrap();
+        query("DELETE FROM download_queue").execute(&mut *tx).await.unwrap();
+        for j in jobs {
+            let _ = query("INSERT INTO download_queue(id, kind, install_id, name, status, payload, position) VALUES ($1, $2, $3, $4, $5, $6, $7)").bind(j.id).bind(j.kind).bind(j.install_id).bind(j.name).bind(j.status).bind(j.payload).bind(j.position).execute(&mut *tx).await;
+        }
+        tx.commit().await.unwrap();
+    });
+}
+
+// === INTEGRITY REPORTS ===
+
+pub fn get_integrity_report_by_install_id<R: Runtime>(app: &AppHandle<R>, install_id: String) -> Option<LauncherIntegrityReport> {
+    let mut rslt = vec![];
+
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("SELECT * FROM integrity_reports WHERE install_id = $1").bind(install_id);
+        rslt = query.fetch_all(&db).await.unwrap();
+    });
+
+    if rslt.len() >= 1 {
+        let r = rslt.get(0).unwrap();
+        Some(LauncherIntegrityReport {
+            install_id: r.get("install_id"),
+            job_kind: r.get("job_kind"),
+            status: r.get("status"),
+            files_verified: r.get("files_verified"),
+            files_redownloaded: r.get("files_redownloaded"),
+            files_changed: r.get("files_changed"),
+            files_failed: r.get("files_failed"),
+            bytes_transferred: r.get("bytes_transferred"),
+            created_at: r.get("created_at"),
+        })
+    } else { None }
+}
+
+pub fn save_integrity_report<R: Runtime>(app: &AppHandle<R>, report: LauncherIntegrityReport) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("INSERT OR REPLACE INTO integrity_reports(install_id, job_kind, status, files_verified, files_redownloaded, files_changed, files_failed, bytes_transferred, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)").bind(report.install_id).bind(report.job_kind).bind(report.status).bind(report.files_verified).bind(report.files_redownloaded).bind(report.files_changed).bind(report.files_failed).bind(report.bytes_transferred).bind(report.created_at);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn delete_integrity_report_by_install_id<R: Runtime>(app: &AppHandle<R>, install_id: String) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("DELETE FROM integrity_reports WHERE install_id = $1").bind(install_id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn create_play_session<R: Runtime>(app: &AppHandle<R>, install_id: String, started_at: i64, game_version: String, runner_version: String) -> i64 {
+    let mut id = 0;
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("INSERT INTO play_sessions (install_id, started_at, ended_at, duration, game_version, runner_version, legacy) VALUES ($1, $2, $2, 0, $3, $4, 0)").bind(install_id).bind(started_at).bind(game_version).bind(runner_version);
+        id = query.execute(&db).await.unwrap().last_insert_rowid();
+    });
+    id
+}
+
+pub fn update_play_session_end_by_id<R: Runtime>(app: &AppHandle<R>, id: i64, ended_at: i64, duration: i64) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE play_sessions SET 'ended_at' = $1, 'duration' = $2 WHERE id = $3").bind(ended_at).bind(duration).bind(id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn get_play_sessions<R: Runtime>(app: &AppHandle<R>, install_id: Option<String>, limit: i64) -> Option<Vec<LauncherPlaySession>> {
+    let mut rslt = vec![];
+
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("SELECT * FROM play_sessions WHERE ($1 IS NULL OR install_id = $1) ORDER BY started_at DESC LIMIT $2").bind(install_id).bind(limit);
+        rslt = query.fetch_all(&db).await.unwrap();
+    });
+
+    if rslt.len() >= 1 {
+        let mut sessions = Vec::new();
+        for r in rslt {
+            sessions.push(LauncherPlaySession {
+                id: r.get("id"),
+                install_id: r.get("install_id"),
+                started_at: r.get("started_at"),
+                ended_at: r.get("ended_at"),
+                duration: r.get("duration"),
+                game_version: r.get("game_version"),
+                runner_version: r.get("runner_version"),
+                legacy: r.get("legacy"),
+            });
+        }
+        Some(sessions)
+    } else { None }
+}
+
+/// Sums session durations per day, week or game version. Legacy sessions only count towards per version totals
+/// because their start and end are made up from the old counter.
+pub fn get_playtime_totals<R: Runtime>(app: &AppHandle<R>, install_id: Option<String>, group_by: PlaytimeGrouping, since: i64) -> Option<Vec<PlaytimeTotal>> {
+    let mut rslt = vec![];
+    let sql = match group_by {
+        PlaytimeGrouping::Day => "SELECT date(started_at, 'unixepoch', 'localtime') AS period, SUM(duration) AS seconds, COUNT(*) AS sessions FROM play_sessions WHERE ($1 IS NULL OR install_id = $1) AND started_at >= $2 AND legacy = 0 GROUP BY period ORDER BY period DESC",
+        // ISO 8601 weeks, the Thursday of a week decides which year it belongs to
+        PlaytimeGrouping::Week => "SELECT strftime('%Y', started_at, 'unixepoch', 'localtime', '-3 days', 'weekday 4') || '-W' || printf('%02d', (strftime('%j', started_at, 'unixepoch', 'localtime', '-3 days', 'weekday 4') - 1) / 7 + 1) AS period, SUM(duration) AS seconds, COUNT(*) AS sessions FROM play_sessions WHERE ($1 IS NULL OR install_id = $1) AND started_at >= $2 AND legacy = 0 GROUP BY period ORDER BY period DESC",
+        PlaytimeGrouping::Version => "SELECT game_version AS period, SUM(duration) AS seconds, COUNT(*) AS sessions FROM play_sessions WHERE ($1 IS NULL OR install_id = $1) AND started_at >= $2 GROUP BY period ORDER BY MAX(started_at) DESC",
+    };
+
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query(sql).bind(install_id).bind(since);
+        rslt = query.fetch_all(&db).await.unwrap();
+    });
+
+    if rslt.len() >= 1 {
+        Some(rslt.iter().map(|r| PlaytimeTotal { period: r.get("period"), seconds: r.get("seconds"), sessions: r.get("sessions") }).collect())
+    } else { None }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PlaytimeGrouping {
+    Day,
+    Week,
+    Version,
+}
+
+pub fn delete_play_sessions_by_install_id<R: Runtime>(app: &AppHandle<R>, install_id: String) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("DELETE FROM play_sessions WHERE install_id = $1").bind(install_id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn create_launch_profile<R: Runtime>(app: &AppHandle<R>, profile: LaunchProfile) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("INSERT INTO launch_profiles (id, install_id, name, launch_args, env_vars, use_mangohud, use_gamemode, use_xxmi, use_fps_unlock, fps_value, graphics_api, created_at, env_list) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)").bind(profile.id).bind(profile.install_id).bind(profile.name).bind(profile.launch_args).bind(profile.env_vars).bind(profile.use_mangohud).bind(profile.use_gamemode).bind(profile.use_xxmi).bind(profile.use_fps_unlock).bind(profile.fps_value).bind(profile.graphics_api).bind(profile.created_at).bind(profile.env_list);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+/// Everything but the install and creation time can change.
+pub fn update_launch_profile_by_id<R: Runtime>(app: &AppHandle<R>, profile: LaunchProfile) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("UPDATE launch_profiles SET 'name' = $1, 'launch_args' = $2, 'env_vars' = $3, 'use_mangohud' = $4, 'use_gamemode' = $5, 'use_xxmi' = $6, 'use_fps_unlock' = $7, 'fps_value' = $8, 'graphics_api' = $9, 'env_list' = $10 WHERE id = $11").bind(profile.name).bind(profile.launch_args).bind(profile.env_vars).bind(profile.use_mangohud).bind(profile.use_gamemode).bind(profile.use_xxmi).bind(profile.use_fps_unlock).bind(profile.fps_value).bind(profile.graphics_api).bind(profile.env_list).bind(profile.id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn get_launch_profile_by_id<R: Runtime>(app: &AppHandle<R>, id: String) -> Option<LaunchProfile> {
+    get_launch_profiles(app, "SELECT * FROM launch_profiles WHERE id = $1", id).into_iter().next()
+}
+
+pub fn get_launch_profiles_by_install_id<R: Runtime>(app: &AppHandle<R>, install_id: String) -> Vec<LaunchProfile> {
+    get_launch_profiles(app, "SELECT * FROM launch_profiles WHERE install_id = $1 ORDER BY created_at ASC", install_id)
+}
+
+fn get_launch_profiles<R: Runtime>(app: &AppHandle<R>, sql: &'static str, bind: String) -> Vec<LaunchProfile> {
+    let mut rslt = vec![];
+
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        rslt = query(sql).bind(bind).fetch_all(&db).await.unwrap();
+    });
+
+    rslt.into_iter().map(|r| LaunchProfile {
+        id: r.get("id"),
+        install_id: r.get("install_id"),
+        name: r.get("name"),
+        launch_args: r.get("launch_args"),
+        env_vars: r.get("env_vars"),
+        use_mangohud: r.get("use_mangohud"),
+        use_gamemode: r.get("use_gamemode"),
+        use_xxmi: r.get("use_xxmi"),
+        use_fps_unlock: r.get("use_fps_unlock"),
+        fps_value: r.get("fps_value"),
+        graphics_api: r.get("graphics_api"),
+        created_at: r.get("created_at"),
+        env_list: r.get("env_list"),
+    }).collect()
+}
+
+pub fn delete_launch_profile_by_id<R: Runtime>(app: &AppHandle<R>, id: String) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("DELETE FROM launch_profiles WHERE id = $1").bind(id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
+pub fn delete_launch_profiles_by_install_id<R: Runtime>(app: &AppHandle<R>, install_id: String) {
+    run_async_command(async {
+        let db = app.state::<DbInstances>().0.lock().await.get("db").unwrap().clone();
+        let query = query("DELETE FROM launch_profiles WHERE install_id = $1").bind(install_id);
+        query.execute(&db).await.unwrap();
+    });
+}
+
 // === DB RELATED ===
 
 fn add_migrations(
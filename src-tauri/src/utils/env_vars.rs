@@ -0,0 +1,107 @@
+use crate::utils::models::{EnvVar, LauncherInstall};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::process::Command;
+
+/// Why an entry of the env var editor was rejected, `index` points into the submitted list.
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvVarError {
+    pub index: usize,
+    pub key: String,
+    /// "invalid_name", "duplicate", "invalid_value" or "invalid_dll_overrides".
+    pub reason: String,
+}
+
+/// A variable both the launcher and the install set, `winner` is "user", or "merged" for WINEDLLOVERRIDES.
+#[derive(Debug, Clone, Serialize)]
+pub struct EnvConflict {
+    pub key: String,
+    pub launcher_value: String,
+    pub user_value: String,
+    pub result: String,
+    pub winner: String,
+}
+
+/// Installs saved before the typed list existed only have the `KEY=value;KEY2=value` string.
+pub fn parse_legacy_env_vars(env_vars: &str) -> Vec<EnvVar> {
+    env_vars.split(";").filter_map(|env| {
+        let mut tmp = env.splitn(2, "=");
+        match (tmp.next(), tmp.next()) { (Some(k), Some(v)) if !k.trim().is_empty() => Some(EnvVar { key: k.trim().to_string(), value: v.replace("\"", ""), enabled: true }), _ => None }
+    }).collect()
+}
+
+pub fn install_env(install: &LauncherInstall) -> Vec<EnvVar> {
+    if install.env_list.is_empty() { parse_legacy_env_vars(&install.env_vars) } else { install.env_list.0.clone() }
+}
+
+/// Disabled entries are checked as well so they can be turned on later without another round of errors.
+/// Duplicates only count between enabled entries, keeping a disabled alternative around is fine.
+pub fn validate_env_vars(vars: &[EnvVar]) -> Vec<EnvVarError> {
+    let mut errors = Vec::new();
+    let mut seen: Vec<&str> = Vec::new();
+    for (index, v) in vars.iter().enumerate() {
+        let err = |reason: &str| EnvVarError { index, key: v.key.clone(), reason: reason.to_string() };
+        if !is_valid_name(&v.key) { errors.push(err("invalid_name")); continue; }
+        if v.value.contains('\0') { errors.push(err("invalid_value")); continue; }
+        if v.key == "WINEDLLOVERRIDES" && parse_dll_overrides(&v.value).is_none() { errors.push(err("invalid_dll_overrides")); continue; }
+        if v.enabled {
+            if seen.contains(&v.key.as_str()) { errors.push(err("duplicate")); } else { seen.push(v.key.as_str()); }
+        }
+    }
+    errors
+}
+
+/// Puts the install's env vars on top of what the launcher already set on `cmd`.
+/// User values win, except WINEDLLOVERRIDES where both lists are merged and the user entry wins per dll.
+pub fn apply_install_env_vars(cmd: &mut Command, install: &LauncherInstall) -> Vec<EnvConflict> {
+    let launcher = cmd.get_envs().filter_map(|(k, v)| Some((k.to_str()?.to_string(), v?.to_str()?.to_string()))).collect::<BTreeMap<String, String>>();
+    let (vars, conflicts) = merge_env(&launcher, &install_env(install));
+    for (k, v) in vars { cmd.env(k, v); }
+    conflicts
+}
+
+pub fn merge_env(launcher: &BTreeMap<String, String>, user: &[EnvVar]) -> (Vec<(String, String)>, Vec<EnvConflict>) {
+    let mut vars: Vec<(String, String)> = Vec::new();
+    for v in user.iter().filter(|v| v.enabled) {
+        // Bad entries can only come from legacy strings, the editor refuses to save them
+        if !is_valid_name(&v.key) { log::warn!("Skipping invalid environment variable name \"{}\"", v.key); continue; }
+        vars.retain(|(k, _)| *k != v.key);
+        vars.push((v.key.clone(), v.value.clone()));
+    }
+
+    let mut conflicts = Vec::new();
+    for (key, value) in vars.iter_mut() {
+        let Some(launcher_value) = launcher.get(key.as_str()) else { continue; };
+        if launcher_value == value { continue; }
+        let user_value = value.clone();
+        let dll_overrides = if key == "WINEDLLOVERRIDES" { parse_dll_overrides(launcher_value).zip(parse_dll_overrides(value)) } else { None };
+        let winner = match dll_overrides {
+            Some((base, extra)) => { *value = join_dll_overrides(base, extra); "merged" }
+            None => "user",
+        };
+        conflicts.push(EnvConflict { key: key.clone(), launcher_value: launcher_value.clone(), user_value, result: value.clone(), winner: winner.to_string() });
+    }
+    (vars, conflicts)
+}
+
+fn is_valid_name(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// `dll1,dll2=n,b;dll3=d` into one (dll, mode) pair per dll, None when an entry has no `=` or an unknown mode.
+fn parse_dll_overrides(value: &str) -> Option<Vec<(String, String)>> {
+    let mut out = Vec::new();
+    for entry in value.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+        let (dlls, mode) = entry.split_once('=')?;
+        if !mode.split(',').all(|m| matches!(m.trim(), "" | "n" | "b" | "d")) { return None; }
+        for dll in dlls.split(',').map(str::trim).filter(|d| !d.is_empty()) { out.push((dll.to_string(), mode.trim().to_string())); }
+    }
+    Some(out)
+}
+
+fn join_dll_overrides(base: Vec<(String, String)>, extra: Vec<(String, String)>) -> String {
+    let mut merged = base.into_iter().filter(|(dll, _)| !extra.iter().any(|(e, _)| e.eq_ignore_ascii_case(dll))).collect::<Vec<(String, String)>>();
+    merged.extend(extra);
+    merged.into_iter().map(|(dll, mode)| format!("{}={}", dll, mode)).collect::<Vec<String>>().join(";")
+}
@@ -1,13 +1,28 @@
-use crate::utils::models::{GameManifest, GlobalSettings, LauncherInstall};
+use crate::utils::models::{GameManifest, GlobalSettings, LaunchHookStage, LauncherInstall};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use crate::utils::{apply_xxmi_tweaks,get_mi_path_from_game,prevent_system_idle,show_dialog_with_callback};
 use std::process::{Command, Stdio};
 use tauri::{AppHandle, Runtime, Emitter, Error};
-use crate::utils::db_manager::{update_install_last_played_by_id,update_install_total_playtime_by_id};
+use crate::utils::db_manager::{create_play_session,update_install_last_played_by_id,update_install_total_playtime_by_id,update_play_session_end_by_id};
+#[cfg(target_os = "linux")]
+use crate::utils::db_manager::update_installed_runner_last_used_by_version;
 use crate::utils::discord_rpc;
-use fischl::utils::is_process_running;
+use crate::downloading::bandwidth::set_game_running;
+use crate::utils::launch_hooks::{run_launch_hooks, HookContext};
+use crate::utils::game_sessions::{attach_helper, begin_session, end_session, finish_launch, get_session, mark_running, mark_tracked, new_session, stop_helpers};
+use crate::utils::process_tree::GameProcessWatch;
+use crate::utils::session_logs::create_session_log;
+use crate::utils::env_vars::{apply_install_env_vars, EnvConflict};
 
 #[cfg(target_os = "linux")]
-use crate::utils::{get_steam_appid, get_steam_tool_appid, is_runner_lower, is_using_overriden_runner, runner_from_runner_version, update_steam_compat_config, repo_manager::get_compatibility};
+use crate::utils::models::GameCompatOverrides;
+#[cfg(target_os = "linux")]
+use crate::utils::session_logs::session_log_dir;
+#[cfg(target_os = "linux")]
+use crate::utils::{get_steam_appid, get_steam_tool_appid, is_runner_lower, is_using_overriden_runner, missing_runner_binaries, queue_runner_verify, update_steam_compat_config, local_runners::runner_compat};
+#[cfg(target_os = "linux")]
+use crate::utils::prefix_snapshots::is_prefix_busy;
 #[cfg(target_os = "linux")]
 use std::os::unix::process::CommandExt;
 #[cfg(target_os = "linux")]
@@ -15,12 +30,31 @@ use tauri::Manager;
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
 
+/// Paths and runner details `launch` and `preview_launch` resolve from the install before building the game command.
 #[cfg(target_os = "linux")]
-pub fn launch<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, gm: GameManifest, gs: GlobalSettings) -> Result<bool, Error> {
-    let Some(rm) = get_compatibility(&app, &runner_from_runner_version(app, install.runner_version.clone()).unwrap_or_default()) else { return Ok(false); };
-    let is_proton = rm.display_name.to_ascii_lowercase().contains("proton") && !rm.display_name.to_ascii_lowercase().contains("wine");
-    let mut compat_config = update_steam_compat_config(vec![]);
-    let cpo = gm.extra.compat_overrides.clone();
+struct LinuxLaunch {
+    is_proton: bool,
+    cpo: GameCompatOverrides,
+    dir: String,
+    prefixp: std::path::PathBuf,
+    prefix: String,
+    runner: String,
+    wine64: String,
+    game: String,
+    exe: String,
+    toolid: String,
+    steamrtp: std::path::PathBuf,
+    steamrt: String,
+    steamrt_path: String,
+    reaper: String,
+    appid: u32,
+    log_dir: String,
+}
+
+/// None when the runner of the install has no compatibility manifest.
+#[cfg(target_os = "linux")]
+fn resolve_linux_launch<R: Runtime>(app: &AppHandle<R>, install: &LauncherInstall, gm: &GameManifest, gs: &GlobalSettings) -> Result<Option<LinuxLaunch>, Error> {
+    let Some((rm, is_proton)) = runner_compat(app, &install.runner_version) else { return Ok(None); };
 
     let dirp = std::path::Path::new(install.directory.as_str());
     let dir = dirp.to_str().unwrap().to_string();
@@ -41,6 +75,87 @@ pub fn launch<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, gm: Game
     #[cfg(debug_assertions)]
     let reaper = app.path().resource_dir()?.join("resources/reaper").to_str().unwrap().to_string();
     let appid = get_steam_appid();
+    let wine64 = if rm.paths.wine64.is_empty() { rm.paths.wine32.clone() } else { rm.paths.wine64.clone() };
+    let log_dir = session_log_dir(app, &install.id).map(|p| p.to_str().unwrap().to_string()).unwrap_or_default();
+
+    Ok(Some(LinuxLaunch { is_proton, cpo: gm.extra.compat_overrides.clone(), dir, prefixp, prefix, runner, wine64, game, exe, toolid, steamrtp, steamrt, steamrt_path, reaper, appid, log_dir }))
+}
+
+#[cfg(target_os = "linux")]
+fn gamemode_available() -> bool {
+    std::env::var("PATH").unwrap_or_default().split(':').any(|dir| std::path::Path::new(dir).join("gamemoderun").exists())
+}
+
+/// Builds the game command without spawning it. Returns the command, the shell line it runs for logging and the env vars the install overrode.
+#[cfg(target_os = "linux")]
+fn build_linux_game_command(l: &LinuxLaunch, install: &LauncherInstall, gm: &GameManifest, gamemode_ok: bool) -> (Command, String, Vec<EnvConflict>) {
+    let LinuxLaunch { is_proton, cpo, dir, prefix, runner, wine64, game, steamrt, steamrt_path, reaper, appid, exe, log_dir, .. } = l;
+    let verb = if install.use_xxmi || install.use_fps_unlock { "run" } else { "waitforexitandrun" };
+    let drive = if cpo.proton_compat_config.contains(&"gamedrive".to_string()) { format!("s:\\{game}") } else { format!("z:\\{dir}/{game}") };
+    let args = launch_args(install, gm);
+
+    let default_command = if *is_proton {
+        let steamrt_run = format!("'{steamrt}' --verb={verb} -- '{reaper}' SteamLaunch AppId={appid} -- '{runner}/{wine64}' {verb} '{drive}' {args}");
+        if gamemode_ok { format!("gamemoderun {steamrt_run}") } else { format!("{steamrt_run}") }
+    } else {
+        if gamemode_ok { format!("gamemoderun '{runner}/{wine64}' '{dir}/{game}' {args}") } else { format!("'{runner}/{wine64}' '{dir}/{game}' {args}") }
+    };
+
+    let command = if install.launch_command.is_empty() { default_command } else {
+        // We assume user knows what he/she is doing so we just execute command that is configured without any checks
+        let c = install.launch_command.clone();
+        let c = if args.is_empty() { c } else { format!("{c} {args}") };
+        c.replace("%command%", default_command.as_str()).replace("%appid%", appid.to_string().as_str()).replace("%reaper%", reaper.as_str()).replace("%steamrt_path%", steamrt_path.as_str()).replace("%steamrt%", steamrt.as_str()).replace("%prefix%", prefix.as_str()).replace("%runner_dir%", runner.as_str()).replace("%runner%", &*(runner.clone() + "/" + wine64.as_str())).replace("%install_dir%", dir.as_str()).replace("%game_exe%", &*(dir.clone() + "/" + exe.as_str()))
+    };
+
+    let mut cmd = Command::new("bash");
+    cmd.arg("-c");
+    cmd.arg(&command);
+
+    cmd.env("SteamGameId", if gm.biz == "wuwa_global" { "3513350".to_string() } else { appid.to_string() });
+    cmd.env("SteamOS", "1");
+    cmd.env("WINEARCH", "win64");
+    cmd.env("WINEPREFIX", prefix.clone() + "/pfx");
+    cmd.env("STEAM_COMPAT_APP_ID", "0");
+    cmd.env("STEAM_COMPAT_DATA_PATH", prefix.clone());
+    cmd.env("STEAM_COMPAT_INSTALL_PATH", dir.clone());
+    cmd.env("STEAM_COMPAT_CLIENT_INSTALL_PATH", "");
+    cmd.env("STEAM_COMPAT_TOOL_PATHS", runner.clone());
+    cmd.env("STEAM_COMPAT_LIBRARY_PATHS", format!("{dir}:{prefix}/pfx"));
+    cmd.env("STEAM_COMPAT_SHADER_PATH", prefix.clone() + "/shadercache");
+    cmd.env("WINEDLLOVERRIDES", "lsteamclient=d;KRSDKExternal.exe=d");
+    if cpo.stub_wintrust { cmd.env("STUB_WINTRUST", "1"); }
+    if cpo.block_first_req { cmd.env("BLOCK_FIRST_REQ", "1"); }
+    if cpo.disable_protonfixes { cmd.env("PROTONFIXES_DISABLE", "1"); }
+    if !cpo.protonfixes_store.is_empty() { cmd.env("STORE", cpo.protonfixes_store.clone()); }
+    if !cpo.protonfixes_id.is_empty() { cmd.env("UMU_ID", cpo.protonfixes_id.clone()); }
+    let compat_config = if !cpo.proton_compat_config.is_empty() { update_steam_compat_config(cpo.proton_compat_config.iter().map(String::as_str).collect()) } else { update_steam_compat_config(vec![]) };
+    if cpo.stub_wintrust || cpo.block_first_req { cmd.env("WINEDLLOVERRIDES", "lsteamclient=d;KRSDKExternal.exe=d;jsproxy=n,b"); }
+    cmd.env("STEAM_COMPAT_CONFIG", compat_config);
+    if install.use_mangohud {
+        cmd.env("MANGOHUD", "1");
+        if install.mangohud_config_path != "" { cmd.env("MANGOHUD_CONFIGFILE", format!("{}", install.mangohud_config_path).as_str()); }
+    }
+    if install.proton_log && !log_dir.is_empty() { cmd.env("PROTON_LOG", "1"); cmd.env("PROTON_LOG_DIR", log_dir.clone()); }
+    if !install.wine_debug.is_empty() { cmd.env("WINEDEBUG", install.wine_debug.clone()); }
+
+    cmd.stdout(Stdio::inherit());
+    cmd.stderr(Stdio::inherit());
+    cmd.current_dir(dir.clone());
+    cmd.process_group(0);
+    let conflicts = apply_install_env_vars(&mut cmd, install);
+    (cmd, command, conflicts)
+}
+
+#[cfg(target_os = "linux")]
+pub fn launch<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, gm: GameManifest, gs: GlobalSettings) -> Result<bool, Error> {
+    if is_prefix_busy(&install.id) {
+        log::info!("Attempted to launch {} while its prefix is being snapshotted or restored", install.name);
+        show_dialog_with_callback(app, "warning", "TwintailLauncher", "dialogs.prefix_snapshot_busy", Some(vec!["dialogs.buttons.i_understand"]), None, Some(std::collections::HashMap::from([("install_name", install.name.as_str())])));
+        return Ok(false);
+    }
+    let Some(l) = resolve_linux_launch(app, &install, &gm, &gs)? else { return Ok(false); };
+    let LinuxLaunch { cpo, dir, prefixp, prefix, runner, wine64, exe, toolid, steamrtp, steamrt, steamrt_path, reaper, appid, is_proton, .. } = &l;
 
     if !steamrtp.exists() {
         log::info!("Attempted to launch {} with broken SteamRT (ToolID: {})! Pressing Repair SteamLinuxRuntime button in application settings is recommended.", install.name, toolid);
@@ -48,6 +163,13 @@ pub fn launch<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, gm: Game
         return Ok(false);
     }
 
+    if !missing_runner_binaries(app, &install.runner_version, std::path::Path::new(runner)).is_empty() {
+        log::warn!("Runner {} of {} is missing its binaries, queuing a verify job to repair it", install.runner_version, install.name);
+        queue_runner_verify(app, install.runner_version.clone(), runner.clone());
+        show_dialog_with_callback(app, "error", "TwintailLauncher", "dialogs.launch_runner_broken", Some(vec!["dialogs.buttons.i_understand"]), None, Some(std::collections::HashMap::from([("install_name", install.name.as_str()), ("runner_version", install.runner_version.as_str())])));
+        return Ok(false);
+    }
+
     if is_runner_lower(cpo.min_runner_versions.clone(), install.clone().runner_version) && !cpo.min_runner_versions.is_empty() {
         log::info!("Attempted to launch {} with runner version {} which is lower than the minimum required runner version(s) of {}!", install.name, install.runner_version, cpo.min_runner_versions.join(", "));
         let min_vers = cpo.min_runner_versions.join(", "); show_dialog_with_callback(app, "warning", "TwintailLauncher", "dialogs.launch_runner_unsupported", Some(vec!["dialogs.buttons.i_understand"]), None, Some(std::collections::HashMap::from([("install_name", install.name.as_str()), ("runner_version", install.runner_version.as_str()), ("min_runner_versions", min_vers.as_str())])));
@@ -60,6 +182,9 @@ pub fn launch<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, gm: Game
         return Ok(false);
     }
 
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs() as i64;
+    update_installed_runner_last_used_by_version(app, install.runner_version.clone(), now);
+
     // If prefix folder somehow does not exist remake it
     if !prefixp.exists() {
         if let Err(e) = std::fs::create_dir_all(&prefixp) {
@@ -69,217 +194,70 @@ pub fn launch<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, gm: Game
         };
     }
 
-    let pre_launch = install.pre_launch_command.clone();
-    let wine64 = if rm.paths.wine64.is_empty() { rm.paths.wine32.clone() } else { rm.paths.wine64.clone() };
-    let can_game_launch: Option<std::thread::JoinHandle<bool>> = if !prefixp.join("pfx").join("drive_c").exists() && !cpo.winetricks_verbs.is_empty() { Some(run_winetricks(app, install.clone(), steamrt.clone(), reaper.clone(), appid, runner.clone(), wine64.clone(), prefix.clone(), dir.clone(), cpo.winetricks_verbs.clone())) } else { None };
+    let can_game_launch: Option<std::thread::JoinHandle<bool>> = if !prefixp.join("pfx").join("drive_c").exists() && !cpo.winetricks_verbs.is_empty() { Some(run_winetricks(app, install.clone(), steamrt.clone(), reaper.clone(), *appid, runner.clone(), wine64.clone(), prefix.clone(), dir.clone(), cpo.winetricks_verbs.clone())) } else { None };
 
     // Wait for winetricks to fully exit before proceeding to game launch
     if let Some(handle) = can_game_launch { if !handle.join().unwrap_or(false) { return Ok(false); } }
 
-    if !pre_launch.is_empty() {
-        let command = format!("{pre_launch}").replace("%appid%", appid.clone().to_string().as_str()).replace("%reaper%", reaper.clone().as_str()).replace("%steamrt_path%", steamrt_path.clone().as_str()).replace("%steamrt%", steamrt.clone().as_str()).replace("%prefix%", prefix.clone().as_str()).replace("%runner_dir%", runner.clone().as_str()).replace("%runner%", &*(runner.clone() + "/" + wine64.as_str())).replace("%install_dir%", dir.clone().as_str()).replace("%game_exe%", &*(dir.clone() + "/" + exe.clone().as_str()));
-
-        let mut cmd = Command::new("bash");
-        cmd.arg("-c");
-        cmd.arg(&command);
-
-        cmd.env("WINEARCH", "win64");
-        cmd.env("WINEPREFIX", prefix.clone() + "/pfx");
-        cmd.env("STEAM_COMPAT_APP_ID", "0");
-        cmd.env("STEAM_COMPAT_DATA_PATH", prefix.clone());
-        cmd.env("STEAM_COMPAT_INSTALL_PATH", dir.clone());
-        cmd.env("STEAM_COMPAT_CLIENT_INSTALL_PATH", "");
-        cmd.env("STEAM_COMPAT_TOOL_PATHS", runner.clone());
-        cmd.env("STEAM_COMPAT_SHADER_PATH", prefix.clone() + "/shadercache");
-        cmd.env("WINEDLLOVERRIDES", "lsteamclient=d;KRSDKExternal.exe=d");
-        if cpo.disable_protonfixes { cmd.env("PROTONFIXES_DISABLE", "1"); }
-        if !cpo.protonfixes_store.is_empty() { cmd.env("STORE", cpo.protonfixes_store.clone()); }
-        if !cpo.protonfixes_id.is_empty() { cmd.env("UMU_ID", cpo.protonfixes_id.clone()); }
-
-        cmd.stdout(Stdio::inherit());
-        cmd.stderr(Stdio::inherit());
-        cmd.current_dir(dir.clone());
-        cmd.process_group(0);
-
-        match cmd.spawn() {
-            Ok(mut child) => match child.try_wait() {
-                Ok(Some(status)) => {
-                    if !status.success() { log::info!("Executing prelaunch command: \"{}\" failed with status: {}", command, status.code().unwrap()); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.prelaunch_cmd_failed", None, None, None); }
-                }
-                Ok(None) => { log::info!("Executing prelaunch command: \"{}\"", command); }
-                Err(_) => { show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.prelaunch_cmd_incorrect", None, None, None); }
-            },
-            Err(_) => { log::error!("Executing prelaunch command \"{}\" failed catastrophically!", command); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.prelaunch_cmd_critical", None, None, None); }
-        }
-    }
-
-    let verb = if install.use_xxmi || install.use_fps_unlock { "run" } else { "waitforexitandrun" };
-    let drive = if cpo.proton_compat_config.contains(&"gamedrive".to_string()) { format!("s:\\{game}") } else { format!("z:\\{dir}/{game}") };
-
-    let mut args = install.launch_args.clone();
-    let xxmi_forced = install.use_xxmi && (gm.biz == "wuwa_global" || gm.biz == "endfield_global");
-    if install.use_xxmi && gm.biz == "wuwa_global" { args = args.split_whitespace().filter(|a| gm.extra.graphics_api_options.options.iter().all(|o| o.value.as_str() != *a)).collect::<Vec<_>>().join(" "); if !args.is_empty() { args += " "; } args += "-dx11"; }
-    if install.use_xxmi && gm.biz == "endfield_global" { args = args.split_whitespace().filter(|a| gm.extra.graphics_api_options.options.iter().all(|o| o.value.as_str() != *a)).collect::<Vec<_>>().join(" "); if !args.is_empty() { args += " "; } args += "-force-d3d11"; }
-    if gm.extra.switches.graphics_api && !xxmi_forced && !args.split_whitespace().any(|a| gm.extra.graphics_api_options.options.iter().any(|o| o.value.as_str() == a)) && !install.graphics_api.is_empty() { if !args.is_empty() { args += " "; } args += &install.graphics_api; }
+    let hook_ctx = HookContext::new(&install, dir.clone()).var("%appid%", appid.to_string()).var("%reaper%", reaper.clone()).var("%steamrt_path%", steamrt_path.clone()).var("%steamrt%", steamrt.clone()).var("%prefix%", prefix.clone()).var("%runner_dir%", runner.clone()).var("%runner%", runner.clone() + "/" + wine64.as_str()).var("%install_dir%", dir.clone()).var("%game_exe%", dir.clone() + "/" + exe.as_str())
+        .env("WINEARCH", "win64".to_string()).env("WINEPREFIX", prefix.clone() + "/pfx").env("STEAM_COMPAT_APP_ID", "0".to_string()).env("STEAM_COMPAT_DATA_PATH", prefix.clone()).env("STEAM_COMPAT_INSTALL_PATH", dir.clone()).env("STEAM_COMPAT_CLIENT_INSTALL_PATH", String::new()).env("STEAM_COMPAT_TOOL_PATHS", runner.clone());
+    if !run_launch_hooks(app, &install.launch_hooks, LaunchHookStage::PreLaunch, &hook_ctx) { log::info!("Launch of {} aborted by a required pre launch hook", install.name); return Ok(false); }
 
     let gamemode_ok = if install.use_gamemode && !crate::utils::is_flatpak() {
-        let found = std::env::var("PATH").unwrap_or_default().split(':').any(|dir| std::path::Path::new(dir).join("gamemoderun").exists());
+        let found = gamemode_available();
         if !found { show_dialog_with_callback(app, "warning", "TwintailLauncher", "dialogs.gamemode_not_found", Some(vec!["dialogs.buttons.i_understand"]), None, None); }
         found
     } else { install.use_gamemode };
 
-    let default_command = if is_proton {
-        let steamrt_run = format!("'{steamrt}' --verb={verb} -- '{reaper}' SteamLaunch AppId={appid} -- '{runner}/{wine64}' {verb} '{drive}' {args}");
-        if gamemode_ok { format!("gamemoderun {steamrt_run}") } else { format!("{steamrt_run}") }
-    } else {
-        if gamemode_ok { format!("gamemoderun '{runner}/{wine64}' '{dir}/{game}' {args}") } else { format!("'{runner}/{wine64}' '{dir}/{game}' {args}") }
-    };
-
-    let rslt = if install.launch_command.is_empty() {
-        let mut cmd = Command::new("bash");
-        cmd.arg("-c");
-        cmd.arg(&default_command);
-
-        cmd.env("SteamGameId", if gm.biz == "wuwa_global" { "3513350".to_string() } else { appid.clone().to_string() });
-        cmd.env("SteamOS", "1");
-        cmd.env("WINEARCH", "win64");
-        cmd.env("WINEPREFIX", prefix.clone() + "/pfx");
-        cmd.env("STEAM_COMPAT_APP_ID", "0");
-        cmd.env("STEAM_COMPAT_DATA_PATH", prefix.clone());
-        cmd.env("STEAM_COMPAT_INSTALL_PATH", dir.clone());
-        cmd.env("STEAM_COMPAT_CLIENT_INSTALL_PATH", "");
-        cmd.env("STEAM_COMPAT_TOOL_PATHS", runner.clone());
-        cmd.env("STEAM_COMPAT_LIBRARY_PATHS", format!("{dir}:{prefix}/pfx"));
-        cmd.env("STEAM_COMPAT_SHADER_PATH", prefix.clone() + "/shadercache");
-        cmd.env("WINEDLLOVERRIDES", "lsteamclient=d;KRSDKExternal.exe=d");
-        if cpo.stub_wintrust { cmd.env("STUB_WINTRUST", "1"); }
-        if cpo.block_first_req { cmd.env("BLOCK_FIRST_REQ", "1"); }
-        if cpo.disable_protonfixes { cmd.env("PROTONFIXES_DISABLE", "1"); }
-        if !cpo.protonfixes_store.is_empty() { cmd.env("STORE", cpo.protonfixes_store); }
-        if !cpo.protonfixes_id.is_empty() { cmd.env("UMU_ID", cpo.protonfixes_id); }
-        if !cpo.proton_compat_config.is_empty() { compat_config = update_steam_compat_config(cpo.proton_compat_config.iter().map(String::as_str).collect()); }
-        if cpo.stub_wintrust || cpo.block_first_req { cmd.env("WINEDLLOVERRIDES", "lsteamclient=d;KRSDKExternal.exe=d;jsproxy=n,b"); crate::utils::apply_patch(app, std::path::Path::new(&dir.clone()).to_str().unwrap().to_string(), "sparkle".to_string(), "add".to_string()); } else if !cpo.stub_wintrust && !cpo.block_first_req { crate::utils::apply_patch(app, std::path::Path::new(&dir.clone()).to_str().unwrap().to_string(), "sparkle".to_string(), "remove".to_string()); }
-        cmd.env("STEAM_COMPAT_CONFIG", compat_config);
-        if install.use_mangohud {
-            cmd.env("MANGOHUD", "1");
-            if install.mangohud_config_path != "" { cmd.env("MANGOHUD_CONFIGFILE", format!("{}", install.clone().mangohud_config_path).as_str()); }
-        }
-
-        cmd.stdout(Stdio::inherit());
-        cmd.stderr(Stdio::inherit());
-        cmd.current_dir(dir.clone());
-        cmd.process_group(0);
-
-        if !install.env_vars.is_empty() {
-            let envs = install.env_vars.clone();
-            let splitted = envs.split(";").collect::<Vec<&str>>();
-            let parsed: Option<Vec<(&str, String)>> = splitted.iter().map(|env| {
-                    if env.is_empty() { return Some(None); }
-                    let mut tmp = env.splitn(2, "=");
-                    match (tmp.next(), tmp.next()) { (Some(k), Some(v)) if !k.is_empty() => Some(Some((k, v.replace("\"", "")))), _ => None }
-                }).collect::<Option<Vec<_>>>().and_then(|vec| Some(vec.into_iter().flatten().collect()));
-            if let Some(env_vars) = parsed { for (k, v) in env_vars { cmd.env(k, v); } }
-        }
+    let wineserver = std::path::Path::new(&runner).join(&wine64).parent().map(|p| p.join("wineserver")).filter(|p| p.exists()).map(|p| p.to_str().unwrap().to_string());
+    if !begin_session(app, new_session(&install.id, &install.name, &exe, &install.runner_version, &prefix, wineserver)) { log::warn!("{} is already running, not launching it again", install.name); return Ok(false); }
 
-        // Load before we spawn the game
-        load_xxmi(app, install.clone(), prefix.clone(), gs.xxmi_path.clone(), runner.clone(), wine64.clone(), exe.clone(), is_proton);
-        load_fps_unlock(app, install.clone(), gm.biz.clone(), prefix.clone(), gs.fps_unlock_path.clone(), dir.clone(), runner.clone(), wine64.clone(), is_proton);
+    let (mut cmd, command, conflicts) = build_linux_game_command(&l, &install, &gm, gamemode_ok);
+    for c in conflicts.iter() { log::debug!("{} of {} overrides the launcher value \"{}\" with \"{}\" ({})", c.key, install.name, c.launcher_value, c.result, c.winner); }
+    if let Some((stdout, stderr, _)) = create_session_log(app, &install.id, &install.name, &command) { cmd.stdout(stdout); cmd.stderr(stderr); }
+    if cpo.stub_wintrust || cpo.block_first_req { crate::utils::apply_patch(app, dir.clone(), "sparkle".to_string(), "add".to_string()); } else { crate::utils::apply_patch(app, dir.clone(), "sparkle".to_string(), "remove".to_string()); }
 
-        match cmd.spawn() {
-            Ok(mut child) => match child.try_wait() {
-                Ok(Some(status)) => {
-                    if !status.success() { log::info!("Executing launch command: \"{}\" failed with status: {}", default_command, status.code().unwrap()); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_failed", None, None, None); }
-                }
-                Ok(None) => {
-                    let time = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs().to_string();
-                    update_install_last_played_by_id(app, install.id.clone(), time);
-                    start_playtime_tracker(app, install.clone(), gm.clone(), exe.clone());
-                    log::info!("Executing launch command: \"{}\"", default_command);
-                }
-                Err(_) => { log::error!("Executing launch command: \"{}\" failed! Is command correct?", default_command); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_incorrect", None, None, None); }
-            },
-            Err(_) => { log::error!("Executing launch command \"{}\" failed catastrophically!", default_command); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_critical", None, None, None); }
-        }
-        true
-    } else {
-        // We assume user knows what he/she is doing so we just execute command that is configured without any checks
-        let c = install.launch_command.clone();
-        let mut args = install.launch_args.clone();
-        let mut command = format!("{c}").replace("%command%", default_command.clone().to_string().as_str()).replace("%appid%", appid.clone().to_string().as_str()).replace("%reaper%", reaper.clone().as_str()).replace("%steamrt_path%", steamrt_path.clone().as_str()).replace("%steamrt%", steamrt.clone().as_str()).replace("%prefix%", prefix.clone().as_str()).replace("%runner_dir%", runner.clone().as_str()).replace("%runner%", &*(runner.clone() + "/" + wine64.as_str())).replace("%install_dir%", dir.clone().as_str()).replace("%game_exe%", &*(dir.clone() + "/" + exe.clone().as_str()));
+    // Load before we spawn the game
+    load_xxmi(app, install.clone(), prefix.clone(), gs.xxmi_path.clone(), runner.clone(), wine64.clone(), exe.clone(), *is_proton);
+    load_fps_unlock(app, install.clone(), gm.biz.clone(), prefix.clone(), gs.fps_unlock_path.clone(), dir.clone(), runner.clone(), wine64.clone(), *is_proton);
 
-        let xxmi_forced = install.use_xxmi && (gm.biz == "wuwa_global" || gm.biz == "endfield_global");
-        if install.use_xxmi && gm.biz == "wuwa_global" { args = args.split_whitespace().filter(|a| gm.extra.graphics_api_options.options.iter().all(|o| o.value.as_str() != *a)).collect::<Vec<_>>().join(" "); if !args.is_empty() { args += " "; } args += "-dx11"; }
-        if install.use_xxmi && gm.biz == "endfield_global" { args = args.split_whitespace().filter(|a| gm.extra.graphics_api_options.options.iter().all(|o| o.value.as_str() != *a)).collect::<Vec<_>>().join(" "); if !args.is_empty() { args += " "; } args += "-force-d3d11"; }
-        if gm.extra.switches.graphics_api && !xxmi_forced && !args.split_whitespace().any(|a| gm.extra.graphics_api_options.options.iter().any(|o| o.value.as_str() == a)) && !install.graphics_api.is_empty() { if !args.is_empty() { args += " "; } args += &install.graphics_api; }
-        if !args.is_empty() { command = format!("{c} {args}").replace("%command%", default_command.clone().to_string().as_str()).replace("%appid%", appid.clone().to_string().as_str()).replace("%reaper%", reaper.clone().as_str()).replace("%steamrt_path%", steamrt_path.clone().as_str()).replace("%steamrt%", steamrt.clone().as_str()).replace("%prefix%", prefix.clone().as_str()).replace("%runner_dir%", runner.clone().as_str()).replace("%runner%", &*(runner.clone() + "/" + wine64.as_str())).replace("%install_dir%", dir.clone().as_str()).replace("%game_exe%", &*(dir.clone() + "/" + exe.clone().as_str())); }
-
-        let mut cmd = Command::new("bash");
-        cmd.arg("-c");
-        cmd.arg(&command);
-
-        cmd.env("SteamGameId", if gm.biz == "wuwa_global" { "3513350".to_string() } else { appid.clone().to_string() });
-        cmd.env("SteamOS", "1");
-        cmd.env("WINEARCH", "win64");
-        cmd.env("WINEPREFIX", prefix.clone() + "/pfx");
-        cmd.env("STEAM_COMPAT_APP_ID", "0");
-        cmd.env("STEAM_COMPAT_DATA_PATH", prefix.clone());
-        cmd.env("STEAM_COMPAT_INSTALL_PATH", dir.clone());
-        cmd.env("STEAM_COMPAT_CLIENT_INSTALL_PATH", "");
-        cmd.env("STEAM_COMPAT_TOOL_PATHS", runner.clone());
-        cmd.env("STEAM_COMPAT_LIBRARY_PATHS", format!("{dir}:{prefix}/pfx"));
-        cmd.env("STEAM_COMPAT_SHADER_PATH", prefix.clone() + "/shadercache");
-        cmd.env("WINEDLLOVERRIDES", "lsteamclient=d;KRSDKExternal.exe=d");
-        if cpo.stub_wintrust { cmd.env("STUB_WINTRUST", "1"); }
-        if cpo.block_first_req { cmd.env("BLOCK_FIRST_REQ", "1"); }
-        if cpo.disable_protonfixes { cmd.env("PROTONFIXES_DISABLE", "1"); }
-        if !cpo.protonfixes_store.is_empty() { cmd.env("STORE", cpo.protonfixes_store); }
-        if !cpo.protonfixes_id.is_empty() { cmd.env("UMU_ID", cpo.protonfixes_id); }
-        if !cpo.proton_compat_config.is_empty() { compat_config = update_steam_compat_config(cpo.proton_compat_config.iter().map(String::as_str).collect()); }
-        if cpo.stub_wintrust || cpo.block_first_req { cmd.env("WINEDLLOVERRIDES", "lsteamclient=d;KRSDKExternal.exe=d;jsproxy=n,b"); crate::utils::apply_patch(app, std::path::Path::new(&dir.clone()).to_str().unwrap().to_string(), "sparkle".to_string(), "add".to_string()); } else if !cpo.stub_wintrust && !cpo.block_first_req { crate::utils::apply_patch(app, std::path::Path::new(&dir.clone()).to_str().unwrap().to_string(), "sparkle".to_string(), "remove".to_string()); }
-        cmd.env("STEAM_COMPAT_CONFIG", compat_config);
-        if install.use_mangohud {
-            cmd.env("MANGOHUD", "1");
-            if install.mangohud_config_path != "" { cmd.env("MANGOHUD_CONFIGFILE", format!("{}", install.clone().mangohud_config_path).as_str()); }
-        }
-
-        cmd.stdout(Stdio::inherit());
-        cmd.stderr(Stdio::inherit());
-        cmd.current_dir(dir.clone());
-        cmd.process_group(0);
-
-        if !install.env_vars.is_empty() {
-            let envs = install.env_vars.clone();
-            let splitted = envs.split(";").collect::<Vec<&str>>();
-            let parsed: Option<Vec<(&str, String)>> = splitted.iter().map(|env| {
-                    if env.is_empty() { return Some(None); }
-                    let mut tmp = env.splitn(2, "=");
-                    match (tmp.next(), tmp.next()) { (Some(k), Some(v)) if !k.is_empty() => Some(Some((k, v.replace("\"", "")))), _ => None }
-                }).collect::<Option<Vec<_>>>().and_then(|vec| Some(vec.into_iter().flatten().collect()));
-            if let Some(env_vars) = parsed { for (k, v) in env_vars { cmd.env(k, v); } }
-        }
-
-        // Load before we spawn the game
-        load_xxmi(app, install.clone(), prefix.clone(), gs.xxmi_path.clone(), runner.clone(), wine64.clone(), exe.clone(), is_proton);
-        load_fps_unlock(app, install.clone(), gm.biz.clone(), prefix.clone(), gs.fps_unlock_path.clone(), dir.clone(), runner.clone(), wine64.clone(), is_proton);
+    match cmd.spawn() {
+        Ok(mut child) => match child.try_wait() {
+            Ok(Some(status)) => {
+                if !status.success() { log::info!("Executing launch command: \"{}\" failed with status: {}", command, status.code().unwrap()); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_failed", None, None, None); }
+                // No tracker runs for a game that is already gone, undo the pre launch hooks here
+                run_launch_hooks(app, &install.launch_hooks, LaunchHookStage::PostExit, &hook_ctx);
+            }
+            Ok(None) => {
+                let time = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs().to_string();
+                update_install_last_played_by_id(app, install.id.clone(), time);
+                start_playtime_tracker(app, install.clone(), gm.clone(), GameProcessWatch::new(child, exe.clone()), hook_ctx.clone());
+                log::info!("Executing launch command: \"{}\"", command);
+            }
+            Err(_) => { log::error!("Executing launch command: \"{}\" failed! Is command correct?", command); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_incorrect", None, None, None); run_launch_hooks(app, &install.launch_hooks, LaunchHookStage::PostExit, &hook_ctx); }
+        },
+        Err(_) => { log::error!("Executing launch command \"{}\" failed catastrophically!", command); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_critical", None, None, None); run_launch_hooks(app, &install.launch_hooks, LaunchHookStage::PostExit, &hook_ctx); }
+    }
+    finish_launch(app, &install.id);
+    Ok(true)
+}
 
-        match cmd.spawn() {
-            Ok(mut child) => match child.try_wait() {
-                Ok(Some(status)) => {
-                    if !status.success() { log::info!("Executing launch command: \"{}\" failed with status: {}", command, status.code().unwrap()); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_failed", None, None, None); }
-                }
-                Ok(None) => {
-                    let time = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs().to_string();
-                    update_install_last_played_by_id(app, install.id.clone(), time);
-                    start_playtime_tracker(app, install.clone(), gm.clone(), exe.clone());
-                    log::info!("Executing launch command: \"{}\"", command);
-                }
-                Err(_) => { log::error!("Executing launch command: \"{}\" failed! Is command correct?", command); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_incorrect", None, None, None); }
-            },
-            Err(_) => { log::error!("Executing launch command \"{}\" failed catastrophically!", command); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_critical", None, None, None); }
-        }
-        true
-    };
-    if rslt { Ok(true) } else { Ok(false) }
+/// Resolves the game command exactly like `launch` would without running anything, for bug reports.
+#[cfg(target_os = "linux")]
+pub fn preview_launch<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, gm: GameManifest, gs: GlobalSettings) -> Result<Option<LaunchPreview>, Error> {
+    let Some(l) = resolve_linux_launch(app, &install, &gm, &gs)? else { return Ok(None); };
+    let mut warnings = Vec::new();
+    if !l.steamrtp.exists() { warnings.push(format!("SteamRT entry point {} is missing", l.steamrt)); }
+    if is_runner_lower(l.cpo.min_runner_versions.clone(), install.runner_version.clone()) && !l.cpo.min_runner_versions.is_empty() { warnings.push(format!("Runner {} is lower than the minimum of {}", install.runner_version, l.cpo.min_runner_versions.join(", "))); }
+    if l.cpo.override_runner.linux.enabled && !l.cpo.override_runner.linux.runner_version.is_empty() && !is_using_overriden_runner(install.runner_version.clone(), l.cpo.override_runner.linux.runner_version.clone()) { warnings.push(format!("Runner {} is required", l.cpo.override_runner.linux.runner_version)); }
+    if !l.prefixp.join("pfx").exists() { warnings.push(format!("Prefix {} is not initialized yet", l.prefix)); }
+    let gamemode_ok = if install.use_gamemode && !crate::utils::is_flatpak() { let found = gamemode_available(); if !found { warnings.push("gamemoderun not found in PATH".to_string()); } found } else { install.use_gamemode };
+
+    let (cmd, _, conflicts) = build_linux_game_command(&l, &install, &gm, gamemode_ok);
+    let mut helpers = Vec::new();
+    if install.use_xxmi { helpers.push("xxmi".to_string()); }
+    if install.use_fps_unlock { helpers.push("fps_unlock".to_string()); }
+    Ok(Some(LaunchPreview::from_command(&cmd, helpers, warnings, conflicts)))
 }
 
 #[cfg(target_os = "linux")]
@@ -289,6 +267,7 @@ fn load_xxmi<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, prefix: S
         // Prevent "App is not responding" by waiting in a separate thread
         std::thread::spawn(move || {
             let app = appc.clone();
+            let install_id = install.id.clone();
             let xxmi_path = xxmi_path.clone();
             let mipath = get_mi_path_from_game(game.clone()).unwrap();
             let mi_pathbuf = std::path::Path::new(&xxmi_path).join(&mipath);
@@ -321,23 +300,14 @@ fn load_xxmi<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, prefix: S
             cmd.current_dir(xxmi_path.clone());
             cmd.process_group(0);
 
-            if !install.env_vars.is_empty() {
-                let envs = install.env_vars.clone();
-                let splitted = envs.split(";").collect::<Vec<&str>>();
-                let parsed: Option<Vec<(&str, String)>> = splitted.iter().map(|env| {
-                    if env.is_empty() { return Some(None); }
-                    let mut tmp = env.splitn(2, "=");
-                    match (tmp.next(), tmp.next()) { (Some(k), Some(v)) if !k.is_empty() => Some(Some((k, v.replace("\"", "")))), _ => None }
-                }).collect::<Option<Vec<_>>>().and_then(|vec| Some(vec.into_iter().flatten().collect()));
-                if let Some(env_vars) = parsed { for (k, v) in env_vars { cmd.env(k, v); } }
-            }
+            apply_install_env_vars(&mut cmd, &install);
 
             match cmd.spawn() {
                 Ok(mut child) => match child.try_wait() {
                     Ok(Some(status)) => {
                         if !status.success() { log::info!("Executing XXMI command: \"{}\" failed with status: {}", command, status.code().unwrap()); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.xxmi_run_failed", None, None, None); }
                     }
-                    Ok(None) => { log::info!("Executing XXMI command: \"{}\"", command); }
+                    Ok(None) => { attach_helper(&app, &install_id, "xxmi", child.id()); log::info!("Executing XXMI command: \"{}\"", command); }
                     Err(_) => { log::error!("Executing XXMI command: \"{}\" failed! Is command correct?", command); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.xxmi_run_retry", None, None, None); }
                 },
                 Err(_) => { log::error!("Executing XXMI command \"{}\" failed catastrophically!", command); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.xxmi_run_critical", None, None, None); }
@@ -376,23 +346,14 @@ fn load_fps_unlock<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, biz
             cmd.current_dir(fpsunlock_path.clone());
             cmd.process_group(0);
 
-            if !install.env_vars.is_empty() {
-                let envs = install.env_vars.clone();
-                let splitted = envs.split(";").collect::<Vec<&str>>();
-                let parsed: Option<Vec<(&str, String)>> = splitted.iter().map(|env| {
-                    if env.is_empty() { return Some(None); }
-                    let mut tmp = env.splitn(2, "=");
-                    match (tmp.next(), tmp.next()) { (Some(k), Some(v)) if !k.is_empty() => Some(Some((k, v.replace("\"", "")))), _ => None }
-                }).collect::<Option<Vec<_>>>().and_then(|vec| Some(vec.into_iter().flatten().collect()));
-                if let Some(env_vars) = parsed { for (k, v) in env_vars { cmd.env(k, v); } }
-            }
+            apply_install_env_vars(&mut cmd, &install);
 
             match cmd.spawn() {
                 Ok(mut child) => match child.try_wait() {
                     Ok(Some(status)) => {
                         if !status.success() { log::info!("Executing FPS Unlocker command: \"{}\" failed with status: {}", command, status.code().unwrap()); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.fps_unlock_run_failed", None, None, None); }
                     }
-                    Ok(None) => { log::info!("Executing FPS Unlocker command: \"{}\"", command); }
+                    Ok(None) => { attach_helper(&app, &install.id, "fps_unlock", child.id()); log::info!("Executing FPS Unlocker command: \"{}\"", command); }
                     Err(_) => { log::error!("Executing FPS Unlocker command: \"{}\" failed! Is command correct?", command); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.fps_unlock_run_retry", None, None, None); }
                 },
                 Err(_) => { log::error!("Executing FPS Unlocker command \"{}\" failed catastrophically!", command); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.fps_unlock_run_critical", None, None, None); }
@@ -448,16 +409,7 @@ fn run_winetricks<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, stea
         cmd.current_dir(&install_dir);
         cmd.process_group(0);
 
-        if !install.env_vars.is_empty() {
-            let envs = install.env_vars.clone();
-            let splitted = envs.split(";").collect::<Vec<&str>>();
-            let parsed: Option<Vec<(&str, String)>> = splitted.iter().map(|env| {
-                if env.is_empty() { return Some(None); }
-                let mut tmp = env.splitn(2, "=");
-                match (tmp.next(), tmp.next()) { (Some(k), Some(v)) if !k.is_empty() => Some(Some((k, v.replace("\"", "")))), _ => None }
-            }).collect::<Option<Vec<_>>>().and_then(|vec| Some(vec.into_iter().flatten().collect()));
-            if let Some(env_vars) = parsed { for (k, v) in env_vars { cmd.env(k, v); } }
-        }
+        apply_install_env_vars(&mut cmd, &install);
 
         match cmd.spawn() {
             Ok(mut child) => {
@@ -475,6 +427,32 @@ fn run_winetricks<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, stea
     })
 }
 
+/// Builds the game command without spawning it. Returns the command, the program it runs for logging, whether it came from a custom launch command and the env vars the install overrode.
+#[cfg(target_os = "windows")]
+fn build_windows_game_command(install: &LauncherInstall, gm: &GameManifest) -> (Command, String, bool, Vec<EnvConflict>) {
+    let dir = install.directory.trim_matches('\\');
+    let game = gm.paths.exe_filename.trim_matches('\\');
+    let tmp = game.replace("/", "\\");
+
+    let full_path = std::path::Path::new(dir).join(&tmp);
+    let full_path_str = full_path.to_str().unwrap().replace("/", "\\");
+    let args = launch_args(install, gm);
+
+    // We assume user knows what he/she is doing so we just execute command that is configured without any checks
+    let custom = !install.launch_command.is_empty();
+    let program = if custom { install.launch_command.clone().replace("%install_dir%", dir).replace("%game_exe%", full_path_str.as_str()) } else { full_path_str };
+
+    let mut cmd = Command::new(&program);
+    if !args.is_empty() { cmd.args(args.split_whitespace().collect::<Vec<_>>()); }
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+
+    cmd.stdout(Stdio::inherit());
+    cmd.stderr(Stdio::inherit());
+    cmd.current_dir(dir);
+    let conflicts = apply_install_env_vars(&mut cmd, install);
+    (cmd, program, custom, conflicts)
+}
+
 #[cfg(target_os = "windows")]
 pub fn launch<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, gm: GameManifest, gs: GlobalSettings) -> Result<bool, Error> {
     let dirp = std::path::Path::new(&install.directory.clone()).to_path_buf();
@@ -482,138 +460,91 @@ pub fn launch<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, gm: Game
     let game = gm.paths.exe_filename.clone();
     let exe = gm.paths.exe_filename.clone().split('/').last().unwrap().to_string();
 
-    let pre_launch = install.pre_launch_command.clone();
-    if !pre_launch.is_empty() {
-        let mut cmd = Command::new(&pre_launch);
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
-
-        cmd.stdout(Stdio::inherit());
-        cmd.stderr(Stdio::inherit());
-        cmd.current_dir(dir.clone());
+    let hook_ctx = HookContext::new(&install, dir.trim_matches('\\').to_string()).var("%install_dir%", dir.trim_matches('\\').to_string()).var("%game_exe%", std::path::Path::new(dir.trim_matches('\\')).join(game.trim_matches('\\').replace("/", "\\")).to_str().unwrap().replace("/", "\\"));
+    if !run_launch_hooks(app, &install.launch_hooks, LaunchHookStage::PreLaunch, &hook_ctx) { log::info!("Launch of {} aborted by a required pre launch hook", install.name); return Ok(false); }
 
-        match cmd.spawn() {
-            Ok(mut child) => match child.try_wait() {
-                Ok(Some(status)) => {
-                    if !status.success() { log::info!("Executing prelaunch command: \"{}\" failed with status: {}", pre_launch, status.code().unwrap()); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.prelaunch_cmd_failed", None, None, None); }
-                }
-                Ok(None) => { log::info!("Executing prelaunch command: \"{}\"", pre_launch); }
-                Err(_) => { log::error!("Executing prelaunch command: \"{}\" failed! Is command correct?", pre_launch); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.prelaunch_cmd_incorrect", None, None, None); }
-            },
-            Err(_) => { log::error!("Executing prelaunch command \"{}\" failed catastrophically!", pre_launch); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.prelaunch_cmd_critical", None, None, None); }
-        }
-    }
+    if !begin_session(app, new_session(&install.id, &install.name, &exe, "", "", None)) { log::warn!("{} is already running, not launching it again", install.name); return Ok(false); }
 
     // Run xxmi first
     load_xxmi(app, install.clone(), gs.xxmi_path, exe.clone());
-    load_fps_unlock(install.clone(), gm.biz.clone(), dir.clone(), gs.fps_unlock_path);
-
-    let rslt = if install.launch_command.is_empty() {
-        let mut args = install.launch_args.clone();
-        let dir = dir.trim_matches('\\');
-        let game = game.trim_matches('\\');
-        let tmp = game.replace("/", "\\");
-
-        let full_path = std::path::Path::new(dir).join(&tmp);
-        let full_path_str = full_path.to_str().unwrap().replace("/", "\\");
-
-        let xxmi_forced = install.use_xxmi && (gm.biz == "wuwa_global" || gm.biz == "endfield_global");
-        if install.use_xxmi && gm.biz == "wuwa_global" { args = args.split_whitespace().filter(|a| gm.extra.graphics_api_options.options.iter().all(|o| o.value.as_str() != *a)).collect::<Vec<_>>().join(" "); if !args.is_empty() { args += " "; } args += "-dx11"; }
-        if install.use_xxmi && gm.biz == "endfield_global" { args = args.split_whitespace().filter(|a| gm.extra.graphics_api_options.options.iter().all(|o| o.value.as_str() != *a)).collect::<Vec<_>>().join(" "); if !args.is_empty() { args += " "; } args += "-force-d3d11"; }
-        if gm.extra.switches.graphics_api && !xxmi_forced && !args.split_whitespace().any(|a| gm.extra.graphics_api_options.options.iter().any(|o| o.value.as_str() == a)) && !install.graphics_api.is_empty() { if !args.is_empty() { args += " "; } args += &install.graphics_api; }
-
-        let mut cmd = Command::new(&full_path_str);
-        if !args.is_empty() { cmd.args(args.split_whitespace().collect::<Vec<_>>()); }
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    load_fps_unlock(app, install.clone(), gm.biz.clone(), dir.clone(), gs.fps_unlock_path);
+
+    let (mut cmd, program, custom, _) = build_windows_game_command(&install, &gm);
+    if let Some((stdout, stderr, _)) = create_session_log(app, &install.id, &install.name, &program) { cmd.stdout(stdout); cmd.stderr(stderr); }
+    match cmd.spawn() {
+        Ok(mut child) => match child.try_wait() {
+            Ok(Some(status)) => {
+                if !status.success() { log::info!("Executing launch command: \"{}\" failed with status: {}", program, status.code().unwrap()); show_dialog_with_callback(&app, "error", "TwintailLauncher", if custom { "dialogs.launch_cmd_failed" } else { "dialogs.launch_run_cmd_failed" }, None, None, None); }
+                // No tracker runs for a game that is already gone, undo the pre launch hooks here
+                run_launch_hooks(app, &install.launch_hooks, LaunchHookStage::PostExit, &hook_ctx);
+            }
+            Ok(None) => {
+                let time = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs().to_string();
+                update_install_last_played_by_id(app, install.id.clone(), time);
+                start_playtime_tracker(app, install.clone(), gm.clone(), GameProcessWatch::new(child, exe.clone()), hook_ctx.clone());
+                log::info!("Executing launch command: \"{}\"", program);
+            }
+            Err(_) => { log::error!("Executing launch command: \"{}\" failed! Is command correct?", program); show_dialog_with_callback(&app, "error", "TwintailLauncher", if custom { "dialogs.launch_cmd_incorrect" } else { "dialogs.launch_run_cmd_incorrect" }, None, None, None); run_launch_hooks(app, &install.launch_hooks, LaunchHookStage::PostExit, &hook_ctx); }
+        },
+        Err(_) => { log::error!("Executing launch command \"{}\" failed catastrophically!", program); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_critical", None, None, None); run_launch_hooks(app, &install.launch_hooks, LaunchHookStage::PostExit, &hook_ctx); }
+    }
+    finish_launch(app, &install.id);
+    Ok(true)
+}
 
-        cmd.stdout(Stdio::inherit());
-        cmd.stderr(Stdio::inherit());
-        cmd.current_dir(dir);
-
-        if !install.env_vars.is_empty() {
-            let envs = install.env_vars.clone();
-            let splitted = envs.split(";").collect::<Vec<&str>>();
-            let parsed: Option<Vec<(&str, String)>> = splitted.iter().map(|env| {
-                    if env.is_empty() { return Some(None); }
-                    let mut tmp = env.splitn(2, "=");
-                    match (tmp.next(), tmp.next()) { (Some(k), Some(v)) if !k.is_empty() => Some(Some((k, v.replace("\"", "")))), _ => None }
-                }).collect::<Option<Vec<_>>>().and_then(|vec| Some(vec.into_iter().flatten().collect()));
-            if let Some(env_vars) = parsed { for (k, v) in env_vars { cmd.env(k, v); } }
-        }
+/// Resolves the game command exactly like `launch` would without running anything, for bug reports.
+#[cfg(target_os = "windows")]
+pub fn preview_launch<R: Runtime>(_app: &AppHandle<R>, install: LauncherInstall, gm: GameManifest, _gs: GlobalSettings) -> Result<Option<LaunchPreview>, Error> {
+    let mut warnings = Vec::new();
+    let (cmd, program, custom, conflicts) = build_windows_game_command(&install, &gm);
+    if !custom && !std::path::Path::new(&program).exists() { warnings.push(format!("Game executable {} is missing", program)); }
+    let mut helpers = Vec::new();
+    if install.use_xxmi { helpers.push("xxmi".to_string()); }
+    if install.use_fps_unlock { helpers.push("fps_unlock".to_string()); }
+    Ok(Some(LaunchPreview::from_command(&cmd, helpers, warnings, conflicts)))
+}
 
-        match cmd.spawn() {
-            Ok(mut child) => match child.try_wait() {
-                Ok(Some(status)) => {
-                    if !status.success() { log::info!("Executing launch command: \"{}\" failed with status: {}", full_path_str, status.code().unwrap()); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_run_cmd_failed", None, None, None); }
-                }
-                Ok(None) => {
-                    let time = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs().to_string();
-                    update_install_last_played_by_id(app, install.id.clone(), time);
-                    start_playtime_tracker(app, install.clone(), gm.clone(), exe.clone());
-                    log::info!("Executing launch command: \"{}\"", full_path_str);
-                }
-                Err(_) => { log::error!("Executing launch command: \"{}\" failed! Is command correct?", full_path_str); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_run_cmd_incorrect", None, None, None); }
-            },
-            Err(_) => { log::error!("Executing launch command \"{}\" failed catastrophically!", full_path_str); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_critical", None, None, None); }
-        }
-        true
-    } else {
-        // We assume user knows what he/she is doing so we just execute command that is configured without any checks
-        let dir = dir.trim_matches('\\');
-        let game = game.trim_matches('\\');
-        let tmp = game.replace("/", "\\");
-
-        let full_path = std::path::Path::new(dir).join(&tmp);
-        let full_path_str = full_path.to_str().unwrap().replace("/", "\\");
-        let c = install.launch_command.clone().replace("%install_dir%", dir).replace("%game_exe%", full_path_str.as_str());
-        let mut args = install.launch_args.clone();
-
-        let xxmi_forced = install.use_xxmi && (gm.biz == "wuwa_global" || gm.biz == "endfield_global");
-        if install.use_xxmi && gm.biz == "wuwa_global" { args = args.split_whitespace().filter(|a| gm.extra.graphics_api_options.options.iter().all(|o| o.value.as_str() != *a)).collect::<Vec<_>>().join(" "); if !args.is_empty() { args += " "; } args += "-dx11"; }
-        if install.use_xxmi && gm.biz == "endfield_global" { args = args.split_whitespace().filter(|a| gm.extra.graphics_api_options.options.iter().all(|o| o.value.as_str() != *a)).collect::<Vec<_>>().join(" "); if !args.is_empty() { args += " "; } args += "-force-d3d11"; }
-        if gm.extra.switches.graphics_api && !xxmi_forced && !args.split_whitespace().any(|a| gm.extra.graphics_api_options.options.iter().any(|o| o.value.as_str() == a)) && !install.graphics_api.is_empty() { if !args.is_empty() { args += " "; } args += &install.graphics_api; }
-
-        let mut cmd = Command::new(&c);
-        if !args.is_empty() { cmd.args(args.split_whitespace().collect::<Vec<_>>()); }
-        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+/// What a launch of the install would run, `launch <id> --dry-run` on the command line.
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchPreview {
+    pub program: String,
+    pub argv: Vec<String>,
+    /// Only variables the launcher sets on top of its own environment.
+    pub env: BTreeMap<String, String>,
+    pub cwd: String,
+    /// Helpers `launch` starts before the game, XXMI or the FPS unlocker.
+    pub helpers: Vec<String>,
+    /// Problems `launch` would stop at or show a dialog for.
+    pub warnings: Vec<String>,
+    /// Variables set by both the launcher and the install and which value ended up in `env`.
+    pub conflicts: Vec<EnvConflict>,
+}
 
-        cmd.stdout(Stdio::inherit());
-        cmd.stderr(Stdio::inherit());
-        cmd.current_dir(dir);
-
-        if !install.env_vars.is_empty() {
-            let envs = install.env_vars.clone();
-            let splitted = envs.split(";").collect::<Vec<&str>>();
-            let parsed: Option<Vec<(&str, String)>> = splitted.iter().map(|env| {
-                    if env.is_empty() { return Some(None); }
-                    let mut tmp = env.splitn(2, "=");
-                    match (tmp.next(), tmp.next()) { (Some(k), Some(v)) if !k.is_empty() => Some(Some((k, v.replace("\"", "")))), _ => None }
-                }).collect::<Option<Vec<_>>>().and_then(|vec| Some(vec.into_iter().flatten().collect()));
-            if let Some(env_vars) = parsed { for (k, v) in env_vars { cmd.env(k, v); } }
-        }
+impl LaunchPreview {
+    fn from_command(cmd: &Command, helpers: Vec<String>, warnings: Vec<String>, conflicts: Vec<EnvConflict>) -> Self {
+        let program = cmd.get_program().to_string_lossy().to_string();
+        let argv = std::iter::once(program.clone()).chain(cmd.get_args().map(|a| a.to_string_lossy().to_string())).collect();
+        let env = cmd.get_envs().filter_map(|(k, v)| v.map(|v| (k.to_string_lossy().to_string(), v.to_string_lossy().to_string()))).collect();
+        let cwd = cmd.get_current_dir().map(|d| d.to_string_lossy().to_string()).unwrap_or_default();
+        LaunchPreview { program, argv, env, cwd, helpers, warnings, conflicts }
+    }
+}
 
-        match cmd.spawn() {
-            Ok(mut child) => match child.try_wait() {
-                Ok(Some(status)) => {
-                    if !status.success() { log::info!("Executing launch command: \"{}\" failed with status: {}", c, status.code().unwrap()); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_failed", None, None, None); }
-                }
-                Ok(None) => {
-                    let time = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs().to_string();
-                    update_install_last_played_by_id(app, install.id.clone(), time);
-                    start_playtime_tracker(app, install.clone(), gm.clone(), exe.clone());
-                    log::info!("Executing launch command: \"{}\"", c);
-                }
-                Err(_) => { log::error!("Executing launch command: \"{}\" failed! Is command correct?", c); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_incorrect", None, None, None); }
-            },
-            Err(_) => { log::error!("Executing launch command \"{}\" failed catastrophically!", c); show_dialog_with_callback(&app, "error", "TwintailLauncher", "dialogs.launch_cmd_critical", None, None, None); }
-        }
-        true
-    };
-    Ok(rslt)
+/// Launch args of the install with the graphics API switch applied, XXMI forces DX11 on the games it needs it for.
+fn launch_args(install: &LauncherInstall, gm: &GameManifest) -> String {
+    let mut args = install.launch_args.clone();
+    let xxmi_forced = install.use_xxmi && (gm.biz == "wuwa_global" || gm.biz == "endfield_global");
+    if install.use_xxmi && gm.biz == "wuwa_global" { args = args.split_whitespace().filter(|a| gm.extra.graphics_api_options.options.iter().all(|o| o.value.as_str() != *a)).collect::<Vec<_>>().join(" "); if !args.is_empty() { args += " "; } args += "-dx11"; }
+    if install.use_xxmi && gm.biz == "endfield_global" { args = args.split_whitespace().filter(|a| gm.extra.graphics_api_options.options.iter().all(|o| o.value.as_str() != *a)).collect::<Vec<_>>().join(" "); if !args.is_empty() { args += " "; } args += "-force-d3d11"; }
+    if gm.extra.switches.graphics_api && !xxmi_forced && !args.split_whitespace().any(|a| gm.extra.graphics_api_options.options.iter().any(|o| o.value.as_str() == a)) && !install.graphics_api.is_empty() { if !args.is_empty() { args += " "; } args += &install.graphics_api; }
+    args
 }
 
+
 #[cfg(target_os = "windows")]
 fn load_xxmi<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, xxmi_path: String, game: String) {
     if install.use_xxmi {
+        let install_id = install.id.clone();
         let xxmi_path = xxmi_path.trim_matches('\\');
         let mipath = get_mi_path_from_game(game.clone()).unwrap();
         let mi_pathbuf = std::path::Path::new(&xxmi_path).join(&mipath);
@@ -636,27 +567,17 @@ fn load_xxmi<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, xxmi_path
         cmd.stderr(Stdio::null());
         cmd.current_dir(xxmi_path);
 
-        if !install.env_vars.is_empty() {
-            let envs = install.env_vars.clone();
-            let splitted = envs.split(";").collect::<Vec<&str>>();
-            let parsed: Option<Vec<(&str, String)>> = splitted.iter().map(|env| {
-                if env.is_empty() { return Some(None); }
-                let mut tmp = env.splitn(2, "=");
-                match (tmp.next(), tmp.next()) { (Some(k), Some(v)) if !k.is_empty() => Some(Some((k, v.replace("\"", "")))), _ => None }
-            }).collect::<Option<Vec<_>>>().and_then(|vec| Some(vec.into_iter().flatten().collect()));
-            if let Some(env_vars) = parsed { for (k, v) in env_vars { cmd.env(k, v); } }
-        }
+        apply_install_env_vars(&mut cmd, &install);
 
-        let spawned = cmd.spawn();
-        if spawned.is_ok() {
+        if let Ok(child) = cmd.spawn() {
+            attach_helper(app, &install_id, "xxmi", child.id());
             log::info!("Executing XXMI command: \"{}\"", loader_path_str);
-            spawned.unwrap();
         }
     }
 }
 
 #[cfg(target_os = "windows")]
-fn load_fps_unlock(install: LauncherInstall, biz: String, game_path: String, fpsunlock_path: String) {
+fn load_fps_unlock<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, biz: String, game_path: String, fpsunlock_path: String) {
     if install.use_fps_unlock {
         let fpsunlock_path = fpsunlock_path.trim_matches('\\');
         let loader_path = std::path::Path::new(fpsunlock_path).join("keqing_unlock.exe");
@@ -671,64 +592,65 @@ fn load_fps_unlock(install: LauncherInstall, biz: String, game_path: String, fps
         cmd.stderr(Stdio::null());
         cmd.current_dir(fpsunlock_path);
 
-        if !install.env_vars.is_empty() {
-            let envs = install.env_vars.clone();
-            let splitted = envs.split(";").collect::<Vec<&str>>();
-            let parsed: Option<Vec<(&str, String)>> = splitted.iter().map(|env| {
-                if env.is_empty() { return Some(None); }
-                let mut tmp = env.splitn(2, "=");
-                match (tmp.next(), tmp.next()) { (Some(k), Some(v)) if !k.is_empty() => Some(Some((k, v.replace("\"", "")))), _ => None }
-            }).collect::<Option<Vec<_>>>().and_then(|vec| Some(vec.into_iter().flatten().collect()));
-            if let Some(env_vars) = parsed { for (k, v) in env_vars { cmd.env(k, v); } }
-        }
+        apply_install_env_vars(&mut cmd, &install);
 
-        let spawned = cmd.spawn();
-        if spawned.is_ok() {
+        if let Ok(child) = cmd.spawn() {
+            attach_helper(app, &install.id, "fps_unlock", child.id());
             log::info!("Executing FPS Unlocker command: \"{}\"", loader_path_str);
-            spawned.unwrap();
         }
     }
 }
 
-fn start_playtime_tracker<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, gm: GameManifest, exe_name: String) {
+fn start_playtime_tracker<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, gm: GameManifest, mut watch: GameProcessWatch, hook_ctx: HookContext) {
     let app = app.clone();
     let install_id = install.id.clone();
     let base_playtime = install.total_playtime as u64;
-    #[cfg(target_os = "linux")]
-    let exe_name = { let stem = exe_name.split('.').next().unwrap_or(&exe_name); stem[..stem.len().min(15)].to_string() };
+    mark_tracked(&app, &install_id, watch.root_pid());
     std::thread::spawn(move || {
         let mut last_db_write_elapsed: u64 = 0;
         const POLL_MS: u64 = 500;
         const MAX_WAIT_POLLS: u64 = 240;
         let mut appeared = false;
         for _ in 0..MAX_WAIT_POLLS {
-            if is_process_running(&exe_name) { appeared = true; break; }
+            let helpers = get_session(&app, &install_id).map(|s| s.helpers.iter().map(|h| h.pid).collect::<Vec<u32>>()).unwrap_or_default();
+            if watch.find(&helpers) { appeared = true; break; }
             std::thread::sleep(std::time::Duration::from_millis(POLL_MS));
         }
         if !appeared {
             if cfg!(target_os = "linux") && gm.biz != "wuwa_global" {
-                if install.use_xxmi && is_process_running("3dmloader.exe") { let _ = Command::new("bash").args(["-c", "for pid in $(pgrep -f 3dmloader.exe); do kill -9 -$pid; done"]).spawn(); log::debug!("Killing 3dmloader.exe as game crashed!"); }
-                if install.use_fps_unlock && is_process_running("keqing_unlock.exe") { let _ = Command::new("bash").args(["-c", "for pid in $(pgrep -f keqing_unlock.exe); do kill -9 -$pid; done"]).spawn(); log::debug!("Killing keqing_unlock.exe as game crashed!"); }
+                if let Some(session) = get_session(&app, &install_id) { if !session.helpers.is_empty() { log::debug!("Stopping {} helper(s) as game crashed!", session.helpers.len()); stop_helpers(&session); } }
             }
+            end_session(&app, &install_id);
+            // Game crashed before showing up, still undo whatever the pre launch hooks changed
+            run_launch_hooks(&app, &install.launch_hooks, LaunchHookStage::PostExit, &hook_ctx);
             return;
         }
+        mark_running(&app, &install_id, watch.pid(), watch.start_time());
+        set_game_running(true);
+        run_launch_hooks(&app, &install.launch_hooks, LaunchHookStage::PostLaunch, &hook_ctx);
         let mut rpc_client = None;
         if install.show_discord_rpc { rpc_client = discord_rpc::init(&app, install.clone(), gm.clone()); }
         let mut keepawake = None;
         if install.disable_system_idle { keepawake = prevent_system_idle(true); }
         let started = std::time::Instant::now();
+        let started_at = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as i64;
+        let session_id = create_play_session(&app, install_id.clone(), started_at, install.version.clone(), install.runner_version.clone());
         loop {
             std::thread::sleep(std::time::Duration::from_millis(POLL_MS));
-            let running = is_process_running(&exe_name);
+            let running = watch.is_running();
             let elapsed = started.elapsed().as_secs();
             if !running || elapsed - last_db_write_elapsed >= 10 {
                 let new_total = base_playtime + elapsed;
                 update_install_total_playtime_by_id(&app, install_id.clone(), new_total.to_string());
+                update_play_session_end_by_id(&app, session_id, started_at + elapsed as i64, elapsed as i64);
                 last_db_write_elapsed = elapsed;
             }
             if !running {
                 if install.show_discord_rpc { if let Some(ref mut client) = rpc_client { discord_rpc::terminate(client); } }
                 if install.disable_system_idle { drop(keepawake); }
+                end_session(&app, &install_id);
+                set_game_running(false);
+                run_launch_hooks(&app, &install.launch_hooks, LaunchHookStage::PostExit, &hook_ctx);
                 app.emit("game_closed", install_id.clone()).unwrap();
                 return;
             }
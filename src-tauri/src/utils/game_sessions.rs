@@ -0,0 +1,231 @@
+use crate::GameState;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+
+#[cfg(target_os = "linux")]
+use crate::utils::process_tree::proc_stat;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// A game launched from the launcher, keyed by install id in `GameState`.
+#[derive(Debug, Clone, Serialize)]
+pub struct GameSession {
+    pub install_id: String,
+    pub name: String,
+    /// "starting" until the game process shows up, "running" after.
+    pub status: String,
+    /// The game itself, found below `root_pid` by the playtime tracker.
+    pub pid: Option<u32>,
+    /// Process `launch` spawned, on Linux also the process group of the whole launch.
+    pub root_pid: Option<u32>,
+    pub exe_name: String,
+    pub started_at: i64,
+    pub runner_version: String,
+    pub prefix: String,
+    pub helpers: Vec<SessionHelper>,
+    /// Linux only, wineserver binary of the runner used to shut the prefix down.
+    #[serde(skip)]
+    pub wineserver: Option<String>,
+    /// Set once a playtime tracker owns the session, `finish_launch` drops sessions nobody is tracking.
+    #[serde(skip)]
+    pub tracked: bool,
+    /// Linux only, start time of every pid above as read from `/proc/<pid>/stat`. A pid whose start time changed got reused and is never signalled.
+    #[serde(skip)]
+    pub start_times: HashMap<u32, u64>,
+}
+
+/// Processes started next to the game such as XXMI or the FPS unlocker, on Linux `pid` is also their process group.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionHelper {
+    pub name: String,
+    pub pid: u32,
+}
+
+/// Registers a launch, false when the install already has a session as only one instance per install can be tracked and stopped.
+pub fn begin_session<R: Runtime>(app: &AppHandle<R>, session: GameSession) -> bool {
+    let state = app.state::<GameState>();
+    let mut sessions = state.sessions.lock().unwrap();
+    if sessions.contains_key(&session.install_id) { return false; }
+    sessions.insert(session.install_id.clone(), session);
+    true
+}
+
+pub fn new_session(install_id: &str, name: &str, exe_name: &str, runner_version: &str, prefix: &str, wineserver: Option<String>) -> GameSession {
+    GameSession { install_id: install_id.to_string(), name: name.to_string(), status: "starting".to_string(), pid: None, root_pid: None, exe_name: exe_name.to_string(), started_at: now_secs(), runner_version: runner_version.to_string(), prefix: prefix.to_string(), helpers: Vec::new(), wineserver, tracked: false, start_times: HashMap::new() }
+}
+
+pub fn attach_helper<R: Runtime>(app: &AppHandle<R>, install_id: &str, name: &str, pid: u32) {
+    let state = app.state::<GameState>();
+    let mut sessions = state.sessions.lock().unwrap();
+    if let Some(s) = sessions.get_mut(install_id) { s.helpers.push(SessionHelper { name: name.to_string(), pid }); record_start_time(s, pid, None); }
+}
+
+pub fn mark_tracked<R: Runtime>(app: &AppHandle<R>, install_id: &str, root_pid: u32) {
+    let state = app.state::<GameState>();
+    let mut sessions = state.sessions.lock().unwrap();
+    if let Some(s) = sessions.get_mut(install_id) { s.tracked = true; s.root_pid = Some(root_pid); record_start_time(s, root_pid, None); }
+}
+
+/// `start_time` is the one the process watch bound the game with, so the session signals exactly the process it tracks.
+pub fn mark_running<R: Runtime>(app: &AppHandle<R>, install_id: &str, pid: Option<u32>, start_time: Option<u64>) {
+    let state = app.state::<GameState>();
+    let mut sessions = state.sessions.lock().unwrap();
+    if let Some(s) = sessions.get_mut(install_id) {
+        s.status = "running".to_string();
+        s.pid = pid;
+        s.started_at = now_secs();
+        if let Some(pid) = pid { record_start_time(s, pid, start_time); }
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn record_start_time(session: &mut GameSession, pid: u32, start_time: Option<u64>) {
+    if let Some(start_time) = start_time.or_else(|| proc_stat(pid).map(|s| s.start_time)) { session.start_times.insert(pid, start_time); }
+}
+
+#[cfg(target_os = "windows")]
+fn record_start_time(_session: &mut GameSession, _pid: u32, _start_time: Option<u64>) {}
+
+pub fn end_session<R: Runtime>(app: &AppHandle<R>, install_id: &str) {
+    app.state::<GameState>().sessions.lock().unwrap().remove(install_id);
+}
+
+/// Called at the end of `launch`, drops the session when the game never got spawned.
+pub fn finish_launch<R: Runtime>(app: &AppHandle<R>, install_id: &str) {
+    let state = app.state::<GameState>();
+    let mut sessions = state.sessions.lock().unwrap();
+    if sessions.get(install_id).is_some_and(|s| !s.tracked) { sessions.remove(install_id); }
+}
+
+pub fn get_session<R: Runtime>(app: &AppHandle<R>, install_id: &str) -> Option<GameSession> {
+    app.state::<GameState>().sessions.lock().unwrap().get(install_id).cloned()
+}
+
+pub fn list_sessions<R: Runtime>(app: &AppHandle<R>) -> Vec<GameSession> {
+    let mut sessions = app.state::<GameState>().sessions.lock().unwrap().values().cloned().collect::<Vec<GameSession>>();
+    sessions.sort_by_key(|s| s.started_at);
+    sessions
+}
+
+/// What `stop_game` did, one entry per signal sent in escalation order.
+#[derive(Debug, Clone, Serialize)]
+pub struct StopGameReport {
+    pub install_id: String,
+    pub steps: Vec<StopStep>,
+    /// Pids still alive after the final kill, normally empty.
+    pub remaining: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StopStep {
+    /// "game", a helper name, "launch" for the spawned wrapper or "wineserver".
+    pub target: String,
+    pub pid: Option<u32>,
+    /// "term", "wineserver_kill" or "kill".
+    pub action: String,
+}
+
+const GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+/// Stops a session in a fixed escalation order: the game gets asked to exit first, then its helpers, the launch wrapper and the prefix's wineserver,
+/// and whatever is still alive after another grace period gets killed. The playtime tracker notices the exit and cleans the session up as usual.
+pub fn stop_game<R: Runtime>(app: &AppHandle<R>, install_id: &str) -> Option<StopGameReport> {
+    let session = get_session(app, install_id)?;
+    log::info!("Stopping game session of {} ({})", session.name, session.install_id);
+    let mut steps = Vec::new();
+
+    if let Some(pid) = session.pid.filter(|p| is_alive(&session, *p)) {
+        terminate(pid, false);
+        steps.push(StopStep { target: "game".to_string(), pid: Some(pid), action: "term".to_string() });
+        if !wait_for_exit(&session, &[pid], GRACE_PERIOD) { log::debug!("{} did not exit within {:?}, stopping the rest of the session", session.name, GRACE_PERIOD); }
+    }
+
+    // Helpers have nothing left to attach to, and the wrapper and wineserver would keep the prefix alive otherwise
+    for h in session.helpers.iter().filter(|h| is_alive(&session, h.pid)) { terminate(h.pid, true); steps.push(StopStep { target: h.name.clone(), pid: Some(h.pid), action: "term".to_string() }); }
+    if let Some(root) = session.root_pid.filter(|p| is_alive(&session, *p)) { terminate(root, true); steps.push(StopStep { target: "launch".to_string(), pid: Some(root), action: "term".to_string() }); }
+    #[cfg(target_os = "linux")]
+    if let Some(wineserver) = session.wineserver.as_ref() {
+        let _ = Command::new(wineserver).arg("-k").env("WINEPREFIX", format!("{}/pfx", session.prefix)).status();
+        steps.push(StopStep { target: "wineserver".to_string(), pid: None, action: "wineserver_kill".to_string() });
+    }
+
+    if !wait_for_exit(&session, &tracked_pids(&session), GRACE_PERIOD) {
+        for pid in tracked_pids(&session).into_iter().filter(|p| is_alive(&session, *p)) {
+            kill(&session, pid);
+            let target = if Some(pid) == session.pid { "game".to_string() } else if Some(pid) == session.root_pid { "launch".to_string() } else { session.helpers.iter().find(|h| h.pid == pid).map(|h| h.name.clone()).unwrap_or_default() };
+            steps.push(StopStep { target, pid: Some(pid), action: "kill".to_string() });
+        }
+    }
+
+    std::thread::sleep(Duration::from_millis(250));
+    let remaining = tracked_pids(&session).into_iter().filter(|p| is_alive(&session, *p)).collect::<Vec<u32>>();
+    if !remaining.is_empty() { log::warn!("Processes {:?} of {} survived stop_game", remaining, session.name); }
+    log::info!("Stopped {} with {} step(s)", session.name, steps.len());
+    Some(StopGameReport { install_id: session.install_id, steps, remaining })
+}
+
+/// Ends helpers that outlived the game, replaces the old name based pkill loops.
+pub fn stop_helpers(session: &GameSession) {
+    let pids = session.helpers.iter().map(|h| h.pid).filter(|p| is_alive(session, *p)).collect::<Vec<u32>>();
+    for pid in pids.iter() { terminate(*pid, true); }
+    if !wait_for_exit(session, &pids, Duration::from_secs(2)) { for pid in pids.into_iter().filter(|p| is_alive(session, *p)) { kill(session, pid); } }
+}
+
+fn tracked_pids(session: &GameSession) -> Vec<u32> {
+    session.pid.into_iter().chain(session.root_pid).chain(session.helpers.iter().map(|h| h.pid)).collect()
+}
+
+fn wait_for_exit(session: &GameSession, pids: &[u32], timeout: Duration) -> bool {
+    let started = Instant::now();
+    while started.elapsed() < timeout {
+        if pids.iter().all(|p| !is_alive(session, *p)) { return true; }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    pids.iter().all(|p| !is_alive(session, *p))
+}
+
+/// `group` signals the whole process group on Linux, helpers and the launch wrapper are spawned as group leaders.
+#[cfg(target_os = "linux")]
+fn terminate(pid: u32, group: bool) {
+    let target = if group { format!("-{}", pid) } else { pid.to_string() };
+    let _ = Command::new("kill").args(["-TERM", target.as_str()]).status();
+}
+
+#[cfg(target_os = "windows")]
+fn terminate(pid: u32, tree: bool) {
+    let mut cmd = Command::new("taskkill");
+    if tree { cmd.arg("/T"); }
+    let _ = cmd.args(["/PID", pid.to_string().as_str()]).creation_flags(0x08000000).status();
+}
+
+#[cfg(target_os = "linux")]
+fn kill(session: &GameSession, pid: u32) {
+    // Checked again right before the signal, the pid could have been reused since the last look
+    let Some(stat) = proc_stat(pid).filter(|s| session.start_times.get(&pid).is_some_and(|t| *t == s.start_time)) else { return; };
+    let group = stat.pgid == pid;
+    let target = if group { format!("-{}", pid) } else { pid.to_string() };
+    let _ = Command::new("kill").args(["-9", target.as_str()]).status();
+}
+
+#[cfg(target_os = "windows")]
+fn kill(_session: &GameSession, pid: u32) {
+    let _ = Command::new("taskkill").args(["/F", "/T", "/PID", pid.to_string().as_str()]).creation_flags(0x08000000).status();
+}
+
+/// Only true for the process the session recorded, a pid reused by something else counts as exited.
+#[cfg(target_os = "linux")]
+fn is_alive(session: &GameSession, pid: u32) -> bool {
+    proc_stat(pid).is_some_and(|s| s.state != 'Z' && session.start_times.get(&pid).is_some_and(|t| *t == s.start_time))
+}
+
+#[cfg(target_os = "windows")]
+fn is_alive(_session: &GameSession, pid: u32) -> bool {
+    Command::new("tasklist").args(["/FI", format!("PID eq {}", pid).as_str(), "/NH"]).creation_flags(0x08000000).output().is_ok_and(|o| String::from_utf8_lossy(&o.stdout).split_whitespace().any(|w| w == pid.to_string()))
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64
+}
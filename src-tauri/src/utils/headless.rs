@@ -0,0 +1,217 @@
+use crate::downloading::bandwidth::{apply_speed_limit, BandwidthLimits};
+use crate::downloading::queue::{QueueJobKind, QueueJobOutcome};
+use crate::downloading::{DownloadGamePayload, QueueJobPayload};
+use crate::DownloadState;
+use crate::downloading::progress::{ProgressEvent, ProgressPhase};
+use crate::utils::args::{Args, CliCommand, QueueCliCommand};
+use crate::utils::{apply_launch_profile, generate_cuid};
+use crate::utils::db_manager::{get_download_queue_jobs, get_install_info_by_id, get_integrity_report_by_install_id, get_installs, get_launch_profile_by_id, get_manifest_info_by_id, get_settings};
+use crate::utils::game_launch_manager::preview_launch as resolve_launch_preview;
+use crate::utils::repo_manager::get_manifest;
+use fischl::utils::prettify_bytes;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Listener, Manager, Runtime};
+
+const CLI_PORT_FILE: &str = "cli.port";
+const PROGRESS_EVENTS: [&str; 5] = ["download_progress", "update_progress", "repair_progress", "verify_progress", "preload_progress"];
+
+/// Runs a CLI subcommand against the already initialized app (database, manifests) and returns the exit code once it is done.
+pub fn run_headless<R: Runtime>(app: &AppHandle<R>, command: CliCommand, json: bool) -> i32 {
+    match command {
+        CliCommand::List(_) => { list_installs(app, json); 0 }
+        CliCommand::Queue(opts) => match opts.command {
+            Some(QueueCliCommand::Status(_)) | None => { queue_status(app, json); 0 }
+        },
+        CliCommand::Update(opts) => run_install_job(app, "update", opts.id, json),
+        CliCommand::Repair(opts) => run_install_job(app, "repair", opts.id, json),
+        CliCommand::Verify(opts) => run_install_job(app, "verify", opts.id, json),
+        CliCommand::Preload(opts) => run_install_job(app, "preload", opts.id, json),
+        CliCommand::Launch(opts) => preview_launch(app, opts.id, opts.profile, json),
+    }
+}
+
+/// Answer to a command handed to the running instance, printed by the process that handed it over.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ForwardReply {
+    pub ok: bool,
+    pub message: String,
+}
+
+/// Accepts commands from other processes on localhost, port and a per start token go to `cli.port` in the app data dir.
+/// The open launcher queues `update`, `repair`, `verify` and `preload`, a running headless command refuses everything so two processes never work on the same install.
+pub fn start_cli_listener<R: Runtime>(app: &AppHandle<R>, data_dir: &Path) {
+    let listener = match TcpListener::bind(("127.0.0.1", 0)) {
+        Ok(l) => l,
+        Err(e) => { log::warn!("Failed to listen for CLI commands: {}", e); return; }
+    };
+    let Ok(addr) = listener.local_addr() else { return; };
+    let token = generate_cuid();
+    if let Err(e) = std::fs::write(data_dir.join(CLI_PORT_FILE), format!("{} {}", addr.port(), token)) { log::warn!("Failed to write {}: {}", CLI_PORT_FILE, e); return; }
+    let app = app.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+            let mut reader = BufReader::new(&stream);
+            let (mut sent_token, mut argv) = (String::new(), String::new());
+            if reader.read_line(&mut sent_token).is_err() || sent_token.trim() != token || reader.read_line(&mut argv).is_err() { continue; }
+            let reply = match serde_json::from_str::<Vec<String>>(argv.trim()) {
+                Ok(argv) => queue_forwarded_command(&app, &argv),
+                Err(_) => ForwardReply { ok: false, message: "Malformed command".to_string() },
+            };
+            let _ = writeln!(&stream, "{}", serde_json::to_string(&reply).unwrap_or_default());
+        }
+    });
+}
+
+/// Removes `cli.port` once a headless command is done, the listener thread ends with the process.
+pub fn stop_cli_listener(data_dir: &Path) {
+    let _ = std::fs::remove_file(data_dir.join(CLI_PORT_FILE));
+}
+
+/// Hands `argv` to the instance listening on `cli.port`. None when no instance answers, the caller then runs the command itself.
+pub fn forward_to_running_instance(data_dir: &Path, argv: &[String]) -> Option<ForwardReply> {
+    let content = std::fs::read_to_string(data_dir.join(CLI_PORT_FILE)).ok()?;
+    let (port, token) = content.trim().split_once(' ')?;
+    let addr = SocketAddr::from(([127, 0, 0, 1], port.parse::<u16>().ok()?));
+    let stream = TcpStream::connect_timeout(&addr, Duration::from_secs(2)).ok()?;
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+    writeln!(&stream, "{}\n{}", token, serde_json::to_string(argv).ok()?).ok()?;
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line).ok()?;
+    serde_json::from_str(line.trim()).ok()
+}
+
+/// Prints the answer of the running instance, failures exit non zero like a failed job would.
+pub fn print_forward_reply(reply: ForwardReply, json: bool) -> i32 {
+    if json { println!("{}", serde_json::json!({"type": "forwarded", "ok": reply.ok, "message": reply.message})); } else if reply.ok { println!("{}", reply.message); } else { eprintln!("{}", reply.message); }
+    if reply.ok { 0 } else { 1 }
+}
+
+fn queue_forwarded_command<R: Runtime>(app: &AppHandle<R>, argv: &[String]) -> ForwardReply {
+    let failed = |message: String| ForwardReply { ok: false, message };
+    let q = app.state::<DownloadState>().queue.lock().unwrap().clone();
+    // Only the open launcher runs a queue, a headless command holds the install data until it exits
+    let Some(queue) = q else { return failed("Another headless command is running, try again once it is done".to_string()); };
+    let Some(command) = Args::parse_argv(argv).and_then(|a| a.command) else { return failed("Not a command the running launcher accepts".to_string()); };
+    let (kind, action, id) = match command {
+        CliCommand::Update(opts) => (QueueJobKind::GameUpdate, "update", opts.id),
+        CliCommand::Repair(opts) => (QueueJobKind::GameRepair, "repair", opts.id),
+        CliCommand::Verify(opts) => (QueueJobKind::GameVerify, "verify", opts.id),
+        CliCommand::Preload(opts) => (QueueJobKind::GamePreload, "preload", opts.id),
+        _ => return failed("Not a command the running launcher accepts".to_string()),
+    };
+    let Some(id) = id else { return failed(format!("Missing installation ID, see `{} --help`", action)); };
+    let Some(install) = get_install_info_by_id(app, id.clone()) else { return failed(format!("Installation {} not found", id)); };
+    if queue.has_job_for_id(install.id.clone()) { return failed(format!("{} already has a queued job in the running launcher", install.name)); }
+    log::info!("Queuing {:?} of {} requested by another process", kind, install.name);
+    queue.enqueue(kind, QueueJobPayload::Game(DownloadGamePayload { install: install.id.clone(), biz: "".to_string(), lang: "".to_string(), region: install.region_code.clone(), is_latest: None }));
+    ForwardReply { ok: true, message: format!("Forwarded {} of {} to the running launcher, it is queued there", action, install.name) }
+}
+
+fn list_installs<R: Runtime>(app: &AppHandle<R>, json: bool) {
+    for i in get_installs(app).unwrap_or_default() {
+        if json { println!("{}", serde_json::json!({"type": "install", "id": i.id, "name": i.name, "version": i.version, "directory": i.directory, "runner_version": i.runner_version})); } else { println!("{}\t{}\t{}\t{}", i.id, i.name, i.version, i.directory); }
+    }
+}
+
+fn queue_status<R: Runtime>(app: &AppHandle<R>, json: bool) {
+    // The queue worker is not running in headless mode, this is what the launcher saved last and not live state
+    if !json { eprintln!("Download queue as last saved by the launcher:"); }
+    for j in get_download_queue_jobs(app).unwrap_or_default() {
+        if json { println!("{}", serde_json::json!({"type": "queue_job", "source": "saved", "position": j.position, "kind": j.kind, "install_id": j.install_id, "name": j.name, "status": j.status})); } else { println!("{}\t{}\t{}\t{}", j.position, j.status, j.kind, j.name); }
+    }
+}
+
+/// `launch <id> --dry-run`, prints what `launch` would spawn. Warnings go to stderr so the output can be piped.
+fn preview_launch<R: Runtime>(app: &AppHandle<R>, id: Option<String>, profile_id: Option<String>, json: bool) -> i32 {
+    let Some(id) = id else { eprintln!("Missing installation ID, see `launch --help`"); return 2; };
+    let Some(mut install) = get_install_info_by_id(app, id.clone()) else { eprintln!("Installation {} not found", id); return 1; };
+    if let Some(pid) = profile_id {
+        let Some(profile) = get_launch_profile_by_id(app, pid.clone()).filter(|p| p.install_id == install.id) else { eprintln!("Launch profile {} of installation {} not found", pid, id); return 1; };
+        apply_launch_profile(&mut install, &profile);
+    }
+    let Some(gm) = get_manifest_info_by_id(app, install.manifest_id.clone()).and_then(|m| get_manifest(app, m.filename)) else { eprintln!("Manifest of installation {} not found", id); return 1; };
+    let Some(gs) = get_settings(app) else { eprintln!("Failed to load launcher settings"); return 1; };
+
+    let preview = match resolve_launch_preview(app, install, gm, gs) {
+        Ok(Some(p)) => p,
+        Ok(None) => { eprintln!("Runner of installation {} is not available", id); return 1; }
+        Err(e) => { eprintln!("Failed to resolve launch of installation {}: {}", id, e); return 1; }
+    };
+    if json { println!("{}", serde_json::json!({"type": "launch_preview", "install_id": id, "data": preview})); } else {
+        println!("cwd: {}", preview.cwd);
+        println!("argv:");
+        for a in preview.argv.iter() { println!("  {}", a); }
+        println!("env:");
+        for (k, v) in preview.env.iter() { println!("  {}={}", k, v); }
+        if !preview.helpers.is_empty() { println!("helpers: {}", preview.helpers.join(", ")); }
+        for w in preview.warnings.iter() { eprintln!("warning: {}", w); }
+    }
+    0
+}
+
+fn run_install_job<R: Runtime>(app: &AppHandle<R>, action: &str, id: Option<String>, json: bool) -> i32 {
+    let Some(id) = id else { eprintln!("Missing installation ID, see `{} --help`", action); return 2; };
+    let Some(install) = get_install_info_by_id(app, id.clone()) else { eprintln!("Installation {} not found", id); return 1; };
+    let payload = DownloadGamePayload { install: install.id.clone(), biz: "".to_string(), lang: "".to_string(), region: install.region_code.clone(), is_latest: None };
+    let started = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    let job_id = format!("headless_{}_{}", action, started.as_millis());
+    let listeners = watch_progress(app, json);
+    // The queue worker normally applies speed limits, it does not run in headless mode
+    let kind = match action { "update" => QueueJobKind::GameUpdate, "repair" => QueueJobKind::GameRepair, "verify" => QueueJobKind::GameVerify, _ => QueueJobKind::GamePreload };
+    if let Some(s) = get_settings(app) { apply_speed_limit(BandwidthLimits::from_settings(&s).effective([kind], false)); }
+
+    log::info!("Running headless {} for install {}", action, install.id);
+    let outcome = match action {
+        "update" => crate::downloading::update::run_game_update(app.clone(), payload, job_id),
+        "repair" => crate::downloading::repair::run_game_repair(app.clone(), payload, job_id),
+        "verify" => crate::downloading::verify::run_game_verify(app.clone(), payload, job_id),
+        _ => crate::downloading::preload::run_game_preload(app.clone(), payload, job_id),
+    };
+    for l in listeners { app.unlisten(l); }
+
+    let (status, code) = match outcome { QueueJobOutcome::Completed => ("completed", 0), QueueJobOutcome::Failed => ("failed", 1), QueueJobOutcome::Cancelled => ("cancelled", 1) };
+    if json { println!("{}", serde_json::json!({"type": "result", "action": action, "install_id": install.id, "status": status})); } else { println!("{} {}: {}", action, install.name, status); }
+    // Only repairs and verifies leave an integrity report behind, skip the one an earlier job stored
+    if let Some(r) = get_integrity_report_by_install_id(app, install.id.clone()).filter(|r| r.job_kind == format!("game_{}", action) && r.created_at >= started.as_secs() as i64) {
+        if json { println!("{}", serde_json::json!({"type": "integrity_report", "data": r})); } else { println!("{} verified, {} failed hash checks, {} redownloaded", r.files_verified, r.files_failed.len(), r.files_redownloaded.len()); }
+    }
+    code
+}
+
+/// Mirrors progress events and dialogs to stdout/stderr. Progress lines are throttled to one per second.
+fn watch_progress<R: Runtime>(app: &AppHandle<R>, json: bool) -> Vec<tauri::EventId> {
+    let last_print = Arc::new(Mutex::new(Instant::now() - Duration::from_secs(1)));
+    let mut ids = Vec::new();
+
+    for event_name in PROGRESS_EVENTS {
+        let last_print = last_print.clone();
+        ids.push(app.listen_any(event_name, move |event| {
+            let Ok(p) = serde_json::from_str::<ProgressEvent>(event.payload()) else { return; };
+            let mut last = last_print.lock().unwrap();
+            if last.elapsed() < Duration::from_secs(1) { return; }
+            *last = Instant::now();
+
+            if json { println!("{}", serde_json::json!({"type": "progress", "event": event_name, "data": p})); return; }
+            let (progress, total) = match p.phase { ProgressPhase::Install | ProgressPhase::Patch | ProgressPhase::Extract | ProgressPhase::Validate | ProgressPhase::Move if p.install_total > 0 => (p.install_progress, p.install_total), ProgressPhase::Verify if event_name == "verify_progress" => (p.install_progress, p.install_total), _ => (p.progress, p.total) };
+            let percent = if total > 0 { progress as f64 / total as f64 * 100.0 } else { 0.0 };
+            let eta = p.eta_secs.map(|s| format!(" ETA {}m {}s", s / 60, s % 60)).unwrap_or_default();
+            println!("{}: {} {:.1}% ({} / {}) {}/s{}", p.name, phase_name(p.phase), percent, prettify_bytes(progress), prettify_bytes(total), prettify_bytes(p.speed), eta);
+        }));
+    }
+
+    ids.push(app.listen_any("show_dialog", move |event| {
+        let Ok(p) = serde_json::from_str::<serde_json::Value>(event.payload()) else { return; };
+        if json { println!("{}", serde_json::json!({"type": "dialog", "data": p})); } else { eprintln!("[{}] {} {}", p["dialog_type"].as_str().unwrap_or("info"), p["message"].as_str().unwrap_or_default(), p.get("variables").map(|v| v.to_string()).unwrap_or_default()); }
+    }));
+    ids
+}
+
+fn phase_name(phase: ProgressPhase) -> &'static str {
+    match phase { ProgressPhase::Verify => "verifying", ProgressPhase::Download => "downloading", ProgressPhase::Install => "installing", ProgressPhase::Patch => "patching", ProgressPhase::Extract => "extracting", ProgressPhase::Validate => "validating", ProgressPhase::Move => "moving", ProgressPhase::Idle => "waiting" }
+}
@@ -0,0 +1,74 @@
+use crate::utils::db_manager::{get_installs, get_manifest_info_by_id, update_install_disable_system_idle_by_id, update_install_env_list_by_id, update_install_graphics_api_by_id, update_install_launch_args_by_id, update_install_show_drpc_by_id, update_install_use_gamemode_by_id, update_install_use_mangohud_by_id};
+use crate::utils::env_vars::install_env;
+use crate::utils::models::{LaunchDefaults, LauncherInstall};
+use crate::utils::repo_manager::get_manifest;
+use serde::Serialize;
+use serde_json::{json, Value};
+use sqlx::types::Json;
+use tauri::{AppHandle, Runtime};
+
+pub const LAUNCH_DEFAULT_FIELDS: [&str; 7] = ["use_gamemode", "use_mangohud", "launch_args", "env_list", "graphics_api", "show_discord_rpc", "disable_system_idle"];
+
+/// Everything `apply_launch_defaults` would change on one install.
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchDefaultsDiff {
+    pub install_id: String,
+    pub install_name: String,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub current: Value,
+    pub new: Value,
+}
+
+/// Diff of the defaults against every install, limited to `fields` when given. Installs without changes are left out.
+pub fn diff_launch_defaults<R: Runtime>(app: &AppHandle<R>, defaults: &LaunchDefaults, fields: &[String]) -> Vec<LaunchDefaultsDiff> {
+    get_installs(app).unwrap_or_default().into_iter().filter_map(|install| {
+        let graphics_api = default_graphics_api(app, &install, defaults);
+        let changes = LAUNCH_DEFAULT_FIELDS.iter().filter(|f| fields.is_empty() || fields.iter().any(|x| x == *f)).filter_map(|f| {
+            let (current, new) = match *f {
+                "use_gamemode" => (json!(install.use_gamemode), json!(defaults.use_gamemode)),
+                "use_mangohud" => (json!(install.use_mangohud), json!(defaults.use_mangohud)),
+                "launch_args" => (json!(install.launch_args), json!(defaults.launch_args)),
+                "env_list" => (json!(install_env(&install)), json!(defaults.env_list)),
+                "graphics_api" => (json!(install.graphics_api), json!(graphics_api.clone()?)),
+                "show_discord_rpc" => (json!(install.show_discord_rpc), json!(defaults.show_discord_rpc)),
+                "disable_system_idle" => (json!(install.disable_system_idle), json!(defaults.disable_system_idle)),
+                _ => return None,
+            };
+            if current == new { None } else { Some(FieldChange { field: f.to_string(), current, new }) }
+        }).collect::<Vec<FieldChange>>();
+        if changes.is_empty() { None } else { Some(LaunchDefaultsDiff { install_id: install.id, install_name: install.name, changes }) }
+    }).collect()
+}
+
+/// Writes a diff from `diff_launch_defaults`, returns the number of installs touched.
+pub fn apply_launch_defaults<R: Runtime>(app: &AppHandle<R>, defaults: &LaunchDefaults, diffs: Vec<LaunchDefaultsDiff>) -> usize {
+    for d in diffs.iter() {
+        for c in d.changes.iter() {
+            let id = d.install_id.clone();
+            match c.field.as_str() {
+                "use_gamemode" => update_install_use_gamemode_by_id(app, id, defaults.use_gamemode),
+                "use_mangohud" => update_install_use_mangohud_by_id(app, id, defaults.use_mangohud),
+                "launch_args" => update_install_launch_args_by_id(app, id, defaults.launch_args.clone()),
+                "env_list" => update_install_env_list_by_id(app, id, Json(defaults.env_list.clone())),
+                "graphics_api" => update_install_graphics_api_by_id(app, id, c.new.as_str().unwrap_or_default().to_string()),
+                "show_discord_rpc" => update_install_show_drpc_by_id(app, id, defaults.show_discord_rpc),
+                "disable_system_idle" => update_install_disable_system_idle_by_id(app, id, defaults.disable_system_idle),
+                _ => {}
+            }
+        }
+        log::info!("Applied {} launch default(s) to \"{}\"", d.changes.len(), d.install_name);
+    }
+    diffs.len()
+}
+
+/// The default graphics API when the game offers it, None keeps whatever the install has.
+fn default_graphics_api<R: Runtime>(app: &AppHandle<R>, install: &LauncherInstall, defaults: &LaunchDefaults) -> Option<String> {
+    if defaults.graphics_api.is_empty() { return None; }
+    let gm = get_manifest(app, get_manifest_info_by_id(app, install.manifest_id.clone())?.filename)?;
+    if gm.extra.switches.graphics_api && gm.extra.graphics_api_options.options.iter().any(|o| o.value == defaults.graphics_api) { Some(defaults.graphics_api.clone()) } else { None }
+}
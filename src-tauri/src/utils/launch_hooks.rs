@@ -0,0 +1,136 @@
+use crate::utils::env_vars::install_env;
+use crate::utils::models::{LaunchHook, LaunchHookStage, LauncherInstall};
+use crate::utils::show_dialog_with_callback;
+use std::collections::HashMap;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime};
+
+#[cfg(target_os = "linux")]
+use std::os::unix::process::CommandExt;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// Everything a hook needs to run outside of `launch`, post exit hooks fire from the playtime tracker thread long after it returned.
+#[derive(Debug, Clone, Default)]
+pub struct HookContext {
+    pub install_name: String,
+    pub dir: String,
+    /// Placeholders in replacement order, longer names that share a prefix (%runner_dir% and %runner%) have to come first.
+    pub vars: Vec<(&'static str, String)>,
+    pub envs: Vec<(String, String)>,
+}
+
+impl HookContext {
+    pub fn new(install: &LauncherInstall, dir: String) -> Self {
+        let envs = install_env(install).into_iter().filter(|v| v.enabled).map(|v| (v.key, v.value)).collect();
+        HookContext { install_name: install.name.clone(), dir, vars: Vec::new(), envs }
+    }
+
+    pub fn var(mut self, key: &'static str, value: String) -> Self { self.vars.push((key, value)); self }
+
+    /// Launcher provided variables go in front so the install's own env vars can still override them.
+    pub fn env(mut self, key: &str, value: String) -> Self { self.envs.insert(0, (key.to_string(), value)); self }
+
+    pub fn substitute(&self, command: &str) -> String {
+        self.vars.iter().fold(command.to_string(), |acc, (k, v)| acc.replace(k, v))
+    }
+}
+
+/// The old single pre launch command as a hook, installs and bundles from before hooks existed get theirs converted with this.
+pub fn pre_launch_hook(command: String) -> LaunchHook {
+    LaunchHook { stage: LaunchHookStage::PreLaunch, command, enabled: true, timeout_secs: 30, required: false }
+}
+
+/// Runs every enabled hook of `stage` in the order they were configured.
+/// Pre launch hooks block until they exit or hit their timeout, returns false when a required one failed and the launch should be aborted.
+/// Post launch and post exit hooks are fire and forget, a reaper thread only logs their exit status.
+pub fn run_launch_hooks<R: Runtime>(app: &AppHandle<R>, hooks: &[LaunchHook], stage: LaunchHookStage, ctx: &HookContext) -> bool {
+    for hook in hooks.iter().filter(|h| h.enabled && h.stage == stage && !h.command.trim().is_empty()) {
+        let command = ctx.substitute(&hook.command);
+        let child = match spawn_hook(&command, ctx) {
+            Ok(c) => c,
+            Err(e) => {
+                log::error!("Executing {:?} hook \"{}\" failed catastrophically! Error: {}", stage, command, e);
+                if stage == LaunchHookStage::PreLaunch {
+                    show_dialog_with_callback(app, "error", "TwintailLauncher", "dialogs.launch_hook_critical", Some(vec!["dialogs.buttons.ok"]), None, Some(HashMap::from([("install_name", ctx.install_name.as_str()), ("command", command.as_str())])));
+                    if hook.required { return false; }
+                }
+                continue;
+            }
+        };
+        log::info!("Executing {:?} hook: \"{}\"", stage, command);
+
+        if stage != LaunchHookStage::PreLaunch {
+            let mut child = child;
+            std::thread::spawn(move || {
+                match child.wait() {
+                    Ok(status) if !status.success() => log::warn!("{:?} hook \"{}\" exited with status: {}", stage, command, status.code().unwrap_or(-1)),
+                    Ok(_) => log::debug!("{:?} hook \"{}\" finished", stage, command),
+                    Err(e) => log::warn!("Failed to wait for {:?} hook \"{}\": {}", stage, command, e),
+                }
+            });
+            continue;
+        }
+
+        let timeout = if hook.timeout_secs == 0 { None } else { Some(Duration::from_secs(hook.timeout_secs)) };
+        let dialog = match wait_with_timeout(child, timeout) {
+            Some(Ok(code)) if code == 0 => { log::debug!("Pre launch hook \"{}\" finished", command); continue; }
+            Some(Ok(code)) => { log::warn!("Pre launch hook \"{}\" failed with status: {}", command, code); ("dialogs.launch_hook_failed", code.to_string()) }
+            Some(Err(e)) => { log::error!("Failed to wait for pre launch hook \"{}\": {}", command, e); ("dialogs.launch_hook_failed", "-1".to_string()) }
+            None => { log::warn!("Pre launch hook \"{}\" timed out after {} seconds and was killed", command, hook.timeout_secs); ("dialogs.launch_hook_timeout", hook.timeout_secs.to_string()) }
+        };
+        let key = if hook.required { format!("{}_aborted", dialog.0) } else { dialog.0.to_string() };
+        show_dialog_with_callback(app, if hook.required { "error" } else { "warning" }, "TwintailLauncher", key.as_str(), Some(vec!["dialogs.buttons.ok"]), None, Some(HashMap::from([("install_name", ctx.install_name.as_str()), ("command", command.as_str()), ("code", dialog.1.as_str()), ("timeout", dialog.1.as_str())])));
+        if hook.required { return false; }
+    }
+    true
+}
+
+#[cfg(target_os = "linux")]
+fn spawn_hook(command: &str, ctx: &HookContext) -> std::io::Result<Child> {
+    let mut cmd = Command::new("bash");
+    cmd.arg("-c");
+    cmd.arg(command);
+    for (k, v) in ctx.envs.iter() { cmd.env(k, v); }
+    cmd.stdout(Stdio::inherit());
+    cmd.stderr(Stdio::inherit());
+    cmd.current_dir(ctx.dir.clone());
+    cmd.process_group(0);
+    cmd.spawn()
+}
+
+#[cfg(target_os = "windows")]
+fn spawn_hook(command: &str, ctx: &HookContext) -> std::io::Result<Child> {
+    let mut cmd = Command::new("cmd");
+    cmd.arg("/C");
+    cmd.raw_arg(command);
+    for (k, v) in ctx.envs.iter() { cmd.env(k, v); }
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    cmd.stdout(Stdio::inherit());
+    cmd.stderr(Stdio::inherit());
+    cmd.current_dir(ctx.dir.clone());
+    cmd.spawn()
+}
+
+/// Returns the exit code, or None when the hook ran past `timeout` and got killed together with everything it spawned.
+fn wait_with_timeout(mut child: Child, timeout: Option<Duration>) -> Option<std::io::Result<i32>> {
+    let started = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return Some(Ok(status.code().unwrap_or(-1))),
+            Ok(None) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        if timeout.is_some_and(|t| started.elapsed() >= t) {
+            #[cfg(target_os = "linux")]
+            { let _ = Command::new("kill").args(["-9", format!("-{}", child.id()).as_str()]).status(); }
+            #[cfg(target_os = "windows")]
+            { let _ = Command::new("taskkill").args(["/F", "/T", "/PID", child.id().to_string().as_str()]).creation_flags(0x08000000).status(); }
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,96 @@
+use crate::utils::db_manager::{create_local_installed_runner, delete_installed_runner_by_version, get_installed_runner_info_by_version, get_installed_runners, get_settings, update_installed_runner_is_installed_by_version};
+use crate::utils::models::{LocalRunner, RunnerManifest, RunnerPaths, RunnerVersion};
+use crate::utils::repo_manager::{get_compatibilities, get_compatibility, ManifestLoaders};
+use crate::utils::runner_from_runner_version;
+use sqlx::types::Json;
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Runner loader key the imported runners are listed under, there is no such file in any repository.
+pub const LOCAL_RUNNERS_MANIFEST: &str = "local_runners.json";
+
+/// Finds the wine binaries of a Proton or Wine build. Proton builds carry a `proton` script next to `files/` (or `dist/` on older builds).
+pub fn detect_runner_layout(dir: &Path) -> Option<(bool, RunnerPaths)> {
+    let is_proton = dir.join("proton").is_file();
+    let candidates: &[&str] = if is_proton { &["files/bin", "dist/bin"] } else { &["bin", "files/bin"] };
+    let bin = candidates.iter().find(|b| dir.join(b).join("wineserver").is_file())?;
+    let wine64 = if dir.join(bin).join("wine64").is_file() { format!("{bin}/wine64") } else { String::new() };
+    // New WoW64 builds only ship `wine`, the launcher falls back to wine32 when wine64 is empty
+    if wine64.is_empty() && !dir.join(bin).join("wine").is_file() { return None; }
+    Some((is_proton, RunnerPaths { wine32: format!("{bin}/wine"), wine64, wine_server: format!("{bin}/wineserver"), wine_boot: format!("{bin}/wineboot") }))
+}
+
+/// Compatibility manifest of a runner version and whether it is Proton. Imported runners get their detected paths and kind instead of the placeholder manifest.
+pub fn runner_compat<R: Runtime>(app: &AppHandle<R>, runner_version: &str) -> Option<(RunnerManifest, bool)> {
+    let filename = runner_from_runner_version(app, runner_version.to_string())?;
+    let mut rm = get_compatibility(app, &filename)?;
+    if filename == LOCAL_RUNNERS_MANIFEST {
+        let local = get_installed_runner_info_by_version(app, runner_version.to_string())?.local_runner.0?;
+        rm.paths = local.paths;
+        return Some((rm, local.is_proton));
+    }
+    let name = rm.display_name.to_ascii_lowercase();
+    let is_proton = name.contains("proton") && !name.contains("wine");
+    Some((rm, is_proton))
+}
+
+/// Lists the imported runners under their own manifest so runner lookups and the runner pickers see them. Runners whose source went away are marked as not installed.
+pub fn register_local_runners<R: Runtime>(app: &AppHandle<R>) {
+    let runners = get_installed_runners(app).unwrap_or_default().into_iter().filter_map(|r| { let local = r.local_runner.0.clone()?; Some((r, local)) }).collect::<Vec<_>>();
+    for (r, local) in runners.iter() {
+        let wine = if local.paths.wine64.is_empty() { &local.paths.wine32 } else { &local.paths.wine64 };
+        let available = Path::new(&r.runner_path).join(wine).is_file();
+        if available != r.is_installed { update_installed_runner_is_installed_by_version(app, r.version.clone(), available); }
+    }
+
+    let versions = runners.iter().map(|(r, _)| RunnerVersion { version: r.version.clone(), url: String::new(), urls: None, hash: String::new() }).collect::<Vec<RunnerVersion>>();
+    let loaders = app.state::<ManifestLoaders>();
+    let mut loader = loaders.runner.0.write().unwrap();
+    if versions.is_empty() { loader.remove(LOCAL_RUNNERS_MANIFEST); return; }
+    let paths = RunnerPaths { wine32: String::new(), wine64: String::new(), wine_server: String::new(), wine_boot: String::new() };
+    loader.insert(LOCAL_RUNNERS_MANIFEST.to_string(), RunnerManifest { version: 1, display_name: "Local runners".to_string(), aarch64_supported: true, versions, paths });
+}
+
+/// Registers a Wine or Proton build from any directory under `name`. The runner folder gets a link to it so install runner paths keep their usual layout.
+/// Errors are dialog keys.
+pub fn import_local_runner<R: Runtime>(app: &AppHandle<R>, source: &Path, name: &str) -> Result<LocalRunner, &'static str> {
+    let name = name.trim();
+    if name.is_empty() || name.contains('/') || name.starts_with('.') || name == "steamrt" { return Err("dialogs.local_runner_invalid_name"); }
+    let Ok(source) = fs::canonicalize(source) else { return Err("dialogs.local_runner_not_detected"); };
+    let Some((is_proton, paths)) = detect_runner_layout(&source) else { return Err("dialogs.local_runner_not_detected"); };
+    if get_compatibilities(app).values().any(|m| m.versions.iter().any(|v| v.version == name)) { return Err("dialogs.local_runner_exists"); }
+
+    let Some(gs) = get_settings(app) else { return Err("dialogs.local_runner_link_failed"); };
+    let link = Path::new(&gs.default_runner_path).join(name);
+    let points_to_source = fs::canonicalize(&link).is_ok_and(|p| p == source);
+    // A folder dropped into the runner directory by hand was picked up as a regular runner already, it only needs its layout
+    match get_installed_runner_info_by_version(app, name.to_string()) {
+        Some(existing) if existing.local_runner.0.is_none() && points_to_source => delete_installed_runner_by_version(app, name.to_string()),
+        Some(_) => return Err("dialogs.local_runner_exists"),
+        None => {}
+    }
+    if !points_to_source {
+        if link.symlink_metadata().is_ok() { return Err("dialogs.local_runner_exists"); }
+        if let Err(e) = std::os::unix::fs::symlink(&source, &link) { log::warn!("Failed to link local runner {} to {}: {}", source.display(), link.display(), e); return Err("dialogs.local_runner_link_failed"); }
+    }
+
+    let local = LocalRunner { source: source.to_str().unwrap_or_default().to_string(), is_proton, paths };
+    if !create_local_installed_runner(app, name.to_string(), link.to_str().unwrap().to_string(), Json(local.clone())).unwrap_or(false) { return Err("dialogs.local_runner_link_failed"); }
+    register_local_runners(app);
+    log::info!("Imported local {} runner {} from {}", if is_proton { "Proton" } else { "Wine" }, name, local.source);
+    Ok(local)
+}
+
+/// Drops an imported runner. Only the link in the runner folder goes, the imported directory itself is never touched.
+pub fn forget_local_runner<R: Runtime>(app: &AppHandle<R>, runner_version: &str) {
+    if let Some(r) = get_installed_runner_info_by_version(app, runner_version.to_string()) {
+        let link = Path::new(&r.runner_path);
+        if link.symlink_metadata().is_ok_and(|m| m.file_type().is_symlink()) {
+            if let Err(e) = fs::remove_file(link) { log::warn!("Failed to remove link of local runner {}: {}", runner_version, e); }
+        }
+    }
+    delete_installed_runner_by_version(app, runner_version.to_string());
+    register_local_runners(app);
+    log::info!("Removed local runner {}", runner_version);
+}
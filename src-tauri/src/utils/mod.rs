@@ -5,7 +5,7 @@ use crate::utils::db_manager::{
     update_settings_default_dxvk_location, update_settings_default_jadeite_location,
     update_settings_default_prefix_location, update_settings_default_runner_location,
 };
-use crate::utils::models::{DialogResponse,XXMISettings};
+use crate::utils::models::{DialogResponse,LaunchProfile,LauncherInstall,XXMISettings};
 use crate::utils::repo_manager::get_manifest;
 use sqlx::types::Json;
 use std::collections::HashMap;
@@ -25,12 +25,26 @@ use crate::downloading::{QueueJobPayload, RunnerDownloadPayload, queue::QueueJob
 
 pub mod args;
 pub mod db_manager;
+pub mod env_vars;
 pub mod game_launch_manager;
+pub mod game_sessions;
 mod git_helpers;
+pub mod headless;
+pub mod launch_defaults;
+pub mod launch_hooks;
+#[cfg(target_os = "linux")]
+pub mod local_runners;
 #[cfg(target_os = "linux")]
 pub mod gpu;
 pub mod models;
+#[cfg(target_os = "linux")]
+pub mod prefix_snapshots;
+pub mod process_tree;
 pub mod repo_manager;
+#[cfg(target_os = "linux")]
+pub mod runner_upgrades;
+pub mod runner_usage;
+pub mod session_logs;
 pub mod shortcuts;
 pub mod system_tray;
 pub mod discord_rpc;
@@ -39,6 +53,19 @@ pub fn generate_cuid() -> String {
     cuid2::create_id()
 }
 
+/// Overrides the launch settings of `install` with the ones of a launch profile, nothing is written to the database.
+pub fn apply_launch_profile(install: &mut LauncherInstall, profile: &LaunchProfile) {
+    install.launch_args = profile.launch_args.clone();
+    install.env_vars = profile.env_vars.clone();
+    install.env_list = profile.env_list.clone();
+    install.use_mangohud = profile.use_mangohud;
+    install.use_gamemode = profile.use_gamemode;
+    install.use_xxmi = profile.use_xxmi;
+    install.use_fps_unlock = profile.use_fps_unlock;
+    install.fps_value = profile.fps_value.clone();
+    install.graphics_api = profile.graphics_api.clone();
+}
+
 pub fn run_async_command<F: Future>(cmd: F) -> F::Output {
     if tokio::runtime::Handle::try_current().is_ok() { tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(cmd)) } else { tauri::async_runtime::block_on(cmd) }
 }
@@ -184,6 +211,49 @@ pub fn runner_from_runner_version<R: Runtime>(app: &AppHandle<R>, runner_version
     Some(rslt.to_string())
 }
 
+/// Archive url and hash of a runner version for this architecture, None for runners no compatibility manifest lists.
+#[cfg(target_os = "linux")]
+pub fn runner_download_source<R: Runtime>(app: &AppHandle<R>, runner_version: &str) -> Option<(String, String)> {
+    let compat = get_compatibility(app, &runner_from_runner_version(app, runner_version.to_string())?)?;
+    let rv = compat.versions.into_iter().find(|v| v.version == runner_version)?;
+    #[allow(unused_mut)]
+    let (mut url, mut hash) = (rv.url.clone(), rv.hash.clone());
+    if let Some(urls) = rv.urls {
+        #[cfg(target_arch = "x86_64")]
+        { url = urls.x86_64; hash = urls.x86_64_hash; }
+        #[cfg(target_arch = "aarch64")]
+        { if !urls.aarch64.is_empty() { url = urls.aarch64; hash = urls.aarch64_hash; } }
+    }
+    Some((url, hash))
+}
+
+/// `wine64` and `wineserver` of a runner as listed in its compatibility manifest, empty for unknown runners.
+#[cfg(target_os = "linux")]
+pub fn runner_binaries<R: Runtime>(app: &AppHandle<R>, runner_version: &str, runner_path: &Path) -> Vec<PathBuf> {
+    let Some((compat, _)) = local_runners::runner_compat(app, runner_version) else { return Vec::new(); };
+    let wine = if compat.paths.wine64.is_empty() { compat.paths.wine32 } else { compat.paths.wine64 };
+    [wine, compat.paths.wine_server].into_iter().filter(|p| !p.is_empty()).map(|p| runner_path.join(p)).collect()
+}
+
+/// Queues a runner verify job, it re-fetches the archive when the runner turns out broken. Returns false when a job for the runner is already queued.
+#[cfg(target_os = "linux")]
+pub fn queue_runner_verify<R: Runtime>(app: &AppHandle<R>, runner_version: String, runner_path: String) -> bool {
+    let (runner_url, runner_hash) = runner_download_source(app, &runner_version).unwrap_or_default();
+    let state = app.state::<DownloadState>();
+    let q = state.queue.lock().unwrap().clone();
+    let Some(queue) = q else { return false; };
+    if queue.has_job_for_id(runner_version.clone()) { return false; }
+    log::info!("Queuing verify for runner {}", runner_version);
+    queue.enqueue(QueueJobKind::RunnerVerify, QueueJobPayload::Runner(RunnerDownloadPayload { runner_version, runner_url, runner_path, runner_hash }));
+    true
+}
+
+/// A binary counts as broken when it is missing or empty, which is what an interrupted extraction leaves behind.
+#[cfg(target_os = "linux")]
+pub fn missing_runner_binaries<R: Runtime>(app: &AppHandle<R>, runner_version: &str, runner_path: &Path) -> Vec<PathBuf> {
+    runner_binaries(app, runner_version, runner_path).into_iter().filter(|b| fs::metadata(b).map(|m| m.len() == 0).unwrap_or(true)).collect()
+}
+
 pub fn get_mi_path_from_game(exe_name: String) -> Option<String> {
     if exe_name.is_empty() {
         None
@@ -328,6 +398,8 @@ pub fn sync_installed_runners<R: Runtime>(app: &AppHandle<R>) {
                                 if subdir.next().is_some() {
                                     let installed_runner = get_installed_runner_info_by_version(app, dir_name.to_string());
                                     if installed_runner.is_none() && dir_name != "steamrt" { create_installed_runner(app, dir_name.to_string(), true, path.to_str().unwrap().parse().unwrap()).unwrap(); } else if dir_name != "steamrt" { update_installed_runner_is_installed_by_version(app, dir_name.to_string(), true); }
+                                    // Interrupted extractions leave a non empty folder without binaries, let a verify job fetch the archive again
+                                    if dir_name != "steamrt" && !missing_runner_binaries(app, dir_name, &path).is_empty() { queue_runner_verify(app, dir_name.to_string(), path.to_str().unwrap().to_string()); }
                                 }
                             }
                             Err(_) => {}
@@ -373,6 +445,8 @@ pub fn sync_installed_runners<R: Runtime>(app: &AppHandle<R>) {
                     #[cfg(target_arch = "aarch64")]
                     { dl_hash = if urls.aarch64.is_empty() { runner_ver.hash.clone() } else { urls.aarch64_hash.clone() }; }
                 }
+                // Imported local runners have nothing to download, their source directory is gone
+                if dl_url.is_empty() { continue; }
 
                 let runner_path = runners.join(rv);
                 if !runner_path.exists() { fs::create_dir_all(&runner_path).unwrap(); }
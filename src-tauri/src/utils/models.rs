@@ -18,6 +18,25 @@ pub struct XXMISettings {
     pub dump_shaders: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchHookStage {
+    PreLaunch,
+    PostLaunch,
+    PostExit,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LaunchHook {
+    pub stage: LaunchHookStage,
+    pub command: String,
+    pub enabled: bool,
+    /// Pre launch only, seconds to wait for the hook before it gets killed. 0 waits forever.
+    pub timeout_secs: u64,
+    /// Pre launch only, a non zero exit code or timeout aborts the launch instead of just warning.
+    pub required: bool,
+}
+
 // === DATABASE ===
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -36,6 +55,30 @@ pub struct GlobalSettings {
     pub default_mangohud_config_path: String,
     pub hide_app_to_tray: bool,
     pub app_lang: String,
+    pub queue_auto_resume: bool,
+    pub max_concurrent_game_jobs: i64,
+    pub max_concurrent_misc_jobs: i64,
+    pub download_schedule_enabled: bool,
+    pub download_schedule_start: String,
+    pub download_schedule_end: String,
+    pub download_schedule_days: String,
+    pub download_speed_limits: String,
+    pub download_speed_limit_while_playing: i64,
+    pub launch_defaults: Json<LaunchDefaults>,
+}
+
+/// Launch settings new installs start with, `apply_launch_defaults` can push them to existing installs too.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+pub struct LaunchDefaults {
+    pub use_gamemode: bool,
+    pub use_mangohud: bool,
+    pub launch_args: String,
+    pub env_list: Vec<EnvVar>,
+    /// Only used for games offering this value, the rest keep the default of their manifest.
+    pub graphics_api: String,
+    pub show_discord_rpc: bool,
+    pub disable_system_idle: bool,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -81,6 +124,7 @@ pub struct LauncherInstall {
     pub use_xxmi: bool,
     pub use_fps_unlock: bool,
     pub env_vars: String,
+    /// Always empty since it became a pre launch hook, only bundles exported by older versions still carry one.
     pub pre_launch_command: String,
     pub launch_command: String,
     pub fps_value: String,
@@ -100,6 +144,59 @@ pub struct LauncherInstall {
     pub disable_system_idle: bool,
     pub steam_imported: bool,
     pub graphics_api: String,
+    #[serde(default)]
+    pub launch_hooks: Json<Vec<LaunchHook>>,
+    /// Sets PROTON_LOG=1, Proton writes its log next to the session logs of the install.
+    #[serde(default)]
+    pub proton_log: bool,
+    /// WINEDEBUG channels, for example "+loaddll,-fixme". Empty keeps the runner default.
+    #[serde(default)]
+    pub wine_debug: String,
+    /// Replaces `env_vars` once saved from the editor, `env_vars` is only read while this is empty.
+    #[serde(default)]
+    pub env_list: Json<Vec<EnvVar>>,
+    /// Follows the newest version of the runner family, the install is switched once the download finished.
+    #[serde(default)]
+    pub runner_auto_upgrade: bool,
+    /// Runner the last automatic upgrade replaced, kept installed for rollback. Empty when there is nothing to roll back to.
+    #[serde(default)]
+    pub previous_runner_version: String,
+}
+
+/// Launcher state of a single install written by `export_install_bundle`, everything machine specific gets rebuilt on import.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InstallBundle {
+    pub bundle_version: u32,
+    pub exported_at: i64,
+    /// Manifest filename without extension, manifest ids differ between machines.
+    pub manifest: String,
+    pub install: LauncherInstall,
+    /// Only set when exported with the game directory, lets the other machine adopt an already copied game folder.
+    pub game_directory: Option<String>,
+    #[serde(default)]
+    pub profiles: Vec<LaunchProfile>,
+}
+
+/// Layout of a runner imported from a local directory, replaces the compatibility manifest paths for it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LocalRunner {
+    /// Directory the runner was imported from, the runner folder only links to it.
+    pub source: String,
+    pub is_proton: bool,
+    pub paths: RunnerPaths,
+}
+
+/// Metadata stored next to every prefix archive.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrefixSnapshot {
+    pub id: String,
+    pub install_id: String,
+    /// Runner the install used when the snapshot was taken, restoring can switch back to it.
+    pub runner_version: String,
+    pub created_at: i64,
+    /// "manual" or "runner_switch".
+    pub reason: String,
+    pub size: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -110,6 +207,87 @@ pub struct LauncherRunner {
     pub version: String,
     pub value: String,
     pub name: String,
+    /// Unix time of the last launch with this runner, 0 when it was never used since tracking started.
+    pub last_used: i64,
+    #[serde(default)]
+    pub local_runner: Json<Option<LocalRunner>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LauncherQueueJob {
+    pub id: String,
+    pub kind: String,
+    pub install_id: String,
+    pub name: String,
+    pub status: String,
+    pub payload: Option<String>,
+    pub position: i64,
+}
+
+/// Outcome of the last download, repair or verify job of an install.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LauncherIntegrityReport {
+    pub install_id: String,
+    pub job_kind: String,
+    pub status: String,
+    /// Files that passed a hash check during the job.
+    pub files_verified: i64,
+    /// Every file the job wrote.
+    pub files_redownloaded: Json<Vec<String>>,
+    /// Files that existed before the job and were rewritten by it.
+    pub files_changed: Json<Vec<String>>,
+    /// Files that did not pass a hash check, missing files included.
+    pub files_failed: Json<Vec<String>>,
+    pub bytes_transferred: i64,
+    pub created_at: i64,
+}
+
+/// One game session from process start to exit, `legacy` marks the single session imported from the old `total_playtime` counter.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LauncherPlaySession {
+    pub id: i64,
+    pub install_id: String,
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub duration: i64,
+    pub game_version: String,
+    pub runner_version: String,
+    pub legacy: bool,
+}
+
+/// One entry of the env var editor, disabled entries are kept but not applied.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct EnvVar {
+    pub key: String,
+    pub value: String,
+    pub enabled: bool,
+}
+
+/// Named set of launch settings of an install, applied on top of the install when `game_launch` gets its id.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LaunchProfile {
+    pub id: String,
+    pub install_id: String,
+    pub name: String,
+    pub launch_args: String,
+    pub env_vars: String,
+    pub use_mangohud: bool,
+    pub use_gamemode: bool,
+    pub use_xxmi: bool,
+    pub use_fps_unlock: bool,
+    pub fps_value: String,
+    pub graphics_api: String,
+    pub created_at: i64,
+    #[serde(default)]
+    pub env_list: Json<Vec<EnvVar>>,
+}
+
+/// Summed playtime of one period, `period` is a date, a week or a game version depending on the query.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PlaytimeTotal {
+    pub period: String,
+    pub seconds: i64,
+    pub sessions: i64,
 }
 
 // === STRUCTS FOR MANIFESTS ===
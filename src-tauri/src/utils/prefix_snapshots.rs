@@ -0,0 +1,155 @@
+use crate::downloading::disk_space::dir_size;
+use crate::utils::game_sessions::list_sessions;
+use crate::utils::generate_cuid;
+use crate::utils::models::{LauncherInstall, PrefixSnapshot};
+use fischl::utils::prettify_bytes;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Automatic snapshots kept per install, manual ones only go when the user deletes them.
+const MAX_AUTO_SNAPSHOTS: usize = 3;
+
+/// Installs whose prefix is being archived or restored right now, launching them has to wait until it is done.
+static BUSY_PREFIXES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct BusyPrefix(String);
+
+impl BusyPrefix {
+    fn acquire(install_id: &str) -> Option<Self> {
+        let mut busy = BUSY_PREFIXES.lock().unwrap();
+        if busy.iter().any(|i| i == install_id) { return None; }
+        busy.push(install_id.to_string());
+        Some(BusyPrefix(install_id.to_string()))
+    }
+}
+
+impl Drop for BusyPrefix {
+    fn drop(&mut self) { BUSY_PREFIXES.lock().unwrap().retain(|i| i != &self.0); }
+}
+
+pub fn is_prefix_busy(install_id: &str) -> bool {
+    BUSY_PREFIXES.lock().unwrap().iter().any(|i| i == install_id)
+}
+
+fn snapshots_dir<R: Runtime>(app: &AppHandle<R>, install_id: &str) -> PathBuf {
+    app.path().app_data_dir().unwrap().join("prefix_snapshots").join(install_id)
+}
+
+fn archive_path<R: Runtime>(app: &AppHandle<R>, snapshot: &PrefixSnapshot) -> PathBuf {
+    snapshots_dir(app, &snapshot.install_id).join(format!("{}.tar.zst", snapshot.id))
+}
+
+/// Snapshots of an install, newest first. Metadata without its archive is skipped.
+pub fn list_prefix_snapshots<R: Runtime>(app: &AppHandle<R>, install_id: &str) -> Vec<PrefixSnapshot> {
+    let Ok(entries) = fs::read_dir(snapshots_dir(app, install_id)) else { return Vec::new(); };
+    let mut snapshots = entries.flatten().map(|e| e.path()).filter(|p| p.extension().is_some_and(|x| x == "json")).filter_map(|p| serde_json::from_str::<PrefixSnapshot>(&fs::read_to_string(p).ok()?).ok()).filter(|s| archive_path(app, s).exists()).collect::<Vec<PrefixSnapshot>>();
+    snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    snapshots
+}
+
+/// Archives the prefix of the install. None when there is no prefix yet, the game is running or the prefix is busy.
+pub fn create_prefix_snapshot<R: Runtime>(app: &AppHandle<R>, install: &LauncherInstall, reason: &str) -> Option<PrefixSnapshot> {
+    if !can_snapshot(app, install) { return None; }
+    let _busy = BusyPrefix::acquire(&install.id)?;
+    write_snapshot(app, install, reason)
+}
+
+/// Same as `create_prefix_snapshot` without blocking the caller. The prefix counts as busy from here on, so a launch can not let the runner touch it before the snapshot is done.
+pub fn snapshot_prefix_in_background<R: Runtime>(app: &AppHandle<R>, install: LauncherInstall, reason: &'static str) {
+    if !can_snapshot(app, &install) { return; }
+    let Some(busy) = BusyPrefix::acquire(&install.id) else { return; };
+    let app = app.clone();
+    std::thread::spawn(move || { write_snapshot(&app, &install, reason); drop(busy); });
+}
+
+fn can_snapshot<R: Runtime>(app: &AppHandle<R>, install: &LauncherInstall) -> bool {
+    if fs::read_dir(&install.runner_prefix).map(|mut d| d.next().is_none()).unwrap_or(true) { return false; }
+    if list_sessions(app).iter().any(|s| s.install_id == install.id) { log::warn!("Not taking a prefix snapshot of {} while it is running", install.name); return false; }
+    true
+}
+
+fn write_snapshot<R: Runtime>(app: &AppHandle<R>, install: &LauncherInstall, reason: &str) -> Option<PrefixSnapshot> {
+    let prefix = Path::new(&install.runner_prefix);
+    let dir = snapshots_dir(app, &install.id);
+    if let Err(e) = fs::create_dir_all(&dir) { log::warn!("Failed to create prefix snapshot folder {}: {}", dir.display(), e); return None; }
+    // The uncompressed prefix size is the worst case for the archive
+    let required = dir_size(prefix);
+    if let Some(available) = fischl::utils::available(dir.to_str().unwrap_or_default().to_string()).filter(|a| *a < required) {
+        log::warn!("Not enough space to snapshot prefix of {}: required {}, available {}", install.name, prettify_bytes(required), prettify_bytes(available));
+        return None;
+    }
+    let created_at = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs() as i64).unwrap_or(0);
+    let mut snapshot = PrefixSnapshot { id: generate_cuid(), install_id: install.id.clone(), runner_version: install.runner_version.clone(), created_at, reason: reason.to_string(), size: 0 };
+    let archive = archive_path(app, &snapshot);
+    let partial = archive.with_extension("partial");
+    log::info!("Taking {} prefix snapshot of {} from {}", reason, install.name, prefix.display());
+    if let Err(e) = write_archive(prefix, &partial) { log::warn!("Failed to snapshot prefix of {}: {}", install.name, e); let _ = fs::remove_file(&partial); return None; }
+    if let Err(e) = fs::rename(&partial, &archive) { log::warn!("Failed to store prefix snapshot of {}: {}", install.name, e); let _ = fs::remove_file(&partial); return None; }
+
+    snapshot.size = fs::metadata(&archive).map(|m| m.len()).unwrap_or(0);
+    if let Err(e) = fs::write(dir.join(format!("{}.json", snapshot.id)), serde_json::to_string_pretty(&snapshot).unwrap_or_default()) { log::warn!("Failed to store prefix snapshot metadata of {}: {}", install.name, e); let _ = fs::remove_file(&archive); return None; }
+    if reason != "manual" { prune_auto_snapshots(app, &install.id); }
+    log::info!("Prefix snapshot {} of {} done ({} bytes)", snapshot.id, install.name, snapshot.size);
+    Some(snapshot)
+}
+
+/// Replaces the prefix with the snapshot. The current prefix is moved aside first and put back when extracting fails.
+pub fn restore_prefix_snapshot<R: Runtime>(app: &AppHandle<R>, install: &LauncherInstall, snapshot: &PrefixSnapshot) -> bool {
+    if list_sessions(app).iter().any(|s| s.install_id == install.id) { return false; }
+    let Some(_busy) = BusyPrefix::acquire(&install.id) else { return false; };
+
+    let prefix = PathBuf::from(&install.runner_prefix);
+    let aside = PathBuf::from(format!("{}.restore", install.runner_prefix.trim_end_matches('/')));
+    if aside.exists() { let _ = fs::remove_dir_all(&aside); }
+    if prefix.exists() {
+        if let Err(e) = fs::rename(&prefix, &aside) { log::warn!("Failed to move prefix of {} aside: {}", install.name, e); return false; }
+    }
+
+    let result = fs::File::open(archive_path(app, snapshot)).and_then(zstd::Decoder::new).and_then(|d| {
+        let mut archive = tar::Archive::new(d);
+        archive.set_preserve_permissions(true);
+        archive.unpack(&prefix)
+    });
+    match result {
+        Ok(_) => {
+            let _ = fs::remove_dir_all(&aside);
+            log::info!("Restored prefix of {} from snapshot {}", install.name, snapshot.id);
+            true
+        }
+        Err(e) => {
+            log::warn!("Failed to restore prefix of {} from snapshot {}: {}", install.name, snapshot.id, e);
+            let _ = fs::remove_dir_all(&prefix);
+            if aside.exists() { let _ = fs::rename(&aside, &prefix); }
+            false
+        }
+    }
+}
+
+pub fn delete_prefix_snapshot<R: Runtime>(app: &AppHandle<R>, snapshot: &PrefixSnapshot) {
+    let _ = fs::remove_file(archive_path(app, snapshot));
+    let _ = fs::remove_file(snapshots_dir(app, &snapshot.install_id).join(format!("{}.json", snapshot.id)));
+}
+
+pub fn delete_prefix_snapshots<R: Runtime>(app: &AppHandle<R>, install_id: &str) {
+    let dir = snapshots_dir(app, install_id);
+    if dir.exists() { let _ = fs::remove_dir_all(dir); }
+}
+
+fn prune_auto_snapshots<R: Runtime>(app: &AppHandle<R>, install_id: &str) {
+    for s in list_prefix_snapshots(app, install_id).into_iter().filter(|s| s.reason != "manual").skip(MAX_AUTO_SNAPSHOTS) {
+        log::debug!("Removing old prefix snapshot {} of {}", s.id, install_id);
+        delete_prefix_snapshot(app, &s);
+    }
+}
+
+fn write_archive(prefix: &Path, out: &Path) -> std::io::Result<()> {
+    let encoder = zstd::Encoder::new(fs::File::create(out)?, 3)?;
+    let mut builder = tar::Builder::new(encoder);
+    // dosdevices and the user folders are links, they have to stay links or the archive would pull in the whole home folder
+    builder.follow_symlinks(false);
+    builder.append_dir_all(".", prefix)?;
+    builder.into_inner()?.finish()?.sync_all()
+}
@@ -0,0 +1,123 @@
+use std::process::Child;
+#[cfg(target_os = "windows")]
+use fischl::utils::is_process_running;
+
+/// Follows the process a launch spawned until the game shows up somewhere below it.
+/// Matching inside our own process tree keeps two installs of the same game (region variants) apart, unlike a global name lookup.
+pub struct GameProcessWatch {
+    root: Child,
+    exe_name: String,
+    pid: Option<u32>,
+    #[cfg(target_os = "linux")]
+    start_time: u64,
+    /// Windows only, set once the spawned wrapper exited before the game could be bound to it (custom launch commands).
+    #[cfg(target_os = "windows")]
+    by_name: bool,
+}
+
+impl GameProcessWatch {
+    pub fn new(root: Child, exe_name: String) -> Self {
+        #[cfg(target_os = "linux")]
+        { GameProcessWatch { root, exe_name, pid: None, start_time: 0 } }
+        #[cfg(target_os = "windows")]
+        { GameProcessWatch { root, exe_name, pid: None, by_name: false } }
+    }
+
+    pub fn pid(&self) -> Option<u32> { self.pid }
+
+    pub fn root_pid(&self) -> u32 { self.root.id() }
+
+    /// Start time of the bound game process from `/proc/<pid>/stat`, sessions use it to tell a reused pid apart.
+    #[cfg(target_os = "linux")]
+    pub fn start_time(&self) -> Option<u64> { self.pid.map(|_| self.start_time) }
+
+    #[cfg(target_os = "windows")]
+    pub fn start_time(&self) -> Option<u64> { None }
+
+    /// Looks for the game once, returns true when it is found and the watch is bound to it.
+    /// `helpers` are the pids `attach_helper` recorded for the session, their trees are searched too as fps_unlock and xxmi start the game themselves.
+    #[cfg(target_os = "linux")]
+    pub fn find(&mut self, helpers: &[u32]) -> bool {
+        // Reap the wrapper if it already exited, its children get reparented but keep the process group
+        let _ = self.root.try_wait();
+        let Some((parent, pid)) = std::iter::once(self.root.id()).chain(helpers.iter().copied()).find_map(|parent| descendants(parent).into_iter().find(|pid| matches_exe(*pid, &self.exe_name)).map(|pid| (parent, pid))) else { return false; };
+        let Some(stat) = proc_stat(pid) else { return false; };
+        log::debug!("Found game process {} ({}) under spawned process {}", pid, self.exe_name, parent);
+        self.pid = Some(pid);
+        self.start_time = stat.start_time;
+        true
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn find(&mut self, _helpers: &[u32]) -> bool {
+        match self.root.try_wait() {
+            Ok(None) => { self.pid = Some(self.root.id()); true }
+            _ => { self.by_name = true; is_process_running(&self.exe_name) }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn is_running(&mut self) -> bool {
+        let _ = self.root.try_wait();
+        let Some(pid) = self.pid else { return false; };
+        // Same pid with a different start time means it got reused by something else
+        proc_stat(pid).is_some_and(|s| s.state != 'Z' && s.start_time == self.start_time)
+    }
+
+    #[cfg(target_os = "windows")]
+    pub fn is_running(&mut self) -> bool {
+        if self.by_name { return is_process_running(&self.exe_name); }
+        matches!(self.root.try_wait(), Ok(None))
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub struct ProcStat {
+    pub ppid: u32,
+    pub pgid: u32,
+    pub state: char,
+    pub start_time: u64,
+    pub comm: String,
+}
+
+#[cfg(target_os = "linux")]
+pub fn proc_stat(pid: u32) -> Option<ProcStat> {
+    let content = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // comm is wrapped in parentheses and may contain spaces or parentheses itself
+    let open = content.find('(')?;
+    let close = content.rfind(')')?;
+    let comm = content[open + 1..close].to_string();
+    let fields = content[close + 1..].split_whitespace().collect::<Vec<&str>>();
+    Some(ProcStat { state: fields.first()?.chars().next()?, ppid: fields.get(1)?.parse().ok()?, pgid: fields.get(2)?.parse().ok()?, start_time: fields.get(19)?.parse().ok()?, comm })
+}
+
+/// Every process below `root`, plus anything still in its process group after being reparented (double forking helpers).
+#[cfg(target_os = "linux")]
+pub fn descendants(root: u32) -> Vec<u32> {
+    let Ok(entries) = std::fs::read_dir("/proc") else { return Vec::new(); };
+    let stats = entries.flatten().filter_map(|e| e.file_name().to_str().and_then(|n| n.parse::<u32>().ok())).filter_map(|pid| proc_stat(pid).map(|s| (pid, s))).collect::<Vec<(u32, ProcStat)>>();
+    let mut found = vec![root];
+    let mut idx = 0;
+    while idx < found.len() {
+        let parent = found[idx];
+        for (pid, stat) in stats.iter() {
+            if stat.ppid == parent && !found.contains(pid) { found.push(*pid); }
+        }
+        idx += 1;
+    }
+    for (pid, stat) in stats.iter() {
+        if stat.pgid == root && !found.contains(pid) { found.push(*pid); }
+    }
+    found.retain(|pid| *pid != root);
+    found
+}
+
+/// Wine sets argv[0] to the windows path of the exe while comm holds its first 15 characters.
+#[cfg(target_os = "linux")]
+fn matches_exe(pid: u32, exe_name: &str) -> bool {
+    let Some(stat) = proc_stat(pid) else { return false; };
+    if stat.state == 'Z' { return false; }
+    if stat.comm == exe_name.chars().take(15).collect::<String>() { return true; }
+    let argv0 = std::fs::read(format!("/proc/{}/cmdline", pid)).ok().and_then(|c| c.split(|b| *b == 0).next().map(|a| String::from_utf8_lossy(a).to_string())).unwrap_or_default();
+    argv0.rsplit(['/', '\\']).next().is_some_and(|n| n.eq_ignore_ascii_case(exe_name))
+}
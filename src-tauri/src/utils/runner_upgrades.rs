@@ -0,0 +1,92 @@
+use crate::DownloadState;
+use crate::downloading::queue::QueueJobKind;
+use crate::downloading::{QueueJobPayload, RunnerDownloadPayload};
+use crate::utils::db_manager::{create_installed_runner, get_installed_runner_info_by_version, get_installs, get_manifest_info_by_id, get_settings, update_install_previous_runner_version_by_id, update_install_runner_auto_upgrade_by_id, update_install_runner_location_by_id, update_install_runner_version_by_id};
+use crate::utils::game_sessions::list_sessions;
+use crate::utils::local_runners::LOCAL_RUNNERS_MANIFEST;
+use crate::utils::models::LauncherInstall;
+use crate::utils::prefix_snapshots::snapshot_prefix_in_background;
+use crate::utils::repo_manager::{get_compatibility, get_manifest};
+use crate::utils::{runner_download_source, runner_from_runner_version, show_dialog_with_callback};
+use std::collections::HashMap;
+use std::path::Path;
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Newest downloadable version of the runner family the install is on. None when the install has it already, uses an imported runner or the game pins its runner.
+pub fn runner_upgrade_target<R: Runtime>(app: &AppHandle<R>, install: &LauncherInstall) -> Option<String> {
+    let family = runner_from_runner_version(app, install.runner_version.clone())?;
+    if family == LOCAL_RUNNERS_MANIFEST { return None; }
+    if let Some(gm) = get_manifest_info_by_id(app, install.manifest_id.clone()).and_then(|m| get_manifest(app, m.filename)) {
+        if gm.extra.compat_overrides.override_runner.linux.enabled { return None; }
+    }
+    let listed = get_compatibility(app, &family)?.versions.into_iter().map(|v| v.version).collect::<Vec<String>>();
+    // Ties keep the version listed first, manifests put the newest build on top
+    let latest = listed.iter().rev().filter(|v| runner_download_source(app, v).is_some_and(|(url, _)| !url.is_empty())).max_by(|a, b| compare_runner_versions(&listed, a, b))?.clone();
+    if compare_runner_versions(&listed, &latest, &install.runner_version).is_gt() { Some(latest) } else { None }
+}
+
+/// Compares every number of the two names, so `10.0-20-proton-cachyos` and `GE-Proton10-20` order by all of their segments.
+/// Names without numbers fall back to the manifest order, earlier means newer and unlisted versions count as oldest.
+fn compare_runner_versions(listed: &[String], a: &str, b: &str) -> std::cmp::Ordering {
+    let (ka, kb) = (runner_version_key(a), runner_version_key(b));
+    if !ka.is_empty() && !kb.is_empty() { return ka.cmp(&kb); }
+    let position = |v: &str| listed.iter().position(|l| l == v).unwrap_or(usize::MAX);
+    position(b).cmp(&position(a))
+}
+
+/// Every number in the name before a `proton-` suffix, empty when there is none.
+fn runner_version_key(version: &str) -> Vec<u64> {
+    let name = version.find("proton-").map(|i| &version[..i]).unwrap_or(version);
+    name.split(|c: char| !c.is_ascii_digit()).filter(|s| !s.is_empty()).filter_map(|s| s.parse().ok()).collect()
+}
+
+/// Runs once the manifests are loaded. Installs whose newest runner is already installed switch right away, for the others the runner gets queued and `apply_runner_upgrades` switches them.
+pub fn check_runner_upgrades<R: Runtime>(app: &AppHandle<R>) {
+    let Some(gs) = get_settings(app) else { return; };
+    for install in get_installs(app).unwrap_or_default().into_iter().filter(|i| i.runner_auto_upgrade) {
+        let Some(target) = runner_upgrade_target(app, &install) else { continue; };
+        if get_installed_runner_info_by_version(app, target.clone()).is_some_and(|r| r.is_installed) { switch_install_runner(app, &install, &target); } else { queue_runner_upgrade(app, &gs.default_runner_path, &target); }
+    }
+}
+
+/// Switches every tracking install whose newest runner is `runner_version`, called when a runner download finished.
+pub fn apply_runner_upgrades<R: Runtime>(app: &AppHandle<R>, runner_version: &str) {
+    for install in get_installs(app).unwrap_or_default().into_iter().filter(|i| i.runner_auto_upgrade) {
+        if runner_upgrade_target(app, &install).as_deref() == Some(runner_version) { switch_install_runner(app, &install, runner_version); }
+    }
+}
+
+/// Puts the install back on the runner the last upgrade replaced and stops tracking, otherwise the next start would upgrade it again.
+/// Returns the runner version rolled back to, the caller downloads it again when it was removed in the meantime.
+pub fn rollback_install_runner<R: Runtime>(app: &AppHandle<R>, install: &LauncherInstall) -> Option<String> {
+    if install.previous_runner_version.is_empty() { return None; }
+    update_install_runner_auto_upgrade_by_id(app, install.id.clone(), false);
+    update_install_previous_runner_version_by_id(app, install.id.clone(), String::new());
+    log::info!("Rolled back runner of {} from {} to {}, automatic runner upgrades are off now", install.name, install.runner_version, install.previous_runner_version);
+    Some(install.previous_runner_version.clone())
+}
+
+/// Moves the install to `version` and remembers the runner it was on. Running games are left alone, the next start of the launcher switches them.
+fn switch_install_runner<R: Runtime>(app: &AppHandle<R>, install: &LauncherInstall, version: &str) {
+    if list_sessions(app).iter().any(|s| s.install_id == install.id) { log::info!("{} is running, upgrading its runner to {} on the next start", install.name, version); return; }
+    let Some(runner) = get_installed_runner_info_by_version(app, version.to_string()) else { return; };
+    snapshot_prefix_in_background(app, install.clone(), "runner_switch");
+    update_install_previous_runner_version_by_id(app, install.id.clone(), install.runner_version.clone());
+    update_install_runner_version_by_id(app, install.id.clone(), version.to_string());
+    update_install_runner_location_by_id(app, install.id.clone(), runner.runner_path);
+    log::info!("Upgraded runner of {} from {} to {}", install.name, install.runner_version, version);
+    show_dialog_with_callback(app, "info", "TwintailLauncher", "dialogs.runner_auto_upgraded", None, None, Some(HashMap::from([("install_name", install.name.as_str()), ("previous_runner", install.runner_version.as_str()), ("runner_version", version)])));
+}
+
+fn queue_runner_upgrade<R: Runtime>(app: &AppHandle<R>, runner_dir: &str, version: &str) {
+    let Some((runner_url, runner_hash)) = runner_download_source(app, version) else { return; };
+    let state = app.state::<DownloadState>();
+    let q = state.queue.lock().unwrap().clone();
+    let Some(queue) = q else { return; };
+    if queue.has_job_for_id(version.to_string()) { return; }
+    let runner_path = Path::new(runner_dir).join(version);
+    if let Err(e) = std::fs::create_dir_all(&runner_path) { log::warn!("Failed to create runner folder {}: {}", runner_path.display(), e); return; }
+    if get_installed_runner_info_by_version(app, version.to_string()).is_none() { let _ = create_installed_runner(app, version.to_string(), false, runner_path.to_str().unwrap().to_string()); }
+    log::info!("Queuing runner {} for automatic runner upgrades", version);
+    queue.enqueue(QueueJobKind::RunnerDownload, QueueJobPayload::Runner(RunnerDownloadPayload { runner_version: version.to_string(), runner_url, runner_path: runner_path.to_str().unwrap().to_string(), runner_hash }));
+}
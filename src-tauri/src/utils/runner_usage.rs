@@ -0,0 +1,101 @@
+use serde::Serialize;
+
+#[cfg(target_os = "linux")]
+use crate::DownloadState;
+#[cfg(target_os = "linux")]
+use crate::downloading::disk_space::dir_size;
+#[cfg(target_os = "linux")]
+use crate::utils::db_manager::{delete_installed_runner_by_version, get_installed_runners, get_installs, get_settings};
+#[cfg(target_os = "linux")]
+use crate::utils::game_sessions::list_sessions;
+#[cfg(target_os = "linux")]
+use crate::utils::runner_from_runner_version;
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+#[cfg(target_os = "linux")]
+use std::path::Path;
+#[cfg(target_os = "linux")]
+use tauri::{AppHandle, Manager, Runtime};
+
+/// One runner of the usage report.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunnerUsage {
+    pub version: String,
+    pub path: String,
+    /// Compatibility manifest the runner comes from, for example "proton_ge". Empty for unknown runners.
+    pub family: String,
+    pub size: u64,
+    pub is_installed: bool,
+    pub installs: Vec<RunnerUser>,
+    pub last_used: i64,
+    /// Used by an install, a running game or a queued download. Referenced runners are never cleaned up.
+    pub referenced: bool,
+    /// Imported from a local directory, removing it only drops the link.
+    pub is_local: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunnerUser {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunnerCleanupReport {
+    pub removed: Vec<String>,
+    /// Requested runners that are no longer cleanup candidates, usually because something started using them.
+    pub skipped: Vec<String>,
+    pub freed: u64,
+}
+
+/// Every runner known to the launcher with its size and users. SteamRT and DXVK share the table but are left out.
+#[cfg(target_os = "linux")]
+pub fn runner_usage_report<R: Runtime>(app: &AppHandle<R>) -> Vec<RunnerUsage> {
+    let installs = get_installs(app).unwrap_or_default();
+    let running = list_sessions(app).into_iter().map(|s| s.runner_version).collect::<Vec<String>>();
+    let queue = app.state::<DownloadState>().queue.lock().unwrap().clone();
+
+    get_installed_runners(app).unwrap_or_default().into_iter().filter(|r| r.version != "steamrt" && !r.version.to_ascii_lowercase().contains("dxvk")).map(|r| {
+        let users = installs.iter().filter(|i| i.runner_version == r.version).map(|i| RunnerUser { id: i.id.clone(), name: i.name.clone() }).collect::<Vec<RunnerUser>>();
+        let queued = queue.as_ref().is_some_and(|q| q.has_job_for_id(r.version.clone()));
+        // The runner an automatic upgrade replaced stays around for rollback
+        let kept = installs.iter().any(|i| i.previous_runner_version == r.version);
+        let referenced = !users.is_empty() || kept || queued || running.contains(&r.version);
+        let family = runner_from_runner_version(app, r.version.clone()).unwrap_or_default().trim_end_matches(".json").to_string();
+        let is_local = r.local_runner.0.is_some();
+        // Imported runners are only linked, removing them frees nothing
+        let size = if r.is_installed && !is_local { dir_size(Path::new(&r.runner_path)) } else { 0 };
+        RunnerUsage { version: r.version, path: r.runner_path, family, size, is_installed: r.is_installed, installs: users, last_used: r.last_used, referenced, is_local }
+    }).collect()
+}
+
+/// Unreferenced runners that can go. With `keep_per_family` the most recently used (then most recently added) unreferenced runners of each family stay as spares.
+#[cfg(target_os = "linux")]
+pub fn cleanup_candidates(report: Vec<RunnerUsage>, keep_per_family: usize) -> Vec<RunnerUsage> {
+    let mut families: HashMap<String, Vec<RunnerUsage>> = HashMap::new();
+    for r in report.into_iter().filter(|r| !r.referenced) { families.entry(r.family.clone()).or_default().push(r); }
+    let mut out = Vec::new();
+    for (_, mut runners) in families {
+        // The report keeps table order, reversing first makes the stable sort prefer later added runners on equal last use
+        runners.reverse();
+        runners.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+        out.extend(runners.into_iter().skip(keep_per_family));
+    }
+    out.sort_by(|a, b| a.version.cmp(&b.version));
+    out
+}
+
+/// Removes the runner folder and its table row. Folders outside the runner directory are only forgotten, never deleted.
+#[cfg(target_os = "linux")]
+pub fn remove_runner<R: Runtime>(app: &AppHandle<R>, runner: &RunnerUsage) -> bool {
+    if runner.is_local { crate::utils::local_runners::forget_local_runner(app, &runner.version); return true; }
+    let Some(gs) = get_settings(app) else { return false; };
+    let path = Path::new(&runner.path);
+    if path.exists() && path.starts_with(&gs.default_runner_path) && path != Path::new(&gs.default_runner_path) {
+        if let Err(e) = std::fs::remove_dir_all(path) { log::warn!("Failed to remove runner {} at {}: {}", runner.version, path.display(), e); return false; }
+    }
+    crate::downloading::verify::delete_runner_manifest(app, &runner.version);
+    delete_installed_runner_by_version(app, runner.version.clone());
+    log::info!("Removed unused runner {} ({} bytes)", runner.version, runner.size);
+    true
+}
@@ -0,0 +1,127 @@
+use crate::utils::game_sessions::get_session;
+use serde::Serialize;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// Logs kept per install, older ones are removed when a new launch starts.
+const MAX_SESSION_LOGS: usize = 10;
+/// Size all logs of an install may take up together, checked with the count on every launch and kept while the game runs.
+const MAX_SESSION_LOG_BYTES: u64 = 512 * 1024 * 1024;
+/// How often a running launch checks its logs against `MAX_SESSION_LOG_BYTES`.
+const LOG_CAP_POLL: Duration = Duration::from_secs(5);
+/// `tail_session_log` only reads this much from the end, WINEDEBUG channels can make logs huge.
+const MAX_TAIL_BYTES: u64 = 256 * 1024;
+
+/// Returned by `get_session_log_tail`.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionLogTail {
+    pub path: String,
+    pub size: u64,
+    pub content: String,
+    /// Set when `content` does not start at the beginning of the file.
+    pub truncated: bool,
+}
+
+/// Game output of every launch lands in `<app log dir>/sessions/<install id>/`, Proton logs go there as well.
+pub fn session_log_dir<R: Runtime>(app: &AppHandle<R>, install_id: &str) -> Option<PathBuf> {
+    app.path().app_log_dir().ok().map(|d| d.join("sessions").join(install_id))
+}
+
+/// Creates the log file of a new launch with a short header and rotates old ones out, Proton's `steam-*.log` files included.
+/// Returns a handle for stdout and one for stderr, both point to the same file. Must be called after `begin_session`, the size cap is kept until the session ends.
+pub fn create_session_log<R: Runtime>(app: &AppHandle<R>, install_id: &str, name: &str, command: &str) -> Option<(File, File, PathBuf)> {
+    let dir = session_log_dir(app, install_id)?;
+    if let Err(e) = std::fs::create_dir_all(&dir) { log::warn!("Failed to create session log directory {}: {}", dir.display(), e); return None; }
+    rotate_session_logs(&dir, MAX_SESSION_LOGS.saturating_sub(1), None);
+
+    let launched = SystemTime::now();
+    let now = launched.duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs();
+    let path = dir.join(format!("session-{}.log", now));
+    // Append mode lets the game keep writing at the new end once `enforce_log_cap` truncates the file
+    let mut file = match OpenOptions::new().create(true).truncate(false).append(true).open(&path) {
+        Ok(f) => f,
+        Err(e) => { log::warn!("Failed to create session log {}: {}", path.display(), e); return None; }
+    };
+    let _ = writeln!(file, "# {} ({}) launched at {}\n# {}\n", name, install_id, now, command);
+    let stderr = file.try_clone().ok()?;
+    let watched = file.try_clone().ok()?;
+    log::debug!("Writing game output of {} to {}", name, path.display());
+
+    let app = app.clone();
+    let id = install_id.to_string();
+    let p = path.clone();
+    std::thread::spawn(move || enforce_log_cap(&app, &id, watched, &p, launched));
+    Some((file, stderr, path))
+}
+
+/// Keeps the logs of a running launch within `MAX_SESSION_LOG_BYTES` until its session ends.
+/// Logs of older launches go first, the active one is only emptied when it alone is over the cap.
+fn enforce_log_cap<R: Runtime>(app: &AppHandle<R>, install_id: &str, file: File, path: &Path, launched: SystemTime) {
+    let Some(dir) = path.parent() else { return; };
+    while get_session(app, install_id).is_some() {
+        std::thread::sleep(LOG_CAP_POLL);
+        let Ok(size) = file.metadata().map(|m| m.len()) else { break; };
+        rotate_session_logs(dir, MAX_SESSION_LOGS, Some(launched));
+        if size > MAX_SESSION_LOG_BYTES {
+            log::warn!("Session log {} reached {} bytes, truncating it", path.display(), size);
+            if file.set_len(0).is_ok() { let _ = writeln!(&file, "# log truncated after reaching {} bytes\n", size); }
+        }
+    }
+}
+
+/// Removes all logs of an install, called when the install itself is removed.
+pub fn delete_session_logs<R: Runtime>(app: &AppHandle<R>, install_id: &str) {
+    let Some(dir) = session_log_dir(app, install_id) else { return; };
+    if !dir.exists() { return; }
+    if let Err(e) = std::fs::remove_dir_all(&dir) { log::warn!("Failed to remove session logs {}: {}", dir.display(), e); }
+}
+
+pub fn latest_session_log<R: Runtime>(app: &AppHandle<R>, install_id: &str) -> Option<PathBuf> {
+    list_session_logs(&session_log_dir(app, install_id)?).pop()
+}
+
+pub fn tail_session_log(path: &Path, lines: usize) -> Option<SessionLogTail> {
+    let mut file = File::open(path).ok()?;
+    let size = file.metadata().ok()?.len();
+    let start = size.saturating_sub(MAX_TAIL_BYTES);
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut buf = Vec::new();
+    file.read_to_end(&mut buf).ok()?;
+
+    let text = String::from_utf8_lossy(&buf);
+    let all = text.lines().collect::<Vec<&str>>();
+    // The first line is most likely cut in half when reading from the middle of the file
+    let skip = if start > 0 { 1 } else { 0 };
+    let from = all.len().saturating_sub(lines).max(skip);
+    Some(SessionLogTail { path: path.to_str().unwrap_or_default().to_string(), size, content: all[from.min(all.len())..].join("\n"), truncated: from > 0 || start > 0 })
+}
+
+/// Session logs of an install oldest first, the timestamp in the name keeps them sortable.
+fn list_session_logs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new(); };
+    let mut logs = entries.flatten().map(|e| e.path()).filter(|p| p.file_name().and_then(|n| n.to_str()).is_some_and(|n| n.starts_with("session-") && n.ends_with(".log"))).collect::<Vec<PathBuf>>();
+    logs.sort();
+    logs
+}
+
+/// Removes the oldest logs until at most `keep` are left and they fit in `MAX_SESSION_LOG_BYTES`.
+/// Proton names its logs after the app id and rewrites them every launch, so everything is ordered by modification time.
+/// Logs written since `active_since` belong to the running launch and are counted but never removed, the game still has them open.
+fn rotate_session_logs(dir: &Path, keep: usize, active_since: Option<SystemTime>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return; };
+    let mut logs = entries.flatten().filter(|e| e.file_name().to_str().is_some_and(|n| (n.starts_with("session-") || n.starts_with("steam-")) && n.ends_with(".log"))).filter_map(|e| e.metadata().ok().map(|m| (e.path(), m.len(), m.modified().ok()))).collect::<Vec<(PathBuf, u64, Option<SystemTime>)>>();
+    logs.sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.cmp(&b.0)));
+    let mut total = logs.iter().map(|l| l.1).sum::<u64>();
+    let mut count = logs.len();
+    for (old, size, modified) in logs.iter() {
+        if count <= keep && total <= MAX_SESSION_LOG_BYTES { break; }
+        if active_since.is_some_and(|since| modified.is_some_and(|m| m >= since)) { continue; }
+        match std::fs::remove_file(old) {
+            Ok(_) => { count -= 1; total -= size; }
+            Err(e) => log::warn!("Failed to remove old session log {}: {}", old.display(), e),
+        }
+    }
+}
@@ -2,8 +2,8 @@ use std::fs;
 use std::io::{Write};
 use std::path::{PathBuf};
 use steam_shortcuts_util::{parse_shortcuts, shortcuts_to_bytes, Shortcut};
-#[cfg(target_os = "linux")]
 use tauri::{AppHandle, Runtime, Manager};
+use crate::utils::db_manager::get_launch_profiles_by_install_id;
 
 #[cfg(target_os = "linux")]
 pub fn resolve_normal_steam_userdata(home_dir: PathBuf) -> PathBuf {
@@ -103,6 +103,20 @@ pub fn remove_steam_shortcut(file: PathBuf, appname: &str) -> bool {
     result
 }
 
+#[allow(dead_code)]
+pub fn rename_steam_shortcut(file: PathBuf, old_name: &str, new_name: &str) -> bool {
+    let mut result = false;
+    for u in check_steam_user_data_dir(file.clone()) {
+        let shortcut_file = file.join(u).join("config").join("shortcuts.vdf");
+        let Ok(data) = fs::read(&shortcut_file) else { continue };
+        let Ok(mut shortcuts) = parse_shortcuts(data.as_slice()) else { continue };
+        let Some(shortcut) = shortcuts.iter_mut().find(|s| s.app_name == old_name) else { continue };
+        shortcut.app_name = new_name;
+        if fs::write(&shortcut_file, shortcuts_to_bytes(&shortcuts)).is_ok() { result = true; }
+    }
+    result
+}
+
 #[allow(dead_code)]
 pub fn add_desktop_shortcut(file: PathBuf, content: String) -> bool {
     if file.exists() { return false; }
@@ -125,8 +139,49 @@ pub fn remove_desktop_shortcut(file: PathBuf) -> bool {
     }
 }
 
+/// Shortcuts of a launch profile are named `<install> (<profile>)`, they follow renames of the profile and are removed with it (`new_profile` is None).
+pub fn sync_profile_shortcuts<R: Runtime>(app: &AppHandle<R>, install_name: &str, old_profile: &str, new_profile: Option<&str>) {
+    rename_profile_shortcut(app, &format!("{} ({})", install_name, old_profile), new_profile.map(|p| format!("{} ({})", install_name, p)));
+}
+
+/// Same for the install side of the name, profile shortcuts follow renames of the install and are removed with it (`new_install` is None).
+pub fn sync_install_profile_shortcuts<R: Runtime>(app: &AppHandle<R>, install_id: &str, old_install: &str, new_install: Option<&str>) {
+    if new_install == Some(old_install) { return; }
+    for p in get_launch_profiles_by_install_id(app, install_id.to_string()) {
+        rename_profile_shortcut(app, &format!("{} ({})", old_install, p.name), new_install.map(|i| format!("{} ({})", i, p.name)));
+    }
+}
+
+fn rename_profile_shortcut<R: Runtime>(app: &AppHandle<R>, old_name: &str, new_name: Option<String>) {
+    #[cfg(target_os = "linux")]
+    {
+        let home = app.path().home_dir().unwrap();
+        let base = home.join(".local/share/applications");
+        let desktop = base.join(format!("{}.desktop", old_name));
+        if desktop.exists() {
+            let ok = match new_name.as_ref() { Some(n) => update_desktop_shortcut(desktop.clone(), n.clone(), base), None => remove_desktop_shortcut(desktop.clone()) };
+            if !ok { log::warn!("Failed to update desktop shortcut {}", desktop.display()); }
+        }
+        for userdata in [home.join(".var/app/com.valvesoftware.Steam/data/Steam/userdata"), resolve_normal_steam_userdata(home.clone())] {
+            if !userdata.exists() { continue; }
+            let _ = match new_name.as_ref() { Some(n) => rename_steam_shortcut(userdata, old_name, n), None => remove_steam_shortcut(userdata, old_name) };
+        }
+    }
+    #[cfg(target_os = "windows")]
+    {
+        let base = app.path().desktop_dir().unwrap();
+        let lnk = base.join(format!("{}.lnk", old_name));
+        if lnk.exists() {
+            let r = match new_name.as_ref() { Some(n) => fs::rename(&lnk, base.join(format!("{}.lnk", n))), None => fs::remove_file(&lnk) };
+            if let Err(e) = r { log::warn!("Failed to update desktop shortcut {}: {}", lnk.display(), e); }
+        }
+    }
+}
+
+/// `old_name` is the name the install had before the update, profile shortcuts are only found by it.
 #[cfg(target_os = "linux")]
-pub fn sync_desktop_shortcut<R: Runtime>(app: &AppHandle<R>, install_id: String, new_name: String) {
+pub fn sync_desktop_shortcut<R: Runtime>(app: &AppHandle<R>, install_id: String, old_name: String, new_name: String) {
+    sync_install_profile_shortcuts(app, &install_id, &old_name, Some(&new_name));
     let install = crate::utils::db_manager::get_install_info_by_id(app, install_id).unwrap();
     if install.shortcut_path.is_empty() { return; }
     let base = app.path().home_dir().unwrap().join(".local/share/applications");
@@ -632,6 +632,7 @@ export default class App extends React.Component<any, any> {
                     runners={this.state.runners}
                     installedRunners={this.state.installedRunners}
                     fetchInstalledRunners={this.fetchInstalledRunners}
+                    fetchCompatibilityVersions={() => { this.fetchCompatibilityVersions(); this.fetchCompatibilityVersionsFiltered(); }}
                     pushInstalls={this.pushInstalls}
                     imageVersion={this.state.imageVersion}
                 />
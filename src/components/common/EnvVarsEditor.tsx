@@ -0,0 +1,113 @@
+import React from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { AlertTriangle, Plus, Trash2 } from "lucide-react";
+import { SettingsCard } from "./SettingsComponents.tsx";
+import { translate } from "../../utils/i18n";
+
+export interface EnvVar {
+    key: string;
+    value: string;
+    enabled: boolean;
+}
+
+interface EnvVarError {
+    index: number;
+    key: string;
+    reason: "invalid_name" | "duplicate" | "invalid_value" | "invalid_dll_overrides";
+}
+
+interface EnvConflict {
+    key: string;
+    launcher_value: string;
+    user_value: string;
+    result: string;
+    winner: "user" | "merged";
+}
+
+// Installs that were never saved from the editor only have the old KEY=value; string
+export const parseLegacy = (envVars: string): EnvVar[] => (envVars || "").split(";").map((e) => e.split("=")).filter((p) => p.length >= 2 && p[0].trim() !== "").map(([k, ...v]) => ({ key: k.trim(), value: v.join("=").replace(/"/g, ""), enabled: true }));
+
+export const EnvVarsEditor = ({ install, onSaved }: { install: any, onSaved: () => void }) => {
+    const stored: EnvVar[] = React.useMemo(() => (install.env_list?.length ? install.env_list : parseLegacy(install.env_vars)), [install.env_list, install.env_vars]);
+    const [localVars, setLocalVars] = React.useState<EnvVar[]>(stored);
+    const [errors, setErrors] = React.useState<EnvVarError[]>([]);
+    const [conflicts, setConflicts] = React.useState<EnvConflict[]>([]);
+
+    React.useEffect(() => {
+        setLocalVars(stored);
+    }, [stored]);
+
+    const validate = React.useCallback(async (vars: EnvVar[]) => {
+        const report = await invoke<{ errors: EnvVarError[], conflicts: EnvConflict[] } | null>("validate_install_env_vars", { id: install.id, vars });
+        setErrors(report?.errors ?? []);
+        setConflicts(report?.conflicts ?? []);
+        return report?.errors ?? [];
+    }, [install.id]);
+
+    React.useEffect(() => { validate(stored); }, [stored, validate]);
+
+    const commit = async (next: EnvVar[]) => {
+        setLocalVars(next);
+        // Rows without a name yet stay local, everything else has to pass validation before it is saved
+        if (next.some((v) => v.key.trim() === "")) return;
+        if ((await validate(next)).length > 0) return;
+        if (await invoke<boolean | null>("update_install_env_list", { id: install.id, vars: next })) onSaved();
+    };
+
+    const patch = (idx: number, value: Partial<EnvVar>) => localVars.map((v, i) => (i === idx ? { ...v, ...value } : v));
+
+    return (
+        <SettingsCard>
+            <div className="flex flex-col gap-3">
+                <div className="flex flex-col gap-1">
+                    <label className="text-base font-medium text-white">{translate("game_settings.launch.env_vars.label")}</label>
+                    <span className="text-sm text-zinc-400">{translate("game_settings.launch.env_vars.description")}</span>
+                </div>
+                {localVars.map((v, idx) => {
+                    const error = errors.find((e) => e.index === idx);
+                    return (
+                        <div key={idx} className="flex flex-col gap-1">
+                            <div className="flex items-center gap-2">
+                                <input type="checkbox" checked={v.enabled} onChange={(e) => commit(patch(idx, { enabled: e.target.checked }))} className="accent-purple-600" title={translate("game_settings.launch.env_vars.enabled")} />
+                                <input
+                                    value={v.key}
+                                    onChange={(e) => setLocalVars(patch(idx, { key: e.target.value }))}
+                                    onBlur={() => commit(localVars)}
+                                    placeholder="DXVK_HUD"
+                                    className={`w-1/3 bg-black/40 border rounded-lg px-3 py-2 text-white placeholder-zinc-600 focus:outline-none font-mono text-sm ${error ? "border-red-500/50" : "border-white/10 focus:border-purple-500/50"}`}
+                                />
+                                <input
+                                    value={v.value}
+                                    onChange={(e) => setLocalVars(patch(idx, { value: e.target.value }))}
+                                    onBlur={() => commit(localVars)}
+                                    placeholder="fps,devinfo"
+                                    className="flex-1 bg-black/40 border border-white/10 rounded-lg px-3 py-2 text-white placeholder-zinc-600 focus:outline-none focus:border-purple-500/50 font-mono text-sm"
+                                />
+                                <button onClick={() => commit(localVars.filter((_, i) => i !== idx))} className="p-2 text-zinc-400 hover:text-red-400 transition-colors" title={translate("game_settings.launch.env_vars.remove")}>
+                                    <Trash2 className="w-4 h-4" />
+                                </button>
+                            </div>
+                            {error && <span className="text-xs text-red-400 pl-6">{translate(`game_settings.launch.env_vars.error.${error.reason}`)}</span>}
+                        </div>
+                    );
+                })}
+                <button
+                    onClick={() => setLocalVars([...localVars, { key: "", value: "", enabled: true }])}
+                    className="flex items-center gap-2 self-start text-purple-400 hover:text-purple-300 text-sm font-medium transition-colors"
+                >
+                    <Plus className="w-4 h-4" /> {translate("game_settings.launch.env_vars.add")}
+                </button>
+                {conflicts.length > 0 && (
+                    <div className="flex flex-col gap-1 bg-amber-500/10 border border-amber-500/20 rounded-lg p-3 text-sm">
+                        <span className="flex items-center gap-2 text-amber-300 font-medium"><AlertTriangle className="w-4 h-4" /> {translate("game_settings.launch.env_vars.conflicts")}</span>
+                        {conflicts.map((c) => (
+                            <span key={c.key} className="text-zinc-300 font-mono text-xs break-all">
+                                {c.key}: {c.launcher_value} → {c.result} ({translate(`game_settings.launch.env_vars.winner.${c.winner}`)})
+                            </span>
+                        ))}
+                    </div>
+                )}
+            </div>
+        </SettingsCard>
+    );
+};
@@ -0,0 +1,86 @@
+import React from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Eye, Wand2 } from "lucide-react";
+import { SettingsCard } from "./SettingsComponents.tsx";
+import { translate } from "../../utils/i18n";
+
+const FIELDS = ["use_gamemode", "use_mangohud", "launch_args", "env_list", "graphics_api", "show_discord_rpc", "disable_system_idle"];
+
+interface LaunchDefaultsDiff {
+    install_id: string;
+    install_name: string;
+    changes: { field: string, current: any, new: any }[];
+}
+
+const formatValue = (value: any): string => {
+    if (typeof value === "boolean") return translate(value ? "launcher_settings.launch_defaults.apply.on" : "launcher_settings.launch_defaults.apply.off");
+    if (Array.isArray(value)) return value.filter((v) => v.enabled).map((v) => `${v.key}=${v.value}`).join(" ") || "-";
+    return value === "" ? "-" : String(value);
+};
+
+// Nothing is written before the diff was looked at, installs can be unticked in the preview
+export const LaunchDefaultsApply = () => {
+    const [fields, setFields] = React.useState<string[]>(FIELDS);
+    const [diffs, setDiffs] = React.useState<LaunchDefaultsDiff[] | null>(null);
+    const [selected, setSelected] = React.useState<string[]>([]);
+    const [applied, setApplied] = React.useState<number | null>(null);
+
+    const preview = async () => {
+        const d = await invoke<LaunchDefaultsDiff[] | null>("preview_apply_launch_defaults", { fields });
+        setDiffs(d ?? []);
+        setSelected((d ?? []).map((x) => x.install_id));
+        setApplied(null);
+    };
+
+    const apply = async () => {
+        const count = await invoke<number | null>("apply_launch_defaults_cmd", { fields, installIds: selected });
+        setApplied(count ?? 0);
+        setDiffs(null);
+    };
+
+    const toggle = (list: string[], value: string) => (list.includes(value) ? list.filter((v) => v !== value) : [...list, value]);
+
+    return (
+        <SettingsCard>
+            <div className="flex flex-col gap-3">
+                <div className="flex flex-col gap-1">
+                    <label className="text-base font-medium text-white">{translate("launcher_settings.launch_defaults.apply.label")}</label>
+                    <span className="text-sm text-zinc-400">{translate("launcher_settings.launch_defaults.apply.description")}</span>
+                </div>
+                <div className="flex flex-wrap gap-x-4 gap-y-2 text-sm text-zinc-300">
+                    {FIELDS.map((f) => (
+                        <label key={f} className="flex items-center gap-2 cursor-pointer">
+                            <input type="checkbox" checked={fields.includes(f)} onChange={() => { setFields(toggle(fields, f)); setDiffs(null); }} className="accent-purple-600" />
+                            {translate(`launcher_settings.launch_defaults.${f}.label`)}
+                        </label>
+                    ))}
+                </div>
+                {diffs !== null && diffs.length === 0 && <span className="text-sm text-zinc-400">{translate("launcher_settings.launch_defaults.apply.no_changes")}</span>}
+                {diffs?.map((d) => (
+                    <div key={d.install_id} className="flex flex-col gap-1 bg-black/20 border border-white/5 rounded-lg p-3">
+                        <label className="flex items-center gap-2 text-white text-sm font-medium cursor-pointer">
+                            <input type="checkbox" checked={selected.includes(d.install_id)} onChange={() => setSelected(toggle(selected, d.install_id))} className="accent-purple-600" />
+                            {d.install_name}
+                        </label>
+                        {d.changes.map((c) => (
+                            <span key={c.field} className="text-xs text-zinc-400 pl-6 break-all">
+                                {translate(`launcher_settings.launch_defaults.${c.field}.label`)}: <span className="font-mono text-red-300/80">{formatValue(c.current)}</span> → <span className="font-mono text-emerald-300/80">{formatValue(c.new)}</span>
+                            </span>
+                        ))}
+                    </div>
+                ))}
+                {applied !== null && <span className="text-sm text-emerald-400">{translate("launcher_settings.launch_defaults.apply.done", { count: `${applied}` })}</span>}
+                <div className="flex items-center gap-4">
+                    <button onClick={preview} disabled={fields.length === 0} className="flex items-center gap-2 text-purple-400 hover:text-purple-300 disabled:opacity-50 text-sm font-medium transition-colors">
+                        <Eye className="w-4 h-4" /> {translate("launcher_settings.launch_defaults.apply.preview")}
+                    </button>
+                    {diffs !== null && diffs.length > 0 && (
+                        <button onClick={apply} disabled={selected.length === 0} className="flex items-center gap-2 text-emerald-400 hover:text-emerald-300 disabled:opacity-50 text-sm font-medium transition-colors">
+                            <Wand2 className="w-4 h-4" /> {translate("launcher_settings.launch_defaults.apply.confirm", { count: `${selected.length}` })}
+                        </button>
+                    )}
+                </div>
+            </div>
+        </SettingsCard>
+    );
+};
@@ -0,0 +1,102 @@
+import React from "react";
+import { Plus, Trash2 } from "lucide-react";
+import { SettingsCard } from "./SettingsComponents.tsx";
+import HelpTooltip from "./HelpTooltip.tsx";
+import { translate } from "../../utils/i18n";
+
+export type LaunchHookStage = "pre_launch" | "post_launch" | "post_exit";
+
+export interface LaunchHook {
+    stage: LaunchHookStage;
+    command: string;
+    enabled: boolean;
+    timeout_secs: number;
+    required: boolean;
+}
+
+const STAGES: LaunchHookStage[] = ["pre_launch", "post_launch", "post_exit"];
+
+export const LaunchHooksEditor = ({ hooks, helpText, onChange }: { hooks: LaunchHook[], helpText?: string, onChange: (hooks: LaunchHook[]) => void }) => {
+    // Edits stay local until the field loses focus, same as ModernInput
+    const [localHooks, setLocalHooks] = React.useState<LaunchHook[]>(hooks ?? []);
+
+    React.useEffect(() => {
+        setLocalHooks(hooks ?? []);
+    }, [hooks]);
+
+    const commit = (next: LaunchHook[]) => {
+        setLocalHooks(next);
+        // Empty commands are rejected by the backend, keep them local until filled in
+        if (next.every((h) => h.command.trim() !== "")) onChange(next);
+    };
+
+    const patch = (idx: number, value: Partial<LaunchHook>) => localHooks.map((h, i) => (i === idx ? { ...h, ...value } : h));
+
+    return (
+        <SettingsCard>
+            <div className="flex flex-col gap-3">
+                <div className="flex flex-col gap-1">
+                    <div className="flex items-center gap-2">
+                        <label className="text-base font-medium text-white">{translate("game_settings.launch.hooks.label")}</label>
+                        {helpText && <HelpTooltip text={helpText} />}
+                    </div>
+                    <span className="text-sm text-zinc-400">{translate("game_settings.launch.hooks.description")}</span>
+                </div>
+                {localHooks.map((hook, idx) => (
+                    <div key={idx} className="flex flex-col gap-2 bg-black/20 border border-white/5 rounded-lg p-3">
+                        <div className="flex items-center gap-2">
+                            <select
+                                value={hook.stage}
+                                onChange={(e) => commit(patch(idx, { stage: e.target.value as LaunchHookStage }))}
+                                className="bg-black/40 border border-white/10 rounded-lg px-3 py-2 text-white text-sm focus:outline-none focus:border-purple-500/50"
+                            >
+                                {STAGES.map((s) => <option key={s} value={s}>{translate(`game_settings.launch.hooks.stage.${s}`)}</option>)}
+                            </select>
+                            <input
+                                value={hook.command}
+                                onChange={(e) => setLocalHooks(patch(idx, { command: e.target.value }))}
+                                onBlur={() => commit(localHooks)}
+                                placeholder={hook.stage === "post_exit" ? "cp -r \"%prefix%/pfx/drive_c/users\" ~/backups/" : "echo %install_dir%"}
+                                className="flex-1 bg-black/40 border border-white/10 rounded-lg px-3 py-2 text-white placeholder-zinc-600 focus:outline-none focus:border-purple-500/50 font-mono text-sm"
+                            />
+                            <button onClick={() => commit(localHooks.filter((_, i) => i !== idx))} className="p-2 text-zinc-400 hover:text-red-400 transition-colors" title={translate("game_settings.launch.hooks.remove")}>
+                                <Trash2 className="w-4 h-4" />
+                            </button>
+                        </div>
+                        <div className="flex items-center gap-4 text-sm text-zinc-300">
+                            <label className="flex items-center gap-2 cursor-pointer">
+                                <input type="checkbox" checked={hook.enabled} onChange={(e) => commit(patch(idx, { enabled: e.target.checked }))} className="accent-purple-600" />
+                                {translate("game_settings.launch.hooks.enabled")}
+                            </label>
+                            {hook.stage === "pre_launch" && (
+                                <>
+                                    <label className="flex items-center gap-2 cursor-pointer">
+                                        <input type="checkbox" checked={hook.required} onChange={(e) => commit(patch(idx, { required: e.target.checked }))} className="accent-purple-600" />
+                                        {translate("game_settings.launch.hooks.required")}
+                                    </label>
+                                    <label className="flex items-center gap-2">
+                                        {translate("game_settings.launch.hooks.timeout")}
+                                        <input
+                                            type="number"
+                                            min={0}
+                                            value={hook.timeout_secs}
+                                            onChange={(e) => setLocalHooks(patch(idx, { timeout_secs: Math.max(0, parseInt(e.target.value) || 0) }))}
+                                            onBlur={() => commit(localHooks)}
+                                            className="w-20 bg-black/40 border border-white/10 rounded-lg px-2 py-1 text-white text-sm focus:outline-none focus:border-purple-500/50"
+                                        />
+                                    </label>
+                                </>
+                            )}
+                        </div>
+                    </div>
+                ))}
+                <button
+                    onClick={() => setLocalHooks([...localHooks, { stage: "pre_launch", command: "", enabled: true, timeout_secs: 30, required: false }])}
+                    className="flex items-center gap-2 self-start text-purple-400 hover:text-purple-300 text-sm font-medium transition-colors"
+                >
+                    <Plus className="w-4 h-4" /> {translate("game_settings.launch.hooks.add")}
+                </button>
+            </div>
+        </SettingsCard>
+    );
+};
@@ -0,0 +1,91 @@
+import React from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Monitor, Play, Plus, Save, Trash2 } from "lucide-react";
+import { SettingsCard } from "./SettingsComponents.tsx";
+import { EnvVar } from "./EnvVarsEditor.tsx";
+import { translate } from "../../utils/i18n";
+
+export interface LaunchProfile {
+    id: string;
+    install_id: string;
+    name: string;
+    launch_args: string;
+    env_vars: string;
+    env_list: EnvVar[];
+    use_mangohud: boolean;
+    use_gamemode: boolean;
+    use_xxmi: boolean;
+    use_fps_unlock: boolean;
+    fps_value: string;
+    graphics_api: string;
+    created_at: number;
+}
+
+export const LaunchProfilesEditor = ({ install, onLaunch }: { install: any, onLaunch: () => void }) => {
+    const [profiles, setProfiles] = React.useState<LaunchProfile[]>([]);
+    const [newName, setNewName] = React.useState("");
+
+    const refresh = React.useCallback(() => {
+        invoke<LaunchProfile[] | null>("list_launch_profiles", { installId: install.id }).then((p) => setProfiles(p ?? []));
+    }, [install.id]);
+
+    React.useEffect(() => { refresh(); }, [refresh]);
+
+    const create = async () => {
+        if (newName.trim() === "") return;
+        const created = await invoke<LaunchProfile | null>("create_launch_profile", { installId: install.id, name: newName });
+        if (created) setNewName("");
+        refresh();
+    };
+
+    // Profiles snapshot the install settings, saving again takes the current ones over
+    const overwrite = async (profile: LaunchProfile) => {
+        await invoke("update_launch_profile", { profile: { ...profile, launch_args: install.launch_args, env_vars: install.env_list?.length ? "" : install.env_vars, env_list: install.env_list ?? [], use_mangohud: install.use_mangohud, use_gamemode: install.use_gamemode, use_xxmi: install.use_xxmi, use_fps_unlock: install.use_fps_unlock, fps_value: `${install.fps_value}`, graphics_api: install.graphics_api } });
+        refresh();
+    };
+
+    const remove = async (profile: LaunchProfile) => {
+        await invoke("delete_launch_profile", { profileId: profile.id });
+        refresh();
+    };
+
+    return (
+        <SettingsCard>
+            <div className="flex flex-col gap-3">
+                <div className="flex flex-col gap-1">
+                    <label className="text-base font-medium text-white">{translate("game_settings.launch.profiles.label")}</label>
+                    <span className="text-sm text-zinc-400">{translate("game_settings.launch.profiles.description")}</span>
+                </div>
+                {profiles.map((profile) => (
+                    <div key={profile.id} className="flex items-center gap-2 bg-black/20 border border-white/5 rounded-lg p-3">
+                        <span className="flex-1 text-white text-sm font-medium truncate">{profile.name}</span>
+                        <button onClick={() => { invoke("game_launch", { id: install.id, profileId: profile.id }); onLaunch(); }} className="p-2 text-zinc-400 hover:text-emerald-400 transition-colors" title={translate("game_settings.launch.profiles.launch")}>
+                            <Play className="w-4 h-4" />
+                        </button>
+                        <button onClick={() => overwrite(profile)} className="p-2 text-zinc-400 hover:text-purple-400 transition-colors" title={translate("game_settings.launch.profiles.save_current")}>
+                            <Save className="w-4 h-4" />
+                        </button>
+                        <button onClick={() => invoke("add_shortcut", { installId: install.id, shortcutType: "desktop", profileId: profile.id })} className="p-2 text-zinc-400 hover:text-purple-400 transition-colors" title={translate("game_settings.launch.profiles.shortcut")}>
+                            <Monitor className="w-4 h-4" />
+                        </button>
+                        <button onClick={() => remove(profile)} className="p-2 text-zinc-400 hover:text-red-400 transition-colors" title={translate("game_settings.launch.profiles.remove")}>
+                            <Trash2 className="w-4 h-4" />
+                        </button>
+                    </div>
+                ))}
+                <div className="flex items-center gap-2">
+                    <input
+                        value={newName}
+                        onChange={(e) => setNewName(e.target.value)}
+                        onKeyDown={(e) => { if (e.key === "Enter") create(); }}
+                        placeholder={translate("game_settings.launch.profiles.placeholder")}
+                        className="flex-1 bg-black/40 border border-white/10 rounded-lg px-3 py-2 text-white placeholder-zinc-600 focus:outline-none focus:border-purple-500/50 text-sm"
+                    />
+                    <button onClick={create} className="flex items-center gap-2 text-purple-400 hover:text-purple-300 text-sm font-medium transition-colors">
+                        <Plus className="w-4 h-4" /> {translate("game_settings.launch.profiles.add")}
+                    </button>
+                </div>
+            </div>
+        </SettingsCard>
+    );
+};
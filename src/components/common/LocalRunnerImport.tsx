@@ -0,0 +1,58 @@
+import React from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { open } from "@tauri-apps/plugin-dialog";
+import { FolderOpen, FolderPlus } from "lucide-react";
+import { SettingsCard } from "./SettingsComponents.tsx";
+import { translate } from "../../utils/i18n";
+
+// The backend detects whether the folder is a Proton or Wine build, errors come back as dialogs
+export const LocalRunnerImport = ({ onImported }: { onImported: () => void }) => {
+    const [path, setPath] = React.useState("");
+    const [name, setName] = React.useState("");
+
+    const pick = async () => {
+        const dir = await open({ multiple: false, directory: true, title: translate("runners_page.local.pick") });
+        if (typeof dir !== "string") return;
+        setPath(dir);
+        if (name === "") setName(dir.split("/").filter((p) => p !== "").pop() ?? "");
+    };
+
+    const importRunner = async () => {
+        if (await invoke<boolean | null>("add_local_runner", { path, name: name.trim() || null })) {
+            setPath("");
+            setName("");
+            onImported();
+        }
+    };
+
+    return (
+        <SettingsCard>
+            <div className="flex flex-col gap-3">
+                <div className="flex flex-col gap-1">
+                    <label className="text-base font-medium text-white">{translate("runners_page.local.label")}</label>
+                    <span className="text-sm text-zinc-400">{translate("runners_page.local.description")}</span>
+                </div>
+                <div className="flex items-center gap-2">
+                    <input
+                        value={path}
+                        readOnly
+                        placeholder={translate("runners_page.local.path_placeholder")}
+                        className="flex-1 bg-black/40 border border-white/10 rounded-lg px-3 py-2 text-white placeholder-zinc-600 focus:outline-none font-mono text-sm"
+                    />
+                    <button onClick={pick} className="p-2 text-zinc-400 hover:text-purple-400 transition-colors" title={translate("runners_page.local.pick")}>
+                        <FolderOpen className="w-4 h-4" />
+                    </button>
+                </div>
+                <input
+                    value={name}
+                    onChange={(e) => setName(e.target.value)}
+                    placeholder={translate("runners_page.local.name_placeholder")}
+                    className="bg-black/40 border border-white/10 rounded-lg px-3 py-2 text-white placeholder-zinc-600 focus:outline-none focus:border-purple-500/50 font-mono text-sm"
+                />
+                <button onClick={importRunner} disabled={path === ""} className="flex items-center gap-2 self-start text-purple-400 hover:text-purple-300 disabled:opacity-50 text-sm font-medium transition-colors">
+                    <FolderPlus className="w-4 h-4" /> {translate("runners_page.local.import")}
+                </button>
+            </div>
+        </SettingsCard>
+    );
+};
@@ -0,0 +1,97 @@
+import React from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Archive, Loader2, RotateCcw, Trash2 } from "lucide-react";
+import { SettingsCard } from "./SettingsComponents.tsx";
+import { formatBytes } from "../../utils/progress";
+import { translate } from "../../utils/i18n";
+
+export interface PrefixSnapshot {
+    id: string;
+    install_id: string;
+    runner_version: string;
+    created_at: number;
+    reason: string;
+    size: number;
+}
+
+// Restoring asks first, the current prefix is gone once the snapshot is unpacked
+export const PrefixSnapshotsEditor = ({ install, onRestored }: { install: any, onRestored: () => void }) => {
+    const [snapshots, setSnapshots] = React.useState<PrefixSnapshot[]>([]);
+    const [busy, setBusy] = React.useState<"" | "taking" | "restoring">("");
+    const [confirming, setConfirming] = React.useState<string | null>(null);
+    const [restoreRunner, setRestoreRunner] = React.useState(true);
+
+    const refresh = React.useCallback(() => {
+        invoke<PrefixSnapshot[] | null>("list_install_prefix_snapshots", { id: install.id }).then((s) => setSnapshots(s ?? []));
+    }, [install.id]);
+
+    React.useEffect(() => { refresh(); }, [refresh]);
+
+    const take = async () => {
+        setBusy("taking");
+        await invoke("create_install_prefix_snapshot", { id: install.id });
+        setBusy("");
+        refresh();
+    };
+
+    const restore = async (snapshot: PrefixSnapshot) => {
+        setBusy("restoring");
+        setConfirming(null);
+        await invoke("restore_install_prefix_snapshot", { id: install.id, snapshotId: snapshot.id, restoreRunner: restoreRunner && snapshot.runner_version !== install.runner_version });
+        setBusy("");
+        refresh();
+        onRestored();
+    };
+
+    const remove = async (snapshot: PrefixSnapshot) => {
+        await invoke("delete_install_prefix_snapshot", { id: install.id, snapshotId: snapshot.id });
+        refresh();
+    };
+
+    return (
+        <SettingsCard>
+            <div className="flex flex-col gap-3">
+                <div className="flex flex-col gap-1">
+                    <label className="text-base font-medium text-white">{translate("game_settings.linux.prefix_snapshots.label")}</label>
+                    <span className="text-sm text-zinc-400">{translate("game_settings.linux.prefix_snapshots.description")}</span>
+                </div>
+                {snapshots.length === 0 && <span className="text-sm text-zinc-500">{translate("game_settings.linux.prefix_snapshots.empty")}</span>}
+                {snapshots.map((snapshot) => (
+                    <div key={snapshot.id} className="flex flex-col gap-2 bg-black/20 border border-white/5 rounded-lg p-3">
+                        <div className="flex items-center gap-2">
+                            <div className="flex-1 flex flex-col min-w-0">
+                                <span className="text-white text-sm font-medium truncate">{new Date(snapshot.created_at * 1000).toLocaleString()} · {translate(`game_settings.linux.prefix_snapshots.reason.${snapshot.reason}`)}</span>
+                                <span className="text-xs text-zinc-500 font-mono truncate">{translate("game_settings.linux.prefix_snapshots.runner", { runner_version: snapshot.runner_version })} · {formatBytes(snapshot.size)}</span>
+                            </div>
+                            <button onClick={() => { setRestoreRunner(true); setConfirming(confirming === snapshot.id ? null : snapshot.id); }} disabled={busy !== ""} className="p-2 text-zinc-400 hover:text-purple-400 disabled:opacity-50 transition-colors" title={translate("game_settings.linux.prefix_snapshots.restore")}>
+                                <RotateCcw className="w-4 h-4" />
+                            </button>
+                            <button onClick={() => remove(snapshot)} disabled={busy !== ""} className="p-2 text-zinc-400 hover:text-red-400 disabled:opacity-50 transition-colors" title={translate("game_settings.linux.prefix_snapshots.delete")}>
+                                <Trash2 className="w-4 h-4" />
+                            </button>
+                        </div>
+                        {confirming === snapshot.id && (
+                            <div className="flex flex-col gap-2 border-t border-white/5 pt-2">
+                                <span className="text-sm text-amber-300">{translate("game_settings.linux.prefix_snapshots.confirm")}</span>
+                                {snapshot.runner_version !== install.runner_version && (
+                                    <label className="flex items-center gap-2 text-sm text-zinc-300">
+                                        <input type="checkbox" checked={restoreRunner} onChange={(e) => setRestoreRunner(e.target.checked)} className="accent-purple-500" />
+                                        {translate("game_settings.linux.prefix_snapshots.restore_runner", { runner_version: snapshot.runner_version })}
+                                    </label>
+                                )}
+                                <div className="flex items-center gap-4">
+                                    <button onClick={() => restore(snapshot)} className="text-purple-400 hover:text-purple-300 text-sm font-medium transition-colors">{translate("game_settings.linux.prefix_snapshots.confirm_restore")}</button>
+                                    <button onClick={() => setConfirming(null)} className="text-zinc-400 hover:text-zinc-300 text-sm font-medium transition-colors">{translate("game_settings.linux.prefix_snapshots.cancel")}</button>
+                                </div>
+                            </div>
+                        )}
+                    </div>
+                ))}
+                <button onClick={take} disabled={busy !== ""} className="flex items-center gap-2 self-start text-purple-400 hover:text-purple-300 disabled:opacity-50 text-sm font-medium transition-colors">
+                    {busy === "" ? <Archive className="w-4 h-4" /> : <Loader2 className="w-4 h-4 animate-spin" />}
+                    {busy === "" ? translate("game_settings.linux.prefix_snapshots.take") : translate(`game_settings.linux.prefix_snapshots.${busy}`)}
+                </button>
+            </div>
+        </SettingsCard>
+    );
+};
@@ -0,0 +1,105 @@
+import React from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { Eye, Trash2 } from "lucide-react";
+import { SettingsCard } from "./SettingsComponents.tsx";
+import { formatBytes } from "../../utils/progress";
+import { translate } from "../../utils/i18n";
+
+interface RunnerUsage {
+    version: string;
+    path: string;
+    family: string;
+    size: number;
+    is_installed: boolean;
+    installs: { id: string, name: string }[];
+    last_used: number;
+    referenced: boolean;
+}
+
+const formatLastUsed = (secs: number) => (secs > 0 ? new Date(secs * 1000).toLocaleString() : translate("runners_page.usage.never_used"));
+
+export const RunnerUsagePanel = ({ onChanged }: { onChanged: () => void }) => {
+    const [report, setReport] = React.useState<RunnerUsage[]>([]);
+    const [keepPerFamily, setKeepPerFamily] = React.useState(0);
+    const [candidates, setCandidates] = React.useState<RunnerUsage[] | null>(null);
+    const [result, setResult] = React.useState<{ removed: string[], skipped: string[], freed: number } | null>(null);
+
+    const refresh = React.useCallback(() => {
+        invoke<RunnerUsage[] | null>("get_runner_usage_report").then((r) => setReport(r ?? []));
+    }, []);
+
+    React.useEffect(() => { refresh(); }, [refresh]);
+
+    const preview = async () => {
+        setCandidates((await invoke<RunnerUsage[] | null>("preview_runner_cleanup", { keepPerFamily })) ?? []);
+        setResult(null);
+    };
+
+    // Only what the user saw in the preview is sent, the backend drops anything that got referenced in the meantime
+    const cleanup = async () => {
+        const r = await invoke<{ removed: string[], skipped: string[], freed: number } | null>("cleanup_runners", { versions: (candidates ?? []).map((c) => c.version), keepPerFamily });
+        setResult(r);
+        setCandidates(null);
+        refresh();
+        onChanged();
+    };
+
+    const totalSize = report.reduce((sum, r) => sum + r.size, 0);
+
+    return (
+        <div className="flex flex-col gap-4">
+            <SettingsCard>
+                <div className="flex flex-col gap-2">
+                    <span className="text-sm text-zinc-400">{translate("runners_page.usage.total", { count: `${report.length}`, size: formatBytes(totalSize) })}</span>
+                    {report.map((r) => (
+                        <div key={r.version} className="flex items-center justify-between gap-4 bg-black/20 border border-white/5 rounded-lg px-3 py-2">
+                            <div className="flex flex-col min-w-0">
+                                <span className="text-white text-sm font-medium truncate">{r.version}</span>
+                                <span className="text-xs text-zinc-500 truncate">
+                                    {r.installs.length > 0 ? r.installs.map((i) => i.name).join(", ") : translate(r.referenced ? "runners_page.usage.in_use" : "runners_page.usage.unused")}
+                                </span>
+                            </div>
+                            <div className="flex flex-col items-end shrink-0 text-xs text-zinc-400">
+                                <span className="font-mono">{r.is_installed ? formatBytes(r.size) : translate("runners_page.usage.not_installed")}</span>
+                                <span>{formatLastUsed(r.last_used)}</span>
+                            </div>
+                        </div>
+                    ))}
+                </div>
+            </SettingsCard>
+            <SettingsCard>
+                <div className="flex flex-col gap-3">
+                    <div className="flex flex-col gap-1">
+                        <label className="text-base font-medium text-white">{translate("runners_page.usage.cleanup.label")}</label>
+                        <span className="text-sm text-zinc-400">{translate("runners_page.usage.cleanup.description")}</span>
+                    </div>
+                    <label className="flex items-center gap-2 text-sm text-zinc-300">
+                        {translate("runners_page.usage.cleanup.keep_per_family")}
+                        <input
+                            type="number"
+                            min={0}
+                            value={keepPerFamily}
+                            onChange={(e) => { setKeepPerFamily(Math.max(0, parseInt(e.target.value) || 0)); setCandidates(null); }}
+                            className="w-20 bg-black/40 border border-white/10 rounded-lg px-2 py-1 text-white text-sm focus:outline-none focus:border-purple-500/50"
+                        />
+                    </label>
+                    {candidates !== null && candidates.length === 0 && <span className="text-sm text-zinc-400">{translate("runners_page.usage.cleanup.nothing")}</span>}
+                    {candidates?.map((c) => (
+                        <span key={c.version} className="text-xs text-zinc-300 font-mono">{c.version} ({formatBytes(c.size)})</span>
+                    ))}
+                    {result && <span className="text-sm text-emerald-400">{translate("runners_page.usage.cleanup.done", { count: `${result.removed.length}`, size: formatBytes(result.freed) })}</span>}
+                    <div className="flex items-center gap-4">
+                        <button onClick={preview} className="flex items-center gap-2 text-purple-400 hover:text-purple-300 text-sm font-medium transition-colors">
+                            <Eye className="w-4 h-4" /> {translate("runners_page.usage.cleanup.preview")}
+                        </button>
+                        {candidates !== null && candidates.length > 0 && (
+                            <button onClick={cleanup} className="flex items-center gap-2 text-red-400 hover:text-red-300 text-sm font-medium transition-colors">
+                                <Trash2 className="w-4 h-4" /> {translate("runners_page.usage.cleanup.confirm", { count: `${candidates.length}`, size: formatBytes(candidates.reduce((s, c) => s + c.size, 0)) })}
+                            </button>
+                        )}
+                    </div>
+                </div>
+            </SettingsCard>
+        </div>
+    );
+};
@@ -55,7 +55,7 @@ const formatTime = (seconds: number): string => {
     return `${hours}h ${minutes % 60}m`;
 };
 
-const INSTALL_ETA_PHASES: DownloadPhase[] = ['installing', 'extracting', 'validating', 'moving'];
+const INSTALL_ETA_PHASES: DownloadPhase[] = ['installing', 'patching', 'extracting', 'validating', 'moving'];
 const MAX_ETA_SECONDS = 60 * 60 * 24 * 7; // Hide unrealistic ETAs (>7 days)
 
 /* Calculate ETA using average of recent speeds */
@@ -98,7 +98,9 @@ function formatKind(kind: QueueJobView['kind']): string {
         case 'game_update': return translate('downloads_page.kind.update');
         case 'game_preload': return translate('downloads_page.kind.preload');
         case 'game_repair': return translate('downloads_page.kind.repair');
+        case 'game_verify': return translate('downloads_page.kind.verify');
         case 'runner_download': return translate('downloads_page.kind.runner');
+        case 'runner_verify': return translate('downloads_page.kind.runner_verify');
         case 'steamrt_download': return 'SteamRT';
         case 'steamrt4_download': return 'SteamRT';
         case 'xxmi_download': return 'XXMI';
@@ -131,6 +133,7 @@ function formatInstallPhase(phase: DownloadPhase | undefined): string {
         case 'validating': return translate('downloads_page.validating');
         case 'moving': return translate('downloads_page.moving');
         case 'extracting': return translate('downloads_page.extracting');
+        case 'patching': return translate('downloads_page.patching');
         default: return translate('downloads_page.installing');
     }
 }
@@ -212,7 +215,7 @@ export default function DownloadsPage({
     const isPaused = isQueuePaused || currentJob?.status === 'paused' || (pausedJob !== null && runningJobs.length === 0);
 
     // Derive if job is pausable
-    const canPause = currentJob && !["runner_download", "steamrt_download", "steamrt4_download", "xxmi_download", "extras_download"].includes(currentJob.kind);
+    const canPause = currentJob && !["runner_download", "runner_verify", "steamrt_download", "steamrt4_download", "xxmi_download", "extras_download"].includes(currentJob.kind);
 
     // Calculate progress values
     const progressBytes = currentProgress?.progress ?? 0;
@@ -232,7 +235,7 @@ export default function DownloadsPage({
 
     // Current phase/status
     const currentPhase = currentProgress?.phase;
-    const etaText = calculateETA(totalBytes, progressBytes, installTotalBytes, installProgressBytes, speedHistory, currentPhase, currentJob?.kind === 'game_repair');
+    const etaText = calculateETA(totalBytes, progressBytes, installTotalBytes, installProgressBytes, speedHistory, currentPhase, currentJob?.kind === 'game_repair' || currentJob?.kind === 'game_verify');
 
 
     // Reset graph history and peak speed when active job changes
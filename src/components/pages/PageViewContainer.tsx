@@ -54,6 +54,7 @@ interface PageViewContainerProps {
     runners: RunnerManifest[];
     installedRunners: InstalledRunner[];
     fetchInstalledRunners: () => void;
+    fetchCompatibilityVersions: () => void;
     pushInstalls: () => void;
 
     // Network recovery
@@ -76,6 +77,7 @@ export default function PageViewContainer({
     runners,
     installedRunners,
     fetchInstalledRunners,
+    fetchCompatibilityVersions,
     pushInstalls,
     imageVersion = 0,
 }: PageViewContainerProps) {
@@ -145,6 +147,7 @@ export default function PageViewContainer({
                         runners={runners}
                         installedRunners={installedRunners}
                         fetchInstalledRunners={fetchInstalledRunners}
+                        fetchCompatibilityVersions={fetchCompatibilityVersions}
                         pushInstalls={pushInstalls}
                         downloadQueueState={downloadQueueState}
                         downloadProgressByJobId={downloadProgressByJobId}
@@ -1,10 +1,12 @@
 import { useState, useMemo, useEffect } from "react";
 import { translate } from "../../utils/i18n";
 import { invoke } from "@tauri-apps/api/core";
-import { ArrowLeft, AtomIcon, DownloadCloud, FolderOpen, Trash2, Check } from "lucide-react";
+import { ArrowLeft, AtomIcon, DownloadCloud, FolderOpen, HardDrive, Trash2, Check, ShieldCheck, FolderPlus } from "lucide-react";
 import { PAGES } from "./PAGES";
 import { SettingsSidebar, SettingsTab } from "../sidebar/SettingsSidebar.tsx";
 import { SettingsSection } from "../common/SettingsComponents.tsx";
+import { RunnerUsagePanel } from "../common/RunnerUsagePanel.tsx";
+import { LocalRunnerImport } from "../common/LocalRunnerImport.tsx";
 import type { DownloadJobProgress, DownloadQueueStatePayload } from "../../types/downloadQueue";
 
 interface RunnerVersion {
@@ -27,6 +29,7 @@ interface RunnersPageProps {
     runners: RunnerManifest[];
     installedRunners: InstalledRunner[];
     fetchInstalledRunners: () => void;
+    fetchCompatibilityVersions: () => void;
     pushInstalls: () => void;
     downloadQueueState: DownloadQueueStatePayload | null;
     downloadProgressByJobId: Record<string, DownloadJobProgress>;
@@ -40,8 +43,10 @@ function RunnerItem({
     progress,
     onInstall,
     onRemove,
+    onVerify,
     onOpenFolder,
     isLastInstalled = false,
+    isLocal = false,
 }: {
     version: string;
     isInstalled: boolean;
@@ -50,8 +55,10 @@ function RunnerItem({
     progress?: DownloadJobProgress;
     onInstall: () => void;
     onRemove: () => void;
+    onVerify: () => void;
     onOpenFolder: () => void;
     isLastInstalled?: boolean;
+    isLocal?: boolean;
 }) {
     const [isLoading, setIsLoading] = useState(false);
 
@@ -83,7 +90,7 @@ function RunnerItem({
                 <span className="text-white/90 text-sm font-medium">{version}</span>
             </div>
             <div className="flex items-center gap-2">
-                {isInstalled ? (
+                {isInstalled && !downloading ? (
                     <>
                         <button
                             onClick={onOpenFolder}
@@ -92,6 +99,13 @@ function RunnerItem({
                         >
                             <FolderOpen className="w-4 h-4" />
                         </button>
+                        <button
+                            onClick={onVerify}
+                            className="p-2 rounded-lg text-white/50 hover:text-purple-400 hover:bg-purple-500/10 transition-all duration-200"
+                            title={translate('runners_page.verify_tooltip')}
+                        >
+                            <ShieldCheck className="w-4 h-4" />
+                        </button>
                         <button
                             onClick={handleRemove}
                             disabled={isLoading || isLastInstalled}
@@ -120,6 +134,19 @@ function RunnerItem({
                             {isQueued ? translate('runners_page.queued_label') : progressPercent > 0 ? `${progressPercent}%` : translate('runners_page.installing_label')}
                         </span>
                     </div>
+                ) : isLocal ? (
+                    // Imported runners whose folder went away can only be forgotten
+                    <>
+                        <span className="text-xs text-zinc-500">{translate('runners_page.local.missing_label')}</span>
+                        <button
+                            onClick={handleRemove}
+                            disabled={isLoading}
+                            className="p-2 rounded-lg text-white/50 hover:text-red-400 hover:bg-red-500/10 transition-all duration-200 disabled:opacity-50"
+                            title={translate('runners_page.remove_tooltip')}
+                        >
+                            <Trash2 className="w-4 h-4" />
+                        </button>
+                    </>
                 ) : (
                     <button
                         onClick={handleInstall}
@@ -147,6 +174,7 @@ export default function RunnersPage({
     runners,
     installedRunners,
     fetchInstalledRunners,
+    fetchCompatibilityVersions,
     pushInstalls,
     downloadQueueState,
     downloadProgressByJobId,
@@ -155,14 +183,14 @@ export default function RunnersPage({
     const queuedJobs = downloadQueueState?.queued || [];
     // Generate tabs from runner manifests
     const tabs: SettingsTab[] = useMemo(() => {
-        return runners.map((runner, index) => ({
+        return [...runners.map((runner, index) => ({
             id: runner.display_name.toLowerCase().replace(/\s+/g, '-'),
             label: runner.display_name,
             icon: AtomIcon, // We could use different icons if we had map, but Atom is good for generic runner
             color: ["purple", "blue", "green", "orange", "pink", "yellow"][index % 6], // Cycle colors
             // Store original name to find data easily
             _originalName: runner.display_name
-        }));
+        })), { id: "local", label: translate('runners_page.local.title'), icon: FolderPlus, color: "blue" }, { id: "usage", label: translate('runners_page.usage.title'), icon: HardDrive, color: "red" }];
     }, [runners]);
 
     const [activeTab, setActiveTab] = useState<string>("");
@@ -234,6 +262,16 @@ export default function RunnersPage({
                             key={activeTab}
                             className={`flex-1 overflow-y-auto p-8 scrollbar-thin scrollbar-thumb-zinc-700 scrollbar-track-transparent ${animClass}`}
                         >
+                            {activeTab === "local" && (
+                                <SettingsSection title={translate('runners_page.local.title')}>
+                                    <LocalRunnerImport onImported={() => { fetchInstalledRunners(); fetchCompatibilityVersions(); }} />
+                                </SettingsSection>
+                            )}
+                            {activeTab === "usage" && (
+                                <SettingsSection title={translate('runners_page.usage.title')}>
+                                    <RunnerUsagePanel onChanged={() => { fetchInstalledRunners(); fetchCompatibilityVersions(); pushInstalls(); }} />
+                                </SettingsSection>
+                            )}
                             {activeRunner && (
                                 <SettingsSection title={translate('runners_page.versions_section', { runner_name: activeRunner.display_name })}>
                                     <div className="flex flex-col gap-2">
@@ -241,9 +279,9 @@ export default function RunnersPage({
                                             const isInstalled = installedRunners.some(
                                                 r => r.version === v.version && r.is_installed
                                             );
-                                            const runningJob = runningJobs.find(j => j.kind === 'runner_download' && j.installId === v.version);
+                                            const runningJob = runningJobs.find(j => (j.kind === 'runner_download' || j.kind === 'runner_verify') && j.installId === v.version);
                                             const isDownloading = !!runningJob;
-                                            const isQueued = queuedJobs.some(j => j.kind === 'runner_download' && j.installId === v.version);
+                                            const isQueued = queuedJobs.some(j => (j.kind === 'runner_download' || j.kind === 'runner_verify') && j.installId === v.version);
                                             const progress = runningJob ? downloadProgressByJobId[runningJob.id] || downloadProgressByJobId[v.version] : undefined;
                                             return (
                                                 <RunnerItem
@@ -254,6 +292,7 @@ export default function RunnersPage({
                                                     isQueued={isQueued}
                                                     progress={progress}
                                                     isLastInstalled={isInstalled && totalInstalled <= 1}
+                                                    isLocal={!v.url}
                                                     onInstall={async () => {
                                                         await invoke("add_installed_runner", {
                                                             runnerUrl: v.url,
@@ -266,8 +305,14 @@ export default function RunnersPage({
                                                             runnerVersion: v.version
                                                         });
                                                         fetchInstalledRunners();
+                                                        if (!v.url) fetchCompatibilityVersions();
                                                         pushInstalls();
                                                     }}
+                                                    onVerify={() => {
+                                                        invoke("verify_installed_runner", {
+                                                            runnerVersion: v.version
+                                                        });
+                                                    }}
                                                     onOpenFolder={() => {
                                                         invoke("open_folder", {
                                                             runnerVersion: v.version,
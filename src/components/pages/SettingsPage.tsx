@@ -1,16 +1,20 @@
 import {useEffect, useState} from "react";
 import { translate } from "../../utils/i18n";
 import {invoke} from "@tauri-apps/api/core";
-import {ArrowLeft, Download, Folder, HeartIcon, Info, LogsIcon, Monitor, Settings, WrenchIcon} from "lucide-react";
+import {ArrowLeft, Download, Folder, HeartIcon, Info, LogsIcon, Monitor, Play, Settings, Upload, WrenchIcon} from "lucide-react";
+import {open} from "@tauri-apps/plugin-dialog";
 import {SettingsSidebar, SettingsTab} from "../sidebar/SettingsSidebar.tsx";
 import {
     ModernInput,
     ModernPathInput,
     ModernSelect,
     ModernToggle,
+    SettingsCard,
     SettingsSection
 } from "../common/SettingsComponents.tsx";
 import {PAGES} from "./PAGES";
+import {LaunchDefaultsApply} from "../common/LaunchDefaultsApply.tsx";
+import {parseLegacy} from "../common/EnvVarsEditor.tsx";
 import {getVersion} from "@tauri-apps/api/app";
 
 interface SettingsPageProps {
@@ -20,18 +24,30 @@ interface SettingsPageProps {
     availableLocales: { value: string; label: string }[];
 }
 
+// Job kinds that get their own speed limit field, the rest always follow the global limit
+const SPEED_LIMIT_KINDS = ["game_download", "game_update", "game_preload", "game_repair", "runner_download"];
+
 export default function SettingsPage({ settings, fetchSettings, setCurrentPage, availableLocales }: SettingsPageProps) {
     const [activeTab, setActiveTab] = useState("general");
 
     const tabs: SettingsTab[] = [
         { id: "general", label: translate("launcher_settings.tabs.general"), icon: Settings, color: "blue" },
         { id: "downloads", label: translate("launcher_settings.tabs.downloads"), icon: Download, color: "green" },
+        { id: "launch_defaults", label: translate("launcher_settings.tabs.launch_defaults"), icon: Play, color: "purple" },
         { id: "files", label: translate("launcher_settings.tabs.files"), icon: Folder, color: "yellow" },
         ...(window.navigator.platform.includes("Linux") ? [/*{ id: "integrations", label: "Integrations & Tools", icon: Box, color: "purple" }*/] : []),
         ...(window.navigator.platform.includes("Linux") ? [{ id: "linux", label: translate("launcher_settings.tabs.linux"), icon: Monitor, color: "orange" }] : []),
         { id: "about", label: translate("launcher_settings.tabs.about"), icon: Info, color: "pink" },
     ];
 
+    // Bundle first, then optionally the already copied game folder, cancelling the second picker means the game gets downloaded
+    const importInstallBundle = async () => {
+        const bundle = await open({ multiple: false, directory: false, filters: [{ name: "Install bundle", extensions: ["json"] }] });
+        if (!bundle || Array.isArray(bundle)) return;
+        const gameDirectory = await open({ multiple: false, directory: true, title: translate("launcher_settings.files.import_install.label") });
+        await invoke("import_install_bundle", { path: bundle, gameDirectory: typeof gameDirectory === "string" ? gameDirectory : null });
+    };
+
     // Helper to update settings
     const updateSetting = async (key: string, value: any) => {
         try {
@@ -40,6 +56,10 @@ export default function SettingsPage({ settings, fetchSettings, setCurrentPage,
             } else if (typeof value === "string" || typeof value === "number") {
                 if (key === "download_speed_limit") {
                     await invoke("update_settings_download_speed_limit_cmd", { speedLimit: Number(value) });
+                } else if (key === "download_speed_limit_while_playing") {
+                    await invoke("update_settings_download_speed_limit_while_playing_cmd", { speedLimit: Number(value) });
+                } else if (key === "max_concurrent_game_jobs" || key === "max_concurrent_misc_jobs") {
+                    await invoke("update_settings_queue_max_concurrent_cmd", { lane: key === "max_concurrent_game_jobs" ? "game" : "misc", maxConcurrent: Number(value) });
                 } else if (key === "app_lang") {
                     await invoke("update_settings_app_lang_cmd", { lang: value });
                 } else if (key === "launcher_action") {
@@ -60,6 +80,43 @@ export default function SettingsPage({ settings, fetchSettings, setCurrentPage,
         }
     };
 
+    // Schedule fields are saved together so the backend can validate the whole window
+    const updateSchedule = async (patch: { enabled?: boolean, start?: string, end?: string, days?: string }) => {
+        try {
+            await invoke("update_settings_download_schedule_cmd", {
+                enabled: patch.enabled ?? Boolean(settings.download_schedule_enabled),
+                start: patch.start ?? settings.download_schedule_start ?? "01:00",
+                end: patch.end ?? settings.download_schedule_end ?? "07:00",
+                days: patch.days ?? settings.download_schedule_days ?? "mon,tue,wed,thu,fri,sat,sun",
+            });
+            fetchSettings();
+        } catch (e) {
+            console.error("Failed to update download schedule:", e);
+        }
+    };
+
+    // Per job kind limits are stored as "game_update=5120,runner_download=0", a missing kind follows the global limit
+    const kindSpeedLimits: Record<string, string> = Object.fromEntries(String(settings.download_speed_limits ?? "").split(",").filter((e: string) => e.includes("=")).map((e: string) => e.split("=").map((p) => p.trim())));
+    const updateKindSpeedLimit = async (kind: string, value: string) => {
+        try {
+            await invoke("update_settings_download_speed_limit_kind_cmd", { kind, speedLimit: value.trim() === "" ? -1 : Number(value) });
+            fetchSettings();
+        } catch (e) {
+            console.error(`Failed to update speed limit for ${kind}:`, e);
+        }
+    };
+
+    // Launch defaults are a single JSON value, every field edit saves the whole object
+    const launchDefaults = settings.launch_defaults ?? {};
+    const updateLaunchDefaults = async (patch: Record<string, any>) => {
+        try {
+            await invoke("update_settings_launch_defaults_cmd", { defaults: { ...launchDefaults, ...patch } });
+            fetchSettings();
+        } catch (e) {
+            console.error("Failed to update launch defaults:", e);
+        }
+    };
+
     // Track animation class state
     const [animClass, setAnimClass] = useState("animate-fadeIn");
 
@@ -146,6 +203,57 @@ export default function SettingsPage({ settings, fetchSettings, setCurrentPage,
                         </SettingsSection>
                     )}
 
+                    {activeTab === "launch_defaults" && (
+                        <SettingsSection title={translate("launcher_settings.launch_defaults.title")}>
+                            <ModernToggle
+                                label={translate("launcher_settings.launch_defaults.use_gamemode.label")}
+                                description={translate("launcher_settings.launch_defaults.use_gamemode.description")}
+                                checked={Boolean(launchDefaults.use_gamemode)}
+                                onChange={(val) => updateLaunchDefaults({ use_gamemode: val })}
+                            />
+                            <ModernToggle
+                                label={translate("launcher_settings.launch_defaults.use_mangohud.label")}
+                                description={translate("launcher_settings.launch_defaults.use_mangohud.description")}
+                                checked={Boolean(launchDefaults.use_mangohud)}
+                                onChange={(val) => updateLaunchDefaults({ use_mangohud: val })}
+                            />
+                            <ModernToggle
+                                label={translate("launcher_settings.launch_defaults.show_discord_rpc.label")}
+                                description={translate("launcher_settings.launch_defaults.show_discord_rpc.description")}
+                                checked={Boolean(launchDefaults.show_discord_rpc)}
+                                onChange={(val) => updateLaunchDefaults({ show_discord_rpc: val })}
+                            />
+                            <ModernToggle
+                                label={translate("launcher_settings.launch_defaults.disable_system_idle.label")}
+                                description={translate("launcher_settings.launch_defaults.disable_system_idle.description")}
+                                checked={Boolean(launchDefaults.disable_system_idle)}
+                                onChange={(val) => updateLaunchDefaults({ disable_system_idle: val })}
+                            />
+                            <ModernInput
+                                label={translate("launcher_settings.launch_defaults.launch_args.label")}
+                                description={translate("launcher_settings.launch_defaults.launch_args.description")}
+                                value={launchDefaults.launch_args ?? ""}
+                                onChange={(e) => updateLaunchDefaults({ launch_args: e.target.value })}
+                                placeholder="-dx11 -console"
+                            />
+                            <ModernInput
+                                label={translate("launcher_settings.launch_defaults.env_list.label")}
+                                description={translate("launcher_settings.launch_defaults.env_list.description")}
+                                value={(launchDefaults.env_list ?? []).map((v: any) => `${v.key}=${v.value}`).join(";")}
+                                onChange={(e) => updateLaunchDefaults({ env_list: parseLegacy(e.target.value) })}
+                                placeholder="DXVK_HUD=fps;PROTON_ENABLE_WAYLAND=1"
+                            />
+                            <ModernInput
+                                label={translate("launcher_settings.launch_defaults.graphics_api.label")}
+                                description={translate("launcher_settings.launch_defaults.graphics_api.description")}
+                                value={launchDefaults.graphics_api ?? ""}
+                                onChange={(e) => updateLaunchDefaults({ graphics_api: e.target.value })}
+                                placeholder="-dx12"
+                            />
+                            <LaunchDefaultsApply />
+                        </SettingsSection>
+                    )}
+
                     {activeTab === "downloads" && (
                         <SettingsSection title={translate("launcher_settings.downloads.title")}>
                             <ModernInput
@@ -156,6 +264,81 @@ export default function SettingsPage({ settings, fetchSettings, setCurrentPage,
                                 value={settings.download_speed_limit ?? 0}
                                 onChange={(e) => updateSetting("download_speed_limit", e.target.value)}
                             />
+                            {SPEED_LIMIT_KINDS.map((kind) => (
+                                <ModernInput
+                                    key={kind}
+                                    label={translate(`launcher_settings.downloads.speed_limit_kind.${kind}`)}
+                                    description={translate("launcher_settings.downloads.speed_limit_kind.description")}
+                                    type="number"
+                                    min={0}
+                                    placeholder={String(settings.download_speed_limit ?? 0)}
+                                    value={kindSpeedLimits[kind] ?? ""}
+                                    onChange={(e) => updateKindSpeedLimit(kind, e.target.value)}
+                                />
+                            ))}
+                            <ModernInput
+                                label={translate("launcher_settings.downloads.speed_limit_while_playing.label")}
+                                description={translate("launcher_settings.downloads.speed_limit_while_playing.description")}
+                                type="number"
+                                min={0}
+                                value={settings.download_speed_limit_while_playing ?? 0}
+                                onChange={(e) => updateSetting("download_speed_limit_while_playing", e.target.value)}
+                            />
+                            <ModernInput
+                                label={translate("launcher_settings.downloads.max_concurrent_game_jobs.label")}
+                                description={translate("launcher_settings.downloads.max_concurrent_game_jobs.description")}
+                                type="number"
+                                min={1}
+                                max={4}
+                                value={settings.max_concurrent_game_jobs ?? 1}
+                                onChange={(e) => updateSetting("max_concurrent_game_jobs", e.target.value)}
+                            />
+                            <ModernInput
+                                label={translate("launcher_settings.downloads.max_concurrent_misc_jobs.label")}
+                                description={translate("launcher_settings.downloads.max_concurrent_misc_jobs.description")}
+                                type="number"
+                                min={1}
+                                max={4}
+                                value={settings.max_concurrent_misc_jobs ?? 1}
+                                onChange={(e) => updateSetting("max_concurrent_misc_jobs", e.target.value)}
+                            />
+                            <ModernToggle
+                                label={translate("launcher_settings.downloads.schedule_enabled.label")}
+                                description={translate("launcher_settings.downloads.schedule_enabled.description")}
+                                checked={Boolean(settings.download_schedule_enabled)}
+                                onChange={(val) => updateSchedule({ enabled: val })}
+                            />
+                            {Boolean(settings.download_schedule_enabled) && (
+                                <>
+                                    <ModernInput
+                                        label={translate("launcher_settings.downloads.schedule_start.label")}
+                                        description={translate("launcher_settings.downloads.schedule_start.description")}
+                                        type="time"
+                                        value={settings.download_schedule_start ?? "01:00"}
+                                        onChange={(e) => updateSchedule({ start: e.target.value })}
+                                    />
+                                    <ModernInput
+                                        label={translate("launcher_settings.downloads.schedule_end.label")}
+                                        description={translate("launcher_settings.downloads.schedule_end.description")}
+                                        type="time"
+                                        value={settings.download_schedule_end ?? "07:00"}
+                                        onChange={(e) => updateSchedule({ end: e.target.value })}
+                                    />
+                                    <ModernInput
+                                        label={translate("launcher_settings.downloads.schedule_days.label")}
+                                        description={translate("launcher_settings.downloads.schedule_days.description")}
+                                        placeholder="sat,sun"
+                                        value={settings.download_schedule_days ?? ""}
+                                        onChange={(e) => updateSchedule({ days: e.target.value })}
+                                    />
+                                </>
+                            )}
+                            <ModernToggle
+                                label={translate("launcher_settings.downloads.queue_auto_resume.label")}
+                                description={translate("launcher_settings.downloads.queue_auto_resume.description")}
+                                checked={Boolean(settings.queue_auto_resume ?? true)}
+                                onChange={(val) => updateSetting("queue_auto_resume", val)}
+                            />
                         </SettingsSection>
                     )}
 
@@ -168,6 +351,18 @@ export default function SettingsPage({ settings, fetchSettings, setCurrentPage,
                                     value={`${settings.default_game_path}`}
                                     onChange={(val) => updateSetting("default_game_path", val)}
                                 />
+                                <SettingsCard className="flex flex-row items-center justify-between">
+                                    <div className="flex flex-col gap-1 pr-4">
+                                        <span className="text-base font-medium text-white">{translate("launcher_settings.files.import_install.label")}</span>
+                                        <span className="text-sm text-zinc-400">{translate("launcher_settings.files.import_install.description")}</span>
+                                    </div>
+                                    <button
+                                        onClick={importInstallBundle}
+                                        className="flex items-center gap-2 bg-zinc-800 hover:bg-zinc-700 text-white px-4 py-2.5 rounded-lg border border-white/5 transition-colors">
+                                        <Upload className="w-5 h-5" />
+                                        <span>{translate("launcher_settings.files.import_install.button")}</span>
+                                    </button>
+                                </SettingsCard>
                             </SettingsSection>
                             <SettingsSection title={translate("launcher_settings.files.tools_title")}>
                                 <ModernPathInput
@@ -3,6 +3,7 @@ import { POPUPS } from "./POPUPS.ts";
 import { PAGES } from "../pages/PAGES.ts";
 import { invoke } from "@tauri-apps/api/core";
 import { emit } from "@tauri-apps/api/event";
+import { save } from "@tauri-apps/plugin-dialog";
 import {
     Folder,
     Play,
@@ -19,11 +20,15 @@ import {
     X,
     FileCode2,
     LayoutDashboard,
-    Terminal, Settings2, Logs
+    Terminal, Settings2, Logs, ShieldCheck, Share, RotateCcw
 } from "lucide-react";
 import { SettingsLayout } from "../layout/SettingsLayout.tsx";
 import { SettingsSidebar, SettingsTab } from "../sidebar/SettingsSidebar.tsx";
 import { SettingsSection, ModernToggle, ModernInput, ModernPathInput, ModernSelect } from "../common/SettingsComponents.tsx";
+import { EnvVarsEditor } from "../common/EnvVarsEditor.tsx";
+import { LaunchHooksEditor } from "../common/LaunchHooksEditor.tsx";
+import { LaunchProfilesEditor } from "../common/LaunchProfilesEditor.tsx";
+import { PrefixSnapshotsEditor } from "../common/PrefixSnapshotsEditor.tsx";
 import { translate } from "../../utils/i18n";
 
 
@@ -117,8 +122,8 @@ export default function GameSettings({
             } else if (key === "env_vars") {
                 // update_install_env_vars uses { id, env_vars }
                 payload.envVars = value;
-            } else if (key === "pre_launch_cmd" || key === "launch_cmd") {
-                // update_install_pre_launch_cmd and update_install_launch_cmd use { id, cmd }
+            } else if (key === "launch_cmd") {
+                // update_install_launch_cmd uses { id, cmd }
                 payload.cmd = value;
             } else if (key === "runner_version" || key === "dxvk_version") {
                 // update_install_runner_version and update_install_dxvk_version use { id, version }
@@ -129,6 +134,9 @@ export default function GameSettings({
             } else if (key === "graphics_api") {
                 // update_install_graphics_api uses { id, api }
                 payload.api = value;
+            } else if (key === "wine_debug") {
+                // update_install_wine_debug uses { id, channels }
+                payload.channels = value;
             }
 
             await invoke(command, payload);
@@ -328,32 +336,7 @@ export default function GameSettings({
                                     onChange={(e) => handleUpdate("launch_args", e.target.value)}
                                     placeholder="-dx11 -console"
                                 />
-                                <ModernInput
-                                    label={translate("game_settings.launch.env_vars.label")}
-                                    description={translate("game_settings.launch.env_vars.description")}
-                                    value={installSettings.env_vars || ""}
-                                    onChange={(e) => handleUpdate("env_vars", e.target.value)}
-                                    placeholder='DXVK_HUD=fps,devinfo;PROTON_LOG=1;SOMETHING="/path/to/thing";'
-                                />
-                                <ModernInput
-                                    label={translate("game_settings.launch.pre_launch_cmd.label")}
-                                    placeholder={isLinux ? "/bin/bash -c echo hi" : "cmd.exe"}
-                                    description={translate("game_settings.launch.pre_launch_cmd.description")}
-                                    value={installSettings.pre_launch_command || ""}
-                                    onChange={(e) => handleUpdate("pre_launch_cmd", e.target.value)}
-                                    helpText={[
-                                        translate("game_settings.launch.help.available_vars"),
-                                        `- %steamrt% = ${translate("game_settings.launch.help.var.steamrt")}`,
-                                        `- %reaper% = ${translate("game_settings.launch.help.var.reaper")}`,
-                                        `- %appid% = ${translate("game_settings.launch.help.var.appid")}`,
-                                        `- %runner% = ${translate("game_settings.launch.help.var.runner")}`,
-                                        `- %game_exe% = ${translate("game_settings.launch.help.var.game_exe")}`,
-                                        `- %runner_dir% = ${translate("game_settings.launch.help.var.runner_dir")}`,
-                                        `- %prefix% = ${translate("game_settings.launch.help.var.prefix")}`,
-                                        `- %install_dir% = ${translate("game_settings.launch.help.var.install_dir")}`,
-                                        `- %steamrt_path% = ${translate("game_settings.launch.help.var.steamrt_path")}`,
-                                    ].join("\n")}
-                                />
+                                <EnvVarsEditor install={installSettings} onSaved={() => fetchInstallSettings(installSettings.id)} />
                                 <ModernInput
                                     label={translate("game_settings.launch.custom_launch_cmd.label")}
                                     placeholder={isLinux ? "%steamrt% --verb=waitforexitandrun -- %reaper% SteamLaunch AppId=%appid% -- %runner% waitforexitandrun %game_exe%" : "Start-Process -FilePath '%game_exe%' -WorkingDirectory '%install_dir%' -Verb RunAs"}
@@ -374,6 +357,28 @@ export default function GameSettings({
                                         `- %command% = ${translate("game_settings.launch.help.var.command")}`,
                                     ].join("\n")}
                                 />
+                                <LaunchHooksEditor
+                                    hooks={installSettings.launch_hooks || []}
+                                    onChange={async (hooks) => {
+                                        await invoke("update_install_launch_hooks", { id: installSettings.id, hooks });
+                                        fetchInstallSettings(installSettings.id);
+                                    }}
+                                    helpText={[
+                                        translate("game_settings.launch.help.available_vars"),
+                                        ...(isLinux ? [
+                                            `- %steamrt% = ${translate("game_settings.launch.help.var.steamrt")}`,
+                                            `- %reaper% = ${translate("game_settings.launch.help.var.reaper")}`,
+                                            `- %appid% = ${translate("game_settings.launch.help.var.appid")}`,
+                                            `- %runner% = ${translate("game_settings.launch.help.var.runner")}`,
+                                            `- %runner_dir% = ${translate("game_settings.launch.help.var.runner_dir")}`,
+                                            `- %prefix% = ${translate("game_settings.launch.help.var.prefix")}`,
+                                            `- %steamrt_path% = ${translate("game_settings.launch.help.var.steamrt_path")}`,
+                                        ] : []),
+                                        `- %game_exe% = ${translate("game_settings.launch.help.var.game_exe")}`,
+                                        `- %install_dir% = ${translate("game_settings.launch.help.var.install_dir")}`,
+                                    ].join("\n")}
+                                />
+                                <LaunchProfilesEditor install={installSettings} onLaunch={() => setOpenPopup(POPUPS.NONE)} />
                             </div>
                         </SettingsSection>
                     )}
@@ -399,6 +404,23 @@ export default function GameSettings({
                                         → {translate("game_settings.linux.manage_runners")}
                                     </button>
                                 </div>
+                                <ModernToggle
+                                    label={translate("game_settings.linux.runner_auto_upgrade.label")}
+                                    description={translate("game_settings.linux.runner_auto_upgrade.description")}
+                                    checked={installSettings.runner_auto_upgrade}
+                                    onChange={(val) => handleUpdate("runner_auto_upgrade", val)}
+                                />
+                                {installSettings.previous_runner_version && (
+                                    <button
+                                        onClick={async () => {
+                                            await invoke("rollback_install_runner_version", { id: installSettings.id });
+                                            fetchInstallSettings(installSettings.id);
+                                            pushInstalls();
+                                        }}
+                                        className="flex items-center gap-2 self-start text-purple-400 hover:text-purple-300 text-sm font-medium transition-colors px-1">
+                                        <RotateCcw className="w-4 h-4" /> {translate("game_settings.linux.runner_rollback", { runner_version: installSettings.previous_runner_version })}
+                                    </button>
+                                )}
                                 <ModernPathInput
                                     label={translate("game_settings.linux.runner_location.label")}
                                     description={translate("game_settings.linux.runner_location.description")}
@@ -439,6 +461,20 @@ export default function GameSettings({
                                     extensions={["conf"]}
                                     onChange={(val) => handleUpdate("mangohud_config_path", val)}
                                 />
+                                <ModernToggle
+                                    label={translate("game_settings.linux.proton_log.label")}
+                                    description={translate("game_settings.linux.proton_log.description")}
+                                    checked={!!installSettings.proton_log}
+                                    onChange={(val) => handleUpdate("proton_log", val)}
+                                />
+                                <ModernInput
+                                    label={translate("game_settings.linux.wine_debug.label")}
+                                    description={translate("game_settings.linux.wine_debug.description")}
+                                    value={installSettings.wine_debug || ""}
+                                    onChange={(e) => handleUpdate("wine_debug", e.target.value)}
+                                    placeholder="+loaddll,-fixme"
+                                />
+                                <PrefixSnapshotsEditor install={installSettings} onRestored={() => { fetchInstallSettings(installSettings.id); pushInstalls(); }} />
                             </div>
                         </SettingsSection>
                         </>
@@ -570,6 +606,39 @@ export default function GameSettings({
                                             </span>
                                         </div>
                                     </button>
+                                    <button
+                                        onClick={() => {
+                                            if (installSettings.steam_imported) return;
+                                            setOpenPopup(POPUPS.NONE);
+                                            emit("start_game_verify", {
+                                                install: installSettings.id,
+                                                biz: installSettings.manifest_id,
+                                                lang: "en-us",
+                                                region: installSettings.region_code
+                                            });
+                                        }}
+                                        disabled={installSettings.steam_imported}
+                                        className={`flex items-center gap-3 p-4 rounded-xl border border-white/5 transition-colors text-white text-left ${installSettings.steam_imported ? "cursor-not-allowed bg-zinc-900/85 opacity-70" : "bg-zinc-900/85 hover:bg-zinc-900 hover:border-white/10"}`}>
+                                        <ShieldCheck className={`w-6 h-6 ${installSettings.steam_imported ? "text-zinc-500" : "text-emerald-400"}`}/>
+                                        <div className="flex flex-col">
+                                            <span className="font-bold">{translate("game_settings.manage.verify_game")}</span>
+                                            <span className={`text-xs ${installSettings.steam_imported ? "text-yellow-300 font-medium" : "text-zinc-400"}`}>
+                                                {installSettings.steam_imported ? translate("game_settings.manage.steam_managed") : translate("game_settings.manage.verify_game.description")}
+                                            </span>
+                                        </div>
+                                    </button>
+                                    <button
+                                        onClick={async () => {
+                                            const path = await save({ defaultPath: `${installSettings.name}.json`, filters: [{ name: "Install bundle", extensions: ["json"] }] });
+                                            if (path) invoke("export_install_bundle", { id: installSettings.id, path, includeGameDirectory: true });
+                                        }}
+                                        className="flex items-center gap-3 p-4 rounded-xl border border-white/5 transition-colors text-white text-left bg-zinc-900/85 hover:bg-zinc-900 hover:border-white/10">
+                                        <Share className="w-6 h-6 text-sky-400"/>
+                                        <div className="flex flex-col">
+                                            <span className="font-bold">{translate("game_settings.manage.export_install")}</span>
+                                            <span className="text-xs text-zinc-400">{translate("game_settings.manage.export_install.description")}</span>
+                                        </div>
+                                    </button>
 
                                     {installSettings.shortcut_is_steam ? (
                                         <button
@@ -682,6 +751,24 @@ export default function GameSettings({
                                             </div>
                                         </button>
                                     )}
+
+                                    <button
+                                        onClick={() => {
+                                            setOpenPopup(POPUPS.NONE);
+                                            invoke("open_folder", {
+                                                runnerVersion: "",
+                                                manifestId: installSettings.manifest_id,
+                                                installId: installSettings.id,
+                                                pathType: "session_log"
+                                            });
+                                        }}
+                                        className="flex items-center gap-3 p-4 bg-zinc-800/50 hover:bg-zinc-700/50 rounded-xl border border-white/5 transition-all hover:border-white/20 text-white text-left">
+                                        <Logs className="w-6 h-6 text-purple-400" />
+                                        <div className="flex flex-col">
+                                            <span className="font-bold">{translate("game_settings.manage.open_session_log")}</span>
+                                            <span className="text-xs text-zinc-400">{translate("game_settings.manage.open_session_log.description")}</span>
+                                        </div>
+                                    </button>
                                 </div>
                             </SettingsSection>
                             {window.navigator.platform.includes("Linux") && (
@@ -10,12 +10,15 @@ export const Events = [
   'repair_progress',
   'repair_installing',
   'repair_complete',
+  'verify_progress',
+  'verify_complete',
   'preload_progress',
   'preload_installing',
   'preload_complete',
   'move_progress',
   'move_complete',
   'game_closed',
+  'install_imported',
 ] as const;
 
 export type AppEvent = typeof Events[number];
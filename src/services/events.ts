@@ -2,10 +2,22 @@ import type { DownloadPhase } from '../types/downloadQueue.ts';
 
 export type EventStateUpdate = Record<string, any> | ((prev: any) => Record<string, any>);
 
-// Convert numeric phase from backend to string phase for frontend
-function parsePhase(phaseNum: string | number | undefined): DownloadPhase | undefined {
-  if (phaseNum === undefined) return undefined;
-  const num = typeof phaseNum === 'string' ? parseInt(phaseNum) : phaseNum;
+// Convert backend phase to string phase for frontend.
+// Progress events send the ProgressPhase enum name, move_progress still sends the legacy number.
+function parsePhase(phase: string | number | undefined): DownloadPhase | undefined {
+  if (phase === undefined) return undefined;
+  // One case per backend ProgressPhase
+  switch (phase) {
+    case 'idle': return 'idle';
+    case 'verify': return 'verifying';
+    case 'download': return 'downloading';
+    case 'install': return 'installing';
+    case 'validate': return 'validating';
+    case 'move': return 'moving';
+    case 'patch': return 'patching';
+    case 'extract': return 'extracting';
+  }
+  const num = typeof phase === 'string' ? parseInt(phase) : phase;
   // Phase: 0=idle, 1=verifying, 2=downloading, 3=installing, 4=validating, 5=moving
   switch (num) {
     case 0: return 'idle';
@@ -78,7 +90,8 @@ export function registerEvents(
         return result;
       };
     }
-    case 'game_closed': {
+    case 'game_closed':
+    case 'install_imported': {
       pushInstalls();
       return undefined;
     }
@@ -87,6 +100,7 @@ export function registerEvents(
     case 'download_complete':
     case 'update_complete':
     case 'repair_complete':
+    case 'verify_complete':
     case 'preload_complete': {
       pushInstalls();
 
@@ -137,7 +151,8 @@ export function registerEvents(
     }
     case 'download_progress': {
       // Use job_id if present, otherwise use name as fallback for misc downloads (proton, steamrt, etc.)
-      const jobId = event?.payload?.job_id ?? event?.payload?.jobId ?? event?.payload?.name;
+      // Extras without a queue job send an empty job_id
+      const jobId = event?.payload?.job_id || event?.payload?.jobId || event?.payload?.name;
       if (!jobId) return undefined;
       const { progress, total } = parseProgressPair(event?.payload?.progress, event?.payload?.total);
       const { progress: installProgress, total: installTotal } = parseProgressPair(event?.payload?.install_progress, event?.payload?.install_total);
@@ -153,8 +168,10 @@ export function registerEvents(
           // Include install progress if present in the same event (Sophon downloads)
           installProgress,
           installTotal,
-          // Phase: verifying, downloading, installing, validating, moving
           phase: parsePhase(event.payload.phase),
+          etaSecs: parseOptionalInt(event.payload.eta_secs),
+          filesDone: parseOptionalInt(event.payload.files_done),
+          filesTotal: parseOptionalInt(event.payload.files_total),
           eventType,
         };
         return { downloadProgressByJobId: next };
@@ -188,7 +205,7 @@ export function registerEvents(
     }
     case 'update_progress': {
       // Use job_id if present, otherwise use name as fallback for misc updates (steamrt, etc.)
-      const jobId = event?.payload?.job_id ?? event?.payload?.jobId ?? event?.payload?.name;
+      const jobId = event?.payload?.job_id || event?.payload?.jobId || event?.payload?.name;
       if (!jobId) return undefined;
       const { progress, total } = parseProgressPair(event?.payload?.progress, event?.payload?.total);
       const { progress: installProgress, total: installTotal } = parseProgressPair(event?.payload?.install_progress, event?.payload?.install_total);
@@ -204,8 +221,10 @@ export function registerEvents(
           // Include install progress if present in the same event (Sophon downloads)
           installProgress,
           installTotal,
-          // Phase: verifying, downloading, installing, validating, moving
           phase: parsePhase(event.payload.phase),
+          etaSecs: parseOptionalInt(event.payload.eta_secs),
+          filesDone: parseOptionalInt(event.payload.files_done),
+          filesTotal: parseOptionalInt(event.payload.files_total),
           eventType,
         };
         return { downloadProgressByJobId: next };
@@ -230,7 +249,8 @@ export function registerEvents(
         return { downloadProgressByJobId: next };
       };
     }
-    case 'repair_progress': {
+    case 'repair_progress':
+    case 'verify_progress': {
       const jobId = event?.payload?.job_id ?? event?.payload?.jobId;
       if (!jobId) return undefined;
       const { progress, total } = parseProgressPair(event?.payload?.progress, event?.payload?.total);
@@ -247,8 +267,10 @@ export function registerEvents(
           // Include install progress if present in the same event (Sophon downloads)
           installProgress,
           installTotal,
-          // Phase: verifying, downloading, installing, validating, moving
           phase: parsePhase(event.payload.phase),
+          etaSecs: parseOptionalInt(event.payload.eta_secs),
+          filesDone: parseOptionalInt(event.payload.files_done),
+          filesTotal: parseOptionalInt(event.payload.files_total),
           eventType,
         };
         return { downloadProgressByJobId: next };
@@ -290,8 +312,10 @@ export function registerEvents(
           // Include install progress if present in the same event (Sophon downloads)
           installProgress,
           installTotal,
-          // Phase: verifying, downloading, installing, validating, moving
           phase: parsePhase(event.payload.phase),
+          etaSecs: parseOptionalInt(event.payload.eta_secs),
+          filesDone: parseOptionalInt(event.payload.files_done),
+          filesTotal: parseOptionalInt(event.payload.files_total),
           eventType,
         };
         return { downloadProgressByJobId: next };
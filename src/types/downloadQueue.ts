@@ -1,18 +1,30 @@
-export type QueueJobKind = 'game_download' | 'game_update' | 'game_preload' | 'game_repair' | 'runner_download' | 'steamrt_download' | 'steamrt4_download' | 'xxmi_download' | 'extras_download';
+export type QueueJobKind = 'game_download' | 'game_update' | 'game_preload' | 'game_repair' | 'game_verify' | 'runner_download' | 'runner_verify' | 'steamrt_download' | 'steamrt4_download' | 'xxmi_download' | 'extras_download';
+
+export type QueueLane = 'game' | 'misc';
 
 export type QueueJobStatus = 'queued' | 'running' | 'completed' | 'failed' | 'cancelled' | 'paused';
 
 export interface QueueJobView {
   id: string;
   kind: QueueJobKind;
+  lane: QueueLane;
   installId: string;
   name: string;
   status: QueueJobStatus;
 }
 
+export interface QueueLaneState {
+  lane: QueueLane;
+  maxConcurrent: number;
+  running: number;
+}
+
 export interface DownloadQueueStatePayload {
   maxConcurrent: number;
+  lanes?: QueueLaneState[];
   paused: boolean;
+  autoPaused?: boolean;
+  nextWindowStart?: number | null;
   running: QueueJobView[];
   queued: QueueJobView[];
   completed?: QueueJobView[];
@@ -20,7 +32,7 @@ export interface DownloadQueueStatePayload {
   pausingInstalls?: string[];
 }
 
-export type DownloadPhase = 'verifying' | 'downloading' | 'installing' | 'extracting' | 'validating' | 'moving' | 'idle';
+export type DownloadPhase = 'verifying' | 'downloading' | 'installing' | 'patching' | 'extracting' | 'validating' | 'moving' | 'idle';
 
 export interface DownloadJobProgress {
   jobId: string;
@@ -32,5 +44,8 @@ export interface DownloadJobProgress {
   installProgress?: number;
   installTotal?: number;
   phase?: DownloadPhase;
+  etaSecs?: number;
+  filesDone?: number;
+  filesTotal?: number;
   eventType: string;
 }